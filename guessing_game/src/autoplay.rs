@@ -0,0 +1,174 @@
+// Lets the strategies play many games on their own and compares them.
+//
+// Every strategy plays against the same list of secrets (drawn from the seed),
+// so the numbers are a fair comparison and the same seed always gives the same report.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::game::GameSettings;
+use crate::host::{FairHost, Host};
+use crate::i18n::Catalog;
+use crate::strategy::Strategy;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkReport {
+    pub strategy: String,
+    pub games: u32,
+    pub mean: f64,
+    pub best: u32,
+    pub worst: u32,
+    // Number of attempts -> how many games needed exactly that many.
+    pub histogram: BTreeMap<u32, u32>,
+}
+
+impl BenchmarkReport {
+    // Builds the report from the number of attempts of every game.
+    pub fn from_attempts(
+        strategy: &str,
+        attempts: impl IntoIterator<Item = u32>,
+    ) -> BenchmarkReport {
+        let mut histogram = BTreeMap::new();
+        let mut games = 0;
+        let mut total = 0u64;
+        for attempts in attempts {
+            games += 1;
+            total += attempts as u64;
+            *histogram.entry(attempts).or_insert(0) += 1;
+        }
+
+        BenchmarkReport {
+            strategy: strategy.to_string(),
+            games,
+            mean: if games == 0 {
+                0.0
+            } else {
+                total as f64 / games as f64
+            },
+            best: histogram.keys().next().copied().unwrap_or(0),
+            worst: histogram.keys().next_back().copied().unwrap_or(0),
+            histogram,
+        }
+    }
+}
+
+// Plays a single game and returns how many guesses the strategy needed.
+// There is no attempt limit here: every strategy in this crate only guesses numbers
+// that are still possible, so it always finds the secret within the size of the range.
+pub fn autoplay_game(strategy: &mut dyn Strategy, low: u32, high: u32, secret: u32) -> u32 {
+    autoplay_against(strategy, &mut FairHost::new(secret), low, high)
+}
+
+// Same as autoplay_game, but against any host, e.g. an EvilHost.
+pub fn autoplay_against(
+    strategy: &mut dyn Strategy,
+    host: &mut dyn Host,
+    low: u32,
+    high: u32,
+) -> u32 {
+    strategy.start(low, high);
+
+    let mut attempts = 0;
+    loop {
+        let guess = strategy.next_guess();
+        attempts += 1;
+
+        match host.judge(guess) {
+            Ordering::Equal => return attempts,
+            ordering => strategy.feedback(guess, ordering),
+        }
+    }
+}
+
+pub fn run_benchmark(
+    strategy: &mut dyn Strategy,
+    settings: &GameSettings,
+    games: u32,
+    seed: u64,
+) -> BenchmarkReport {
+    let mut secrets = StdRng::seed_from_u64(seed);
+    let mut attempts = Vec::new();
+    for _ in 0..games {
+        let secret = secrets.gen_range(settings.low()..=settings.high());
+        attempts.push(autoplay_game(
+            strategy,
+            settings.low(),
+            settings.high(),
+            secret,
+        ));
+    }
+    BenchmarkReport::from_attempts(strategy.name(), attempts)
+}
+
+// The smallest number of guesses that always finds a secret among `size` numbers
+// when every answer halves what is left: ceil(log2(size + 1)).
+pub fn optimal_worst_case(size: u32) -> u32 {
+    let mut guesses = 0;
+    let mut covered: u64 = 0;
+    while covered < size as u64 {
+        guesses += 1;
+        covered = (1u64 << guesses) - 1;
+    }
+    guesses
+}
+
+const HISTOGRAM_WIDTH: u32 = 40;
+
+// Ranges with many different attempt counts (e.g. linear on 1-1000)
+// are grouped into at most this many rows.
+const HISTOGRAM_ROWS: u32 = 20;
+
+pub fn render_report(report: &BenchmarkReport, catalog: &Catalog) -> String {
+    let mean = format!("{:.2}", report.mean);
+    let mut out = catalog.format(
+        "autoplay_report",
+        &[
+            ("strategy", &report.strategy),
+            ("games", &report.games),
+            ("mean", &mean),
+            ("best", &report.best),
+            ("worst", &report.worst),
+        ],
+    );
+    out.push('\n');
+
+    let span = report.worst - report.best + 1;
+    let bucket = span.div_ceil(HISTOGRAM_ROWS).max(1);
+
+    let mut rows: BTreeMap<u32, u32> = BTreeMap::new();
+    for (attempts, count) in &report.histogram {
+        let start = report.best + (attempts - report.best) / bucket * bucket;
+        *rows.entry(start).or_insert(0) += count;
+    }
+
+    let tallest = rows.values().copied().max().unwrap_or(1);
+    for (start, count) in rows {
+        let label = if bucket == 1 {
+            format!("{start}")
+        } else {
+            format!("{}-{}", start, (start + bucket - 1).min(report.worst))
+        };
+        let bar = "#".repeat((count * HISTOGRAM_WIDTH).div_ceil(tallest) as usize);
+        out.push_str(&format!("  {label:>9} | {bar} {count}\n"));
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn optimal_worst_case_examples() {
+        assert_eq!(optimal_worst_case(1), 1);
+        assert_eq!(optimal_worst_case(3), 2);
+        assert_eq!(optimal_worst_case(100), 7);
+        assert_eq!(optimal_worst_case(127), 7);
+        assert_eq!(optimal_worst_case(128), 8);
+        assert_eq!(optimal_worst_case(1000), 10);
+    }
+}
@@ -0,0 +1,91 @@
+// Joins a room on a multiplayer server. Run it with:
+// $ cargo run --bin client -- --addr 127.0.0.1:7878 --room lobby --name ada
+use std::env;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::process;
+use std::thread;
+
+use guessing_game::config::USAGE;
+use guessing_game::leaderboard;
+use guessing_game::net::{ClientMessage, ServerMessage};
+use guessing_game::{Catalog, Config};
+
+fn main() {
+    let config = Config::build(env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("Problem parsing arguments: {err}");
+        eprintln!("{USAGE}");
+        process::exit(2);
+    });
+
+    if config.help {
+        println!("{USAGE}");
+        return;
+    }
+
+    if let Err(e) = run(config) {
+        eprintln!("Connection problem: {e}");
+        process::exit(1);
+    }
+}
+
+fn run(config: Config) -> io::Result<()> {
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let catalog = Catalog::for_locale(&config.lang);
+
+    let name = match config.name {
+        Some(name) => name,
+        None => leaderboard::ask_player_name(&mut input, &mut io::stdout(), &catalog)?,
+    };
+    // The protocol separates words with spaces.
+    let name = name.split_whitespace().collect::<Vec<_>>().join("_");
+
+    let mut stream = TcpStream::connect(&config.addr)?;
+    let reader = BufReader::new(stream.try_clone()?);
+
+    writeln!(
+        stream,
+        "{}",
+        ClientMessage::Join {
+            room: config.room,
+            name,
+            lang: Some(config.lang),
+        }
+    )?;
+
+    // Everything the server says is printed by a second thread,
+    // so other players' guesses show up while we wait for our own input.
+    // It also ends the client if the server goes away while we wait for input.
+    let printer = {
+        let catalog = catalog.clone();
+        thread::spawn(move || {
+            for line in reader.lines() {
+                let Ok(line) = line else { break };
+                match ServerMessage::parse(&line) {
+                    Ok(message) => println!("{}", message.describe(&catalog)),
+                    Err(_) => println!("{line}"),
+                }
+            }
+            println!("{}", catalog.text("room_disconnected"));
+            process::exit(0);
+        })
+    };
+
+    println!("{}", catalog.text("room_prompt"));
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().eq_ignore_ascii_case("quit") {
+            break;
+        }
+        writeln!(stream, "{}", ClientMessage::Guess(line.trim().to_string()))?;
+    }
+
+    // Leaving (or reaching the end of the input) says goodbye to the room.
+    // The server then closes the connection, which lets the printer thread
+    // show whatever was still on its way before the program ends.
+    writeln!(stream, "{}", ClientMessage::Quit)?;
+    let _ = printer.join();
+
+    Ok(())
+}
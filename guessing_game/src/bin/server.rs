@@ -0,0 +1,33 @@
+// Hosts multiplayer rooms. Run it with:
+// $ cargo run --bin server -- --addr 0.0.0.0:7878 --difficulty hard
+use std::env;
+use std::process;
+
+use guessing_game::Config;
+use guessing_game::config::USAGE;
+use guessing_game::net::Server;
+
+fn main() {
+    let config = Config::build(env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("Problem parsing arguments: {err}");
+        eprintln!("{USAGE}");
+        process::exit(2);
+    });
+
+    if config.help {
+        println!("{USAGE}");
+        return;
+    }
+
+    let server = Server::bind(&config.addr, config.settings, config.seed).unwrap_or_else(|err| {
+        eprintln!("Could not listen on {}: {err}", config.addr);
+        process::exit(1);
+    });
+
+    match server.local_addr() {
+        Ok(addr) => println!("Guessing game server listening on {addr}"),
+        Err(_) => println!("Guessing game server listening on {}", config.addr),
+    }
+
+    server.run();
+}
@@ -0,0 +1,512 @@
+// Bulls and Cows, and its cousin Mastermind: a code-breaking game next to the number game.
+//
+// The secret is a code of a few digits. Every guess is a code of the same length and is
+// answered with bulls (right digit in the right place) and cows (right digit in the wrong place).
+// Bulls and Cows uses 4 different digits out of 0-9; Mastermind uses 4 digits out of 0-5
+// that may repeat (the six colours of the board game).
+//
+// The game runs on the same loop as the number game (engine.rs), so input, validation,
+// attempt counting and win detection behave the same way.
+//
+// MinimaxSolver plays on its own with Knuth's minimax rule: always guess the code whose
+// worst possible answer leaves the fewest candidates. It never needs more than 5 guesses
+// for Mastermind and 7 for Bulls and Cows.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::autoplay::BenchmarkReport;
+use crate::engine::{self, Puzzle};
+use crate::game::GameResult;
+use crate::i18n::Catalog;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Code(Vec<u8>);
+
+impl Code {
+    pub fn digits(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Display for Code {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for digit in &self.0 {
+            write!(f, "{digit}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Score {
+    pub bulls: u8,
+    pub cows: u8,
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let plural = |n: u8| if n == 1 { "" } else { "s" };
+        write!(
+            f,
+            "{} bull{}, {} cow{}",
+            self.bulls,
+            plural(self.bulls),
+            self.cows,
+            plural(self.cows)
+        )
+    }
+}
+
+// Cows are counted per digit, so with repeats a digit is never counted more often
+// than it appears in both codes.
+pub fn score(secret: &Code, guess: &Code) -> Score {
+    let bulls = secret
+        .0
+        .iter()
+        .zip(&guess.0)
+        .filter(|(s, g)| s == g)
+        .count() as u8;
+
+    let mut in_secret = [0u8; 10];
+    let mut in_guess = [0u8; 10];
+    for (s, g) in secret.0.iter().zip(&guess.0) {
+        in_secret[*s as usize] += 1;
+        in_guess[*g as usize] += 1;
+    }
+    let common: u8 = in_secret
+        .iter()
+        .zip(&in_guess)
+        .map(|(s, g)| *s.min(g))
+        .sum();
+
+    Score {
+        bulls,
+        cows: common - bulls,
+    }
+}
+
+// The rules of one code game. Like GameSettings, it can only be built through new, which checks them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CodeRules {
+    length: usize,
+    symbols: u8,
+    repeats: bool,
+    max_attempts: Option<u32>,
+}
+
+// Long codes would make the solver (and the list of every possible code) far too big.
+const MAX_LENGTH: usize = 6;
+const DEFAULT_MAX_ATTEMPTS: u32 = 10;
+
+impl CodeRules {
+    pub fn new(length: usize, symbols: u8, repeats: bool) -> Result<CodeRules, String> {
+        if !(1..=MAX_LENGTH).contains(&length) {
+            return Err(format!(
+                "a code must have between 1 and {MAX_LENGTH} digits, got {length}"
+            ));
+        }
+        if !(2..=10).contains(&symbols) {
+            return Err(format!(
+                "a code uses between 2 and 10 different digits, got {symbols}"
+            ));
+        }
+        if !repeats && length > symbols as usize {
+            return Err(format!(
+                "{length} different digits can't be picked from only {symbols}"
+            ));
+        }
+
+        Ok(CodeRules {
+            length,
+            symbols,
+            repeats,
+            max_attempts: Some(DEFAULT_MAX_ATTEMPTS),
+        })
+    }
+
+    pub fn bulls_and_cows() -> CodeRules {
+        CodeRules::new(4, 10, false).expect("the classic rules are valid")
+    }
+
+    pub fn mastermind() -> CodeRules {
+        CodeRules::new(4, 6, true).expect("the classic rules are valid")
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: Option<u32>) -> CodeRules {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    pub fn symbols(&self) -> u8 {
+        self.symbols
+    }
+
+    pub fn repeats(&self) -> bool {
+        self.repeats
+    }
+
+    pub fn max_attempts(&self) -> Option<u32> {
+        self.max_attempts
+    }
+
+    // The most guesses MinimaxSolver needs for the classic rules, if we know it.
+    pub fn known_worst_case(&self) -> Option<u32> {
+        if *self == CodeRules::mastermind().with_max_attempts(self.max_attempts) {
+            Some(5)
+        } else if *self == CodeRules::bulls_and_cows().with_max_attempts(self.max_attempts) {
+            Some(7)
+        } else {
+            None
+        }
+    }
+
+    // Every code allowed by the rules, in increasing order.
+    pub fn all_codes(&self) -> Vec<Code> {
+        let mut codes = Vec::new();
+        let mut digits = vec![0u8; self.length];
+        loop {
+            if self.repeats || all_different(&digits) {
+                codes.push(Code(digits.clone()));
+            }
+
+            // Count up like an odometer in base `symbols`.
+            let mut position = self.length;
+            loop {
+                if position == 0 {
+                    return codes;
+                }
+                position -= 1;
+                digits[position] += 1;
+                if digits[position] < self.symbols {
+                    break;
+                }
+                digits[position] = 0;
+            }
+        }
+    }
+
+    pub fn draw_secret<R: Rng>(&self, rng: &mut R) -> Code {
+        let codes = self.all_codes();
+        codes[rng.gen_range(0..codes.len())].clone()
+    }
+
+    pub fn parse(&self, line: &str) -> Result<Code, CodeError> {
+        let mut digits = Vec::new();
+        for c in line.chars() {
+            match c.to_digit(10) {
+                Some(d) if d < self.symbols as u32 => digits.push(d as u8),
+                _ => return Err(CodeError::InvalidDigit),
+            }
+        }
+
+        if digits.len() != self.length {
+            return Err(CodeError::WrongLength(digits.len()));
+        }
+        if !self.repeats && !all_different(&digits) {
+            return Err(CodeError::RepeatedDigit);
+        }
+        Ok(Code(digits))
+    }
+}
+
+// Why a line is not a valid code. The player sees it through the message catalog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodeError {
+    InvalidDigit,
+    // How many digits were entered.
+    WrongLength(usize),
+    RepeatedDigit,
+}
+
+fn all_different(digits: &[u8]) -> bool {
+    digits
+        .iter()
+        .enumerate()
+        .all(|(i, d)| !digits[..i].contains(d))
+}
+
+// Everything we know about a finished code game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeOutcome {
+    pub secret: Code,
+    pub guesses: Vec<Code>,
+    pub scores: Vec<Score>,
+    pub result: GameResult,
+}
+
+impl CodeOutcome {
+    pub fn attempts(&self) -> u32 {
+        self.guesses.len() as u32
+    }
+}
+
+struct CodePuzzle<'a> {
+    rules: CodeRules,
+    secret: Code,
+    catalog: &'a Catalog,
+}
+
+impl Puzzle for CodePuzzle<'_> {
+    type Guess = Code;
+    type Answer = Score;
+
+    fn read_guess(&self, line: &str) -> Result<Code, String> {
+        let length = self.rules.length;
+        self.rules.parse(line).map_err(|e| match e {
+            CodeError::InvalidDigit => self.catalog.format(
+                "code_invalid_digits",
+                &[("length", &length), ("highest", &(self.rules.symbols - 1))],
+            ),
+            CodeError::WrongLength(entered) => self.catalog.format(
+                "code_wrong_length",
+                &[("length", &length), ("entered", &entered)],
+            ),
+            CodeError::RepeatedDigit => self.catalog.text("code_repeated_digits"),
+        })
+    }
+
+    fn judge(&mut self, guess: &Code) -> Score {
+        score(&self.secret, guess)
+    }
+
+    fn is_win(&self, answer: &Score) -> bool {
+        answer.bulls as usize == self.rules.length
+    }
+
+    fn describe(&self, answer: &Score) -> String {
+        self.catalog.format(
+            "code_score",
+            &[("bulls", &answer.bulls), ("cows", &answer.cows)],
+        )
+    }
+
+    fn lose_message(&self) -> String {
+        self.catalog
+            .format("code_lose", &[("secret", &self.secret)])
+    }
+}
+
+// Plays one code game, the way GameSession::play plays a number game.
+pub fn play<R: Rng, I: BufRead, W: Write>(
+    rules: CodeRules,
+    rng: &mut R,
+    mut input: I,
+    mut output: W,
+    catalog: &Catalog,
+) -> io::Result<CodeOutcome> {
+    let secret = rules.draw_secret(rng);
+
+    writeln!(output, "{}", catalog.text("code_title"))?;
+    let key = if rules.repeats {
+        "code_rules"
+    } else {
+        "code_rules_different"
+    };
+    writeln!(
+        output,
+        "{}",
+        catalog.format(
+            key,
+            &[("length", &rules.length), ("highest", &(rules.symbols - 1))]
+        )
+    )?;
+    writeln!(output, "{}", catalog.text("code_explain"))?;
+    if let Some(max) = rules.max_attempts {
+        writeln!(output, "{}", catalog.format("attempts", &[("max", &max)]))?;
+    }
+
+    let mut puzzle = CodePuzzle {
+        rules,
+        secret,
+        catalog,
+    };
+    let rounds = engine::play_rounds(
+        &mut puzzle,
+        rules.max_attempts,
+        None,
+        &mut input,
+        &mut output,
+        catalog,
+    )?;
+
+    Ok(CodeOutcome {
+        secret: puzzle.secret,
+        guesses: rounds.guesses,
+        scores: rounds.answers,
+        result: rounds.result,
+    })
+}
+
+// Knuth's minimax solver. It only depends on the answers it got so far, so the guess
+// for every sequence of answers is remembered and many games can be solved quickly.
+pub struct MinimaxSolver {
+    rules: CodeRules,
+    codes: Vec<Code>,
+    // answers[a * codes.len() + b] is the score of b against a, as an index (see answer_index).
+    // Scoring is by far the most frequent step, so every score is worked out once up front.
+    answers: Vec<u8>,
+    memo: HashMap<Vec<u8>, usize>,
+}
+
+// A Score packed into one number, used to index the tables of the solver.
+fn answer_index(score: Score) -> u8 {
+    score.bulls * (MAX_LENGTH as u8 + 1) + score.cows
+}
+
+impl MinimaxSolver {
+    pub fn new(rules: CodeRules) -> MinimaxSolver {
+        let codes = rules.all_codes();
+        let n = codes.len();
+        let mut answers = vec![0; n * n];
+        // The score is symmetric, so half of the table is enough to work out.
+        for a in 0..n {
+            for b in a..n {
+                let answer = answer_index(score(&codes[a], &codes[b]));
+                answers[a * n + b] = answer;
+                answers[b * n + a] = answer;
+            }
+        }
+
+        MinimaxSolver {
+            rules,
+            codes,
+            answers,
+            memo: HashMap::new(),
+        }
+    }
+
+    fn answer(&self, a: usize, b: usize) -> u8 {
+        self.answers[a * self.codes.len() + b]
+    }
+
+    // Plays against `secret` and returns every guess, the last one being the secret.
+    pub fn solve(&mut self, secret: &Code) -> Vec<Code> {
+        let solved = answer_index(Score {
+            bulls: self.rules.length as u8,
+            cows: 0,
+        });
+        let secret = self
+            .codes
+            .binary_search_by(|c| c.0.cmp(&secret.0))
+            .expect("the secret follows the rules of the solver");
+        let mut candidates: Vec<usize> = (0..self.codes.len()).collect();
+        let mut answers = Vec::new();
+        let mut guesses = Vec::new();
+
+        loop {
+            let guess = match self.memo.get(&answers) {
+                Some(&guess) => guess,
+                None => {
+                    let guess = self.minimax(&candidates);
+                    self.memo.insert(answers.clone(), guess);
+                    guess
+                }
+            };
+            guesses.push(self.codes[guess].clone());
+            let answer = self.answer(secret, guess);
+            if answer == solved {
+                return guesses;
+            }
+
+            candidates.retain(|&c| self.answer(c, guess) == answer);
+            answers.push(answer);
+        }
+    }
+
+    // The guess whose most common answer leaves the fewest candidates.
+    // Ties go to a guess that could still be the secret, then to the smallest code.
+    fn minimax(&self, candidates: &[usize]) -> usize {
+        if let [only] = candidates {
+            return *only;
+        }
+
+        let n = self.codes.len();
+        let mut best = (usize::MAX, true, 0);
+        for guess in 0..n {
+            let row = &self.answers[guess * n..(guess + 1) * n];
+            // One counter per possible answer.
+            let mut partitions = [0usize; (MAX_LENGTH + 1) * (MAX_LENGTH + 1)];
+            for &c in candidates {
+                partitions[row[c] as usize] += 1;
+            }
+            let worst = partitions.iter().copied().max().unwrap_or(0);
+            let impossible = candidates.binary_search(&guess).is_err();
+            if (worst, impossible) < (best.0, best.1) {
+                best = (worst, impossible, guess);
+            }
+        }
+        best.2
+    }
+}
+
+// Lets the solver break `games` codes drawn from the seed, like autoplay::run_benchmark.
+pub fn run_benchmark(rules: CodeRules, games: u32, seed: u64) -> BenchmarkReport {
+    let mut secrets = StdRng::seed_from_u64(seed);
+    let mut solver = MinimaxSolver::new(rules);
+    let attempts = (0..games).map(|_| {
+        let secret = rules.draw_secret(&mut secrets);
+        solver.solve(&secret).len() as u32
+    });
+    BenchmarkReport::from_attempts("minimax", attempts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(digits: &str) -> Code {
+        Code(digits.bytes().map(|b| b - b'0').collect())
+    }
+
+    #[test]
+    fn scores_bulls_and_cows() {
+        assert_eq!(
+            score(&code("1234"), &code("1243")),
+            Score { bulls: 2, cows: 2 }
+        );
+        assert_eq!(
+            score(&code("1234"), &code("5678")),
+            Score { bulls: 0, cows: 0 }
+        );
+        // The secret has one 1, so the second 1 of the guess earns nothing.
+        assert_eq!(
+            score(&code("1234"), &code("1155")),
+            Score { bulls: 1, cows: 0 }
+        );
+    }
+
+    #[test]
+    fn counts_every_code() {
+        assert_eq!(CodeRules::bulls_and_cows().all_codes().len(), 5040);
+        assert_eq!(CodeRules::mastermind().all_codes().len(), 1296);
+    }
+
+    #[test]
+    fn validates_guesses() {
+        let rules = CodeRules::bulls_and_cows();
+
+        assert_eq!(rules.parse("0123"), Ok(code("0123")));
+        assert!(rules.parse("012").is_err());
+        assert!(rules.parse("0113").is_err());
+        assert!(rules.parse("01a3").is_err());
+        assert!(CodeRules::mastermind().parse("0116").is_err());
+        assert_eq!(CodeRules::mastermind().parse("0115"), Ok(code("0115")));
+    }
+
+    #[test]
+    fn rejects_impossible_rules() {
+        assert!(CodeRules::new(0, 10, false).is_err());
+        assert!(CodeRules::new(4, 11, false).is_err());
+        assert!(CodeRules::new(5, 4, false).is_err());
+        assert!(CodeRules::new(5, 4, true).is_ok());
+    }
+}
@@ -0,0 +1,562 @@
+// Command-line options, parsed by hand like the Config of chapter 12's minigrep.
+//
+// guessing_game [play|stats|autoplay|replay FILE|verify FILE] [--difficulty easy|normal|hard] [--range LOW-HIGH] [--max-attempts N]
+//               [--seed N] [--reveal] [--evil] [--name NAME] [--results FILE]
+//               [--games N] [--strategy binary|random|linear|all]
+//               [--addr HOST:PORT] [--room NAME] [--record FILE] [--pace step|recorded|instant]
+//               [--hints LIST] [--hint-budget N] [--mode number|daily|bulls|mastermind] [--lang en|it]
+//               [--time-limit SECONDS] [--json]
+//
+// The language comes from --lang, or else from the LANG environment variable, like
+// IGNORE_CASE in minigrep. Languages without a translation fall back to English.
+//
+// --range and --max-attempts override the values taken from the difficulty preset.
+
+use std::env;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::code::CodeRules;
+use crate::daily;
+use crate::game::GameSettings;
+use crate::hint::HintKind;
+use crate::host::HostKind;
+use crate::i18n::{self, DEFAULT_LOCALE};
+use crate::leaderboard::DEFAULT_RESULTS_FILE;
+use crate::net::DEFAULT_ADDR;
+use crate::record::Pace;
+use crate::strategy::StrategyKind;
+
+pub const USAGE: &str = "\
+Usage: guessing_game [COMMAND] [OPTIONS]
+
+Commands:
+  play                             Play a game (default)
+  stats                            Show per-player statistics and the top 10
+  autoplay                         Let the computer strategies play and compare them
+  replay <FILE>                    Play back a recorded game
+  verify <FILE>                    Check that a recorded game is consistent
+
+Options:
+  --difficulty <easy|normal|hard>  Preset range and attempt limit (default: normal)
+  --range <LOW-HIGH>               Custom range for the secret, e.g. 1-500
+  --max-attempts <N>               Number of guesses before you lose
+  --seed <N>                       Seed the random number generator for a reproducible game
+  --reveal                         Debug: print the secret number before the first guess
+  --evil                           Play against a host that never fixes the secret up front
+  --name <NAME>                    Player name (asked before the game if missing)
+  --results <FILE>                 Results file (default: guessing_game_results.json)
+  --games <N>                      autoplay: games per strategy (default: 1000)
+  --strategy <NAME>                autoplay: binary, random, linear or all (default: all)
+  --addr <HOST:PORT>               server/client: address to listen on or connect to (default: 127.0.0.1:7878)
+  --room <NAME>                    client: room to join (default: lobby)
+  --record <FILE>                  Write an event log of the game to FILE
+  --pace <step|recorded|instant>   replay: one step per Enter, the original speed, or all at once (default: step)
+  --hints <LIST>                   Hints on offer, e.g. parity,within-10 (warmer-colder, parity, digit-sum, within-N or all)
+  --hint-budget <N>                Hint points per game (default: 5); type \"hint\" during the game to see the menu
+  --mode <MODE>                    number (default), daily (the same number for everybody, once a day),
+                                   or break a code: bulls (Bulls and Cows) or mastermind
+  --time-limit <SECONDS>           Timed mode: seconds for each guess; a late turn counts as a miss
+  --lang <en|it>                   Language of the game (default: from LANG, else en)
+  --json                           Read {\"guess\": N} and write one JSON object per line, for programs
+  --help                           Print this message";
+
+// The presets. Each one gives exactly enough attempts to always win with a
+// perfect binary search (ceil(log2(size of the range + 1))), plus some slack on easy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn parse(name: &str) -> Result<Difficulty, String> {
+        match name.to_lowercase().as_str() {
+            "easy" => Ok(Difficulty::Easy),
+            "normal" => Ok(Difficulty::Normal),
+            "hard" => Ok(Difficulty::Hard),
+            other => Err(format!(
+                "unknown difficulty '{other}', expected easy, normal or hard"
+            )),
+        }
+    }
+
+    pub fn range(&self) -> (u32, u32) {
+        match self {
+            Difficulty::Easy => (1, 50),
+            Difficulty::Normal => (1, 100),
+            Difficulty::Hard => (1, 1000),
+        }
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        match self {
+            Difficulty::Easy => 10,
+            Difficulty::Normal => 7,
+            Difficulty::Hard => 10,
+        }
+    }
+}
+
+// Which game is played. The code games have their own rules and attempt limit,
+// so --difficulty and --range only apply to the number game.
+// Daily is the number game with the rules and the secret of the day (see daily.rs).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Number,
+    Daily,
+    Code(CodeRules),
+}
+
+impl Mode {
+    pub fn parse(name: &str) -> Result<Mode, String> {
+        match name.to_lowercase().as_str() {
+            "number" => Ok(Mode::Number),
+            "daily" => Ok(Mode::Daily),
+            "bulls" | "bulls-and-cows" => Ok(Mode::Code(CodeRules::bulls_and_cows())),
+            "mastermind" => Ok(Mode::Code(CodeRules::mastermind())),
+            other => Err(format!(
+                "unknown mode '{other}', expected number, daily, bulls or mastermind"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Play,
+    Stats,
+    Autoplay,
+    Replay,
+    Verify,
+}
+
+impl Command {
+    fn parse(name: &str) -> Option<Command> {
+        match name {
+            "play" => Some(Command::Play),
+            "stats" => Some(Command::Stats),
+            "autoplay" => Some(Command::Autoplay),
+            "replay" => Some(Command::Replay),
+            "verify" => Some(Command::Verify),
+            _ => None,
+        }
+    }
+}
+
+const DEFAULT_AUTOPLAY_GAMES: u32 = 1000;
+const DEFAULT_ROOM: &str = "lobby";
+const DEFAULT_HINT_BUDGET: u32 = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub command: Command,
+    pub mode: Mode,
+    pub settings: GameSettings,
+    pub seed: Option<u64>,
+    pub name: Option<String>,
+    pub results: PathBuf,
+    pub games: u32,
+    pub strategies: Vec<StrategyKind>,
+    pub addr: String,
+    pub room: String,
+    // --record for play, or the recording given to replay and verify.
+    pub record: Option<PathBuf>,
+    pub pace: Pace,
+    // No hints unless they are asked for, so the classic game stays the same.
+    pub hints: Vec<HintKind>,
+    pub hint_budget: u32,
+    // A language code like "it".
+    pub lang: String,
+    // Play through the JSON-lines protocol of json.rs instead of text.
+    pub json: bool,
+    pub help: bool,
+}
+
+impl Config {
+    // Takes the arguments without the program name (i.e. env::args().skip(1)).
+    pub fn build(args: impl Iterator<Item = String>) -> Result<Config, String> {
+        let mut args = args.peekable();
+
+        // The subcommand, if there is one, comes first.
+        let command = match args.peek().and_then(|a| Command::parse(a)) {
+            Some(command) => {
+                args.next();
+                command
+            }
+            None => Command::Play,
+        };
+
+        let mut mode = Mode::Number;
+        let mut difficulty = Difficulty::Normal;
+        let mut range = None;
+        let mut max_attempts = None;
+        let mut seed = None;
+        let mut reveal = false;
+        let mut host = HostKind::Fair;
+        let mut name = None;
+        let mut results = PathBuf::from(DEFAULT_RESULTS_FILE);
+        let mut games = DEFAULT_AUTOPLAY_GAMES;
+        let mut strategies = StrategyKind::ALL.to_vec();
+        let mut addr = String::from(DEFAULT_ADDR);
+        let mut room = String::from(DEFAULT_ROOM);
+        let mut record = None;
+        let mut pace = Pace::Step;
+        let mut hints = Vec::new();
+        let mut hint_budget = None;
+        let mut lang = None;
+        let mut time_limit = None;
+        let mut json = false;
+        let mut help = false;
+
+        // replay and verify take the recording as their first argument.
+        if matches!(command, Command::Replay | Command::Verify) {
+            match args.next_if(|a| !a.starts_with("--")) {
+                Some(file) => record = Some(PathBuf::from(file)),
+                None => return Err(String::from("replay and verify need a recording file")),
+            }
+        }
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--mode" => mode = Mode::parse(&value_of(&arg, args.next())?)?,
+                "--difficulty" => difficulty = Difficulty::parse(&value_of(&arg, args.next())?)?,
+                "--range" => range = Some(parse_range(&value_of(&arg, args.next())?)?),
+                "--max-attempts" => max_attempts = Some(parse_number::<u32>(&arg, args.next())?),
+                "--seed" => seed = Some(parse_number::<u64>(&arg, args.next())?),
+                "--reveal" => reveal = true,
+                "--evil" => host = HostKind::Evil,
+                "--name" => name = Some(value_of(&arg, args.next())?),
+                "--results" => results = PathBuf::from(value_of(&arg, args.next())?),
+                "--games" => games = parse_number::<u32>(&arg, args.next())?,
+                "--strategy" => strategies = parse_strategies(&value_of(&arg, args.next())?)?,
+                "--addr" => addr = value_of(&arg, args.next())?,
+                "--room" => room = value_of(&arg, args.next())?,
+                "--record" => record = Some(PathBuf::from(value_of(&arg, args.next())?)),
+                "--pace" => pace = Pace::parse(&value_of(&arg, args.next())?)?,
+                "--hints" => hints = HintKind::parse_list(&value_of(&arg, args.next())?)?,
+                "--hint-budget" => hint_budget = Some(parse_number::<u32>(&arg, args.next())?),
+                "--time-limit" => time_limit = Some(parse_seconds(&value_of(&arg, args.next())?)?),
+                "--lang" => lang = Some(parse_lang(&value_of(&arg, args.next())?)?),
+                "--json" => json = true,
+                "--help" | "-h" => help = true,
+                other => return Err(format!("unknown option '{other}'")),
+            }
+        }
+
+        if let Mode::Code(rules) = mode {
+            // The code games have their own rules: nothing of the number game applies.
+            if command == Command::Play
+                && (record.is_some()
+                    || !hints.is_empty()
+                    || hint_budget.is_some()
+                    || time_limit.is_some()
+                    || host != HostKind::Fair
+                    || reveal
+                    || difficulty != Difficulty::Normal
+                    || range.is_some())
+            {
+                return Err(String::from(
+                    "--record, --hints, --hint-budget, --time-limit, --evil, --reveal, --difficulty and --range only work with the number game",
+                ));
+            }
+            // An explicit --max-attempts replaces the 10 attempts of the code games.
+            if max_attempts.is_some() {
+                mode = Mode::Code(rules.with_max_attempts(max_attempts));
+            }
+        }
+
+        // A program can't ask for hints, and the other modes don't speak JSON.
+        if json
+            && command == Command::Play
+            && (mode != Mode::Number || !hints.is_empty() || hint_budget.is_some())
+        {
+            return Err(String::from(
+                "--json only works with the number game, without --hints or --hint-budget",
+            ));
+        }
+
+        // The daily game is the same for everybody, so nothing that changes it is allowed.
+        let daily = mode == Mode::Daily && command == Command::Play;
+        if daily
+            && (difficulty != Difficulty::Normal
+                || range.is_some()
+                || max_attempts.is_some()
+                || seed.is_some()
+                || reveal
+                || host != HostKind::Fair
+                || !hints.is_empty()
+                || hint_budget.is_some()
+                || time_limit.is_some())
+        {
+            return Err(String::from(
+                "the daily challenge has fixed rules: leave out --difficulty, --range, --max-attempts, --seed, --reveal, --evil, --hints, --hint-budget and --time-limit",
+            ));
+        }
+
+        let settings = if daily {
+            daily::settings()
+        } else {
+            let (low, high) = range.unwrap_or(difficulty.range());
+            let max_attempts = max_attempts.unwrap_or(difficulty.max_attempts());
+            GameSettings::new(low, high, Some(max_attempts))?
+                .with_reveal(reveal)
+                .with_host(host)
+                .with_time_limit(time_limit)
+        };
+
+        // A budget on its own means every hint is on offer.
+        if hint_budget.is_some() && hints.is_empty() {
+            hints = HintKind::ALL.to_vec();
+        }
+        let hint_budget = hint_budget.unwrap_or(DEFAULT_HINT_BUDGET);
+
+        let lang = lang
+            .or_else(|| env::var("LANG").ok().map(|l| i18n::language_of(&l)))
+            .unwrap_or_else(|| String::from(DEFAULT_LOCALE));
+
+        Ok(Config {
+            command,
+            mode,
+            settings,
+            seed,
+            name,
+            results,
+            games,
+            strategies,
+            addr,
+            room,
+            record,
+            pace,
+            hints,
+            hint_budget,
+            lang,
+            json,
+            help,
+        })
+    }
+}
+
+fn value_of(option: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or_else(|| format!("{option} needs a value"))
+}
+
+fn parse_number<T: std::str::FromStr>(option: &str, value: Option<String>) -> Result<T, String> {
+    let value = value_of(option, value)?;
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("{option} expects a non-negative number, got '{value}'"))
+}
+
+// "2.5" -> 2.5 seconds
+fn parse_seconds(value: &str) -> Result<Duration, String> {
+    match value.trim().parse::<f64>() {
+        Ok(seconds) if seconds > 0.0 && seconds.is_finite() => Ok(Duration::from_secs_f64(seconds)),
+        _ => Err(format!(
+            "--time-limit expects a positive number of seconds, got '{value}'"
+        )),
+    }
+}
+
+fn parse_lang(value: &str) -> Result<String, String> {
+    let lang = i18n::language_of(value);
+    if i18n::is_available(&lang) {
+        Ok(lang)
+    } else {
+        Err(format!("no translation for '{value}', expected en or it"))
+    }
+}
+
+fn parse_strategies(value: &str) -> Result<Vec<StrategyKind>, String> {
+    if value.eq_ignore_ascii_case("all") {
+        Ok(StrategyKind::ALL.to_vec())
+    } else {
+        Ok(vec![StrategyKind::parse(value)?])
+    }
+}
+
+// "1-500" -> (1, 500)
+fn parse_range(value: &str) -> Result<(u32, u32), String> {
+    let (low, high) = value
+        .split_once('-')
+        .ok_or_else(|| format!("a range looks like LOW-HIGH, got '{value}'"))?;
+
+    let low = low
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid range start '{low}'"))?;
+    let high = high
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid range end '{high}'"))?;
+
+    Ok((low, high))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(args: &[&str]) -> Result<Config, String> {
+        Config::build(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn defaults_to_normal() {
+        let config = build(&[]).unwrap();
+
+        assert_eq!(config.settings.low(), 1);
+        assert_eq!(config.settings.high(), 100);
+        assert_eq!(config.settings.max_attempts(), Some(7));
+        assert!(!config.settings.reveal());
+        assert_eq!(config.seed, None);
+        assert_eq!(config.command, Command::Play);
+        assert_eq!(config.results, PathBuf::from(DEFAULT_RESULTS_FILE));
+    }
+
+    #[test]
+    fn stats_subcommand() {
+        let config = build(&["stats", "--results", "other.json"]).unwrap();
+
+        assert_eq!(config.command, Command::Stats);
+        assert_eq!(config.results, PathBuf::from("other.json"));
+    }
+
+    #[test]
+    fn autoplay_options() {
+        let config = build(&["autoplay", "--games", "50", "--strategy", "linear"]).unwrap();
+
+        assert_eq!(config.command, Command::Autoplay);
+        assert_eq!(config.games, 50);
+        assert_eq!(config.strategies, vec![StrategyKind::Linear]);
+        assert_eq!(build(&["autoplay"]).unwrap().strategies.len(), 3);
+    }
+
+    #[test]
+    fn custom_range_overrides_the_preset() {
+        let config = build(&["--difficulty", "hard", "--range", "10-20", "--seed", "5"]).unwrap();
+
+        assert_eq!((config.settings.low(), config.settings.high()), (10, 20));
+        assert_eq!(config.settings.max_attempts(), Some(10));
+        assert_eq!(config.seed, Some(5));
+    }
+
+    #[test]
+    fn replay_takes_a_file() {
+        let config = build(&["replay", "game.jsonl", "--pace", "instant"]).unwrap();
+
+        assert_eq!(config.command, Command::Replay);
+        assert_eq!(config.record, Some(PathBuf::from("game.jsonl")));
+        assert_eq!(config.pace, Pace::Instant);
+        assert!(build(&["verify"]).is_err());
+        assert!(build(&["verify", "--pace", "step"]).is_err());
+    }
+
+    #[test]
+    fn hints_are_opt_in() {
+        assert!(build(&[]).unwrap().hints.is_empty());
+
+        let config = build(&["--hints", "parity,within-5"]).unwrap();
+        assert_eq!(config.hints, vec![HintKind::Parity, HintKind::Within(5)]);
+        assert_eq!(config.hint_budget, DEFAULT_HINT_BUDGET);
+
+        let config = build(&["--hint-budget", "3"]).unwrap();
+        assert_eq!(config.hints, HintKind::ALL.to_vec());
+        assert_eq!(config.hint_budget, 3);
+    }
+
+    #[test]
+    fn code_modes() {
+        assert_eq!(build(&[]).unwrap().mode, Mode::Number);
+
+        let config = build(&["--mode", "bulls"]).unwrap();
+        assert_eq!(config.mode, Mode::Code(CodeRules::bulls_and_cows()));
+
+        let config = build(&["--mode", "mastermind", "--max-attempts", "6"]).unwrap();
+        assert_eq!(
+            config.mode,
+            Mode::Code(CodeRules::mastermind().with_max_attempts(Some(6)))
+        );
+        assert!(build(&["--mode", "bulls", "--hints", "all"]).is_err());
+    }
+
+    #[test]
+    fn code_modes_refuse_the_number_game_options() {
+        for option in [
+            &["--record", "game.jsonl"][..],
+            &["--hints", "all"],
+            &["--hint-budget", "3"],
+            &["--time-limit", "10"],
+            &["--evil"],
+            &["--reveal"],
+            &["--difficulty", "hard"],
+            &["--range", "1-10"],
+        ] {
+            for mode in ["bulls", "mastermind"] {
+                let args = [&["--mode", mode][..], option].concat();
+                let err = build(&args).unwrap_err();
+                assert!(err.contains("only work with the number game"), "{args:?}");
+            }
+        }
+        // Autoplay of the codes doesn't play a game with those rules.
+        assert!(build(&["autoplay", "--mode", "bulls", "--evil"]).is_ok());
+    }
+
+    #[test]
+    fn daily_mode_has_fixed_rules() {
+        let config = build(&["--mode", "daily", "--name", "ada"]).unwrap();
+        assert_eq!(config.mode, Mode::Daily);
+        assert_eq!(config.settings, daily::settings());
+
+        assert!(build(&["--mode", "daily", "--seed", "1"]).is_err());
+        assert!(build(&["--mode", "daily", "--difficulty", "easy"]).is_err());
+        assert!(build(&["--mode", "daily", "--evil"]).is_err());
+        assert!(build(&["--mode", "daily", "--hint-budget", "3"]).is_err());
+        // The rules only matter when a daily game is played.
+        assert!(build(&["stats", "--mode", "daily", "--seed", "1"]).is_ok());
+    }
+
+    #[test]
+    fn json_flag() {
+        assert!(!build(&[]).unwrap().json);
+        assert!(build(&["--json", "--name", "bot"]).unwrap().json);
+        assert!(build(&["--json", "--mode", "mastermind"]).is_err());
+        assert!(build(&["--json", "--hints", "all"]).is_err());
+    }
+
+    #[test]
+    fn time_limit() {
+        assert_eq!(build(&[]).unwrap().settings.time_limit(), None);
+        assert_eq!(
+            build(&["--time-limit", "2.5"])
+                .unwrap()
+                .settings
+                .time_limit(),
+            Some(Duration::from_millis(2500))
+        );
+        assert!(build(&["--time-limit", "0"]).is_err());
+        assert!(build(&["--time-limit", "soon"]).is_err());
+    }
+
+    #[test]
+    fn lang_flag() {
+        assert_eq!(build(&["--lang", "it"]).unwrap().lang, "it");
+        assert_eq!(build(&["--lang", "it_IT.UTF-8"]).unwrap().lang, "it");
+        assert!(build(&["--lang", "tlh"]).is_err());
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert!(build(&["--difficulty", "impossible"]).is_err());
+        assert!(build(&["--range", "50-10"]).is_err());
+        assert!(build(&["--range", "fifty"]).is_err());
+        assert!(build(&["--max-attempts", "0"]).is_err());
+        assert!(build(&["--seed"]).is_err());
+        assert!(build(&["--cheat"]).is_err());
+        assert!(build(&["autoplay", "--strategy", "psychic"]).is_err());
+        assert!(build(&["--hints", "horoscope"]).is_err());
+        assert!(build(&["--mode", "chess"]).is_err());
+    }
+}
@@ -0,0 +1,234 @@
+// The daily challenge: one secret per day, the same for everybody.
+//
+// The seed comes from the date, so everyone who plays on the same day gets the same number
+// without any server. The day changes at midnight UTC, the same moment all over the world,
+// which also spares us a time zone database.
+//
+// Everybody plays with the same rules too (the normal difficulty, no hints, no clock),
+// and each player gets one scored game a day: the results file remembers who played which day.
+// After the game there is a line to share that tells how it went without giving the number away:
+//
+//   Guess the number 2026-10-17 4/7
+//   ⬆️⬇️⬆️✅
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::Difficulty;
+use crate::game::{GameOutcome, GameResult, GameSettings};
+use crate::i18n::Catalog;
+use crate::leaderboard::{GameRecord, Leaderboard};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+// Mixed into the seed so the daily numbers don't line up with the games of --seed 0, 1, 2...
+const SALT: u64 = 0x6775_6573_735f_6e75;
+
+// A day, counted from 1970-01-01.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    days: u64,
+}
+
+impl Date {
+    pub fn today() -> Date {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Date::from_days(seconds / SECONDS_PER_DAY)
+    }
+
+    pub fn from_days(days: u64) -> Date {
+        Date { days }
+    }
+
+    // (year, month, day), with the algorithm from Howard Hinnant's "chrono-Compatible
+    // Low-Level Date Algorithms". It works in 400-year eras that start on March 1st,
+    // so the leap day is the last day of its year.
+    pub fn year_month_day(&self) -> (u64, u64, u64) {
+        let z = self.days + 719_468;
+        let era = z / 146_097;
+        let day_of_era = z % 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        // Months counted from March.
+        let month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month + 2) / 5 + 1;
+        let month = if month < 10 { month + 3 } else { month - 9 };
+        let year = year_of_era + era * 400 + u64::from(month <= 2);
+        (year, month, day)
+    }
+}
+
+// 2026-10-17
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (year, month, day) = self.year_month_day();
+        write!(f, "{year:04}-{month:02}-{day:02}")
+    }
+}
+
+pub fn seed(date: Date) -> u64 {
+    date.days.wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ SALT
+}
+
+// The same game for everybody: the normal difficulty with the fair host.
+pub fn settings() -> GameSettings {
+    let (low, high) = Difficulty::Normal.range();
+    GameSettings::new(low, high, Some(Difficulty::Normal.max_attempts()))
+        .expect("the normal difficulty is valid")
+}
+
+// The player's daily game of that date, if there is one in the results.
+pub fn played_on<'a>(board: &'a Leaderboard, player: &str, date: Date) -> Option<&'a GameRecord> {
+    let date = date.to_string();
+    board
+        .records()
+        .iter()
+        .find(|record| record.player == player && record.daily.as_deref() == Some(date.as_str()))
+}
+
+// The result line and one arrow per guess: up for "Too small!", down for "Too big!".
+pub fn share_text(
+    date: Date,
+    outcome: &GameOutcome,
+    settings: &GameSettings,
+    catalog: &Catalog,
+) -> String {
+    let attempts = match outcome.result {
+        GameResult::Won => outcome.attempts.to_string(),
+        GameResult::Lost | GameResult::Abandoned => String::from("X"),
+    };
+    let max = match settings.max_attempts() {
+        Some(max) => max.to_string(),
+        None => String::from("∞"),
+    };
+
+    let trail: String = outcome
+        .feedback
+        .iter()
+        .map(|answer| match answer {
+            Ordering::Less => "⬆️",
+            Ordering::Greater => "⬇️",
+            Ordering::Equal => "✅",
+        })
+        .collect();
+
+    let line = catalog.format(
+        "daily_share_line",
+        &[
+            ("date", &date as &dyn fmt::Display),
+            ("attempts", &attempts),
+            ("max", &max),
+        ],
+    );
+    format!("{line}\n{trail}")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::game::GameSession;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn outcome(feedback: Vec<Ordering>, result: GameResult) -> GameOutcome {
+        GameOutcome {
+            secret: 50,
+            attempts: feedback.len() as u32,
+            guesses: Vec::new(),
+            feedback,
+            misses: 0,
+            guess_times: Vec::new(),
+            result,
+            duration: Duration::ZERO,
+            hint_points: 0,
+            time_limit: None,
+        }
+    }
+
+    fn daily_record(player: &str, date: Date) -> GameRecord {
+        GameRecord {
+            player: player.to_string(),
+            low: 1,
+            high: 100,
+            attempts: 3,
+            duration_ms: 0,
+            result: GameResult::Won,
+            finished_at: 0,
+            hint_points: 0,
+            score: 800,
+            guess_times_ms: Vec::new(),
+            daily: Some(date.to_string()),
+        }
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(Date::from_days(0).to_string(), "1970-01-01");
+        assert_eq!(Date::from_days(59).to_string(), "1970-03-01");
+        assert_eq!(Date::from_days(19_723).to_string(), "2024-01-01");
+        assert_eq!(Date::from_days(19_782).to_string(), "2024-02-29");
+        assert_eq!(Date::from_days(20_743).to_string(), "2026-10-17");
+        assert_eq!(Date::from_days(11_016).to_string(), "2000-02-29");
+    }
+
+    #[test]
+    fn same_day_same_secret() {
+        let today = Date::from_days(20_743);
+        let secret = |date: Date| {
+            let script: &[u8] = b"";
+            let mut output = Vec::new();
+            GameSession::with_settings(
+                settings(),
+                StdRng::seed_from_u64(seed(date)),
+                script,
+                &mut output,
+            )
+            .play()
+            .unwrap()
+            .secret
+        };
+
+        assert_eq!(secret(today), secret(today));
+        assert_ne!(seed(today), seed(Date::from_days(20_744)));
+    }
+
+    #[test]
+    fn share_text_has_the_trail() {
+        let date = Date::from_days(20_743);
+        let catalog = Catalog::english();
+
+        let won = outcome(
+            vec![Ordering::Less, Ordering::Greater, Ordering::Equal],
+            GameResult::Won,
+        );
+        assert_eq!(
+            share_text(date, &won, &settings(), &catalog),
+            "Guess the number 2026-10-17 3/7\n⬆️⬇️✅"
+        );
+
+        let lost = outcome(vec![Ordering::Less; 7], GameResult::Lost);
+        assert!(
+            share_text(date, &lost, &settings(), &catalog)
+                .starts_with("Guess the number 2026-10-17 X/7\n")
+        );
+    }
+
+    #[test]
+    fn one_game_a_day() {
+        let today = Date::from_days(20_743);
+        let mut board = Leaderboard::new();
+        board.record(daily_record("ada", Date::from_days(20_742)));
+        assert!(played_on(&board, "ada", today).is_none());
+
+        board.record(daily_record("ada", today));
+        assert!(played_on(&board, "ada", today).is_some());
+        assert!(played_on(&board, "bob", today).is_none());
+    }
+}
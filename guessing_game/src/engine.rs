@@ -0,0 +1,210 @@
+// The loop every game in this crate shares: prompt, read a line, validate it,
+// judge it, check for a win and count the attempts.
+//
+// What a guess looks like and how it is answered is up to a Puzzle:
+// the number game in game.rs answers "Too small!" / "Too big!",
+// Bulls and Cows in code.rs answers with bulls and cows.
+//
+// With a time limit every turn has a deadline. Invalid lines don't stop the clock,
+// and a turn that runs out of time is a miss: it costs an attempt without a guess.
+
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use crate::game::GameResult;
+use crate::i18n::Catalog;
+use crate::input::{Line, LineSource};
+
+pub trait Puzzle {
+    type Guess;
+    type Answer;
+
+    // Turns a line into a guess, or returns the message that tells the player what is wrong.
+    // Rejected lines are not attempts.
+    fn read_guess(&self, line: &str) -> Result<Self::Guess, String>;
+
+    fn judge(&mut self, guess: &Self::Guess) -> Self::Answer;
+
+    fn is_win(&self, answer: &Self::Answer) -> bool;
+
+    // What the player is told after a guess that did not win, e.g. "Too small!".
+    fn describe(&self, answer: &Self::Answer) -> String;
+
+    // Shown when the last attempt is used up, e.g. "You lose! The secret number was 42."
+    fn lose_message(&self) -> String;
+
+    // The hooks below do nothing unless a puzzle needs them.
+
+    // A line the puzzle answers itself instead of treating it as a guess, e.g. "hint".
+    // Some(reply) prints the reply and asks for the next line.
+    fn intercept(&mut self, _line: &str, _guesses: &[Self::Guess]) -> io::Result<Option<String>> {
+        Ok(None)
+    }
+
+    // Every line as it was typed, without the line ending.
+    fn on_input(&mut self, _line: &str) -> io::Result<()> {
+        Ok(())
+    }
+
+    // The game is about to wait for attempt number `attempt`, counting from 1.
+    fn on_prompt(&mut self, _attempt: u32) -> io::Result<()> {
+        Ok(())
+    }
+
+    // A line that was not a guess, and the message that says why.
+    fn on_invalid(&mut self, _line: &str, _reason: &str) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn on_answer(&mut self, _guess: &Self::Guess, _answer: &Self::Answer) -> io::Result<()> {
+        Ok(())
+    }
+
+    // A turn whose deadline passed before a valid guess came in.
+    fn on_timeout(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    // The guess in words, shown after the line when it is echoed: "You guessed: 42 (forty-two)".
+    fn spell_out(&self, _line: &str) -> Option<String> {
+        None
+    }
+}
+
+// The guesses that counted and the answers they got, in order.
+// times has one entry per attempt, misses included: how long the player took for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rounds<G, A> {
+    pub guesses: Vec<G>,
+    pub answers: Vec<A>,
+    pub misses: u32,
+    pub times: Vec<Duration>,
+    pub result: GameResult,
+}
+
+impl<G, A> Rounds<G, A> {
+    pub fn attempts(&self) -> u32 {
+        self.guesses.len() as u32 + self.misses
+    }
+}
+
+// Plays until the puzzle is solved, the attempts run out or the input ends.
+// Errors only come from reading the input or writing the output.
+// The catalog has the messages of the loop itself; the puzzle brings its own.
+pub fn play_rounds<P: Puzzle, I: LineSource, W: Write>(
+    puzzle: &mut P,
+    max_attempts: Option<u32>,
+    time_limit: Option<Duration>,
+    input: &mut I,
+    output: &mut W,
+    catalog: &Catalog,
+) -> io::Result<Rounds<P::Guess, P::Answer>> {
+    let mut rounds = Rounds {
+        guesses: Vec::new(),
+        answers: Vec::new(),
+        misses: 0,
+        times: Vec::new(),
+        result: GameResult::Abandoned,
+    };
+    let mut turn_started = Instant::now();
+
+    loop {
+        writeln!(output, "{}", catalog.text("prompt"))?;
+        puzzle.on_prompt(rounds.attempts() + 1)?;
+
+        let timeout = time_limit.map(|limit| limit.saturating_sub(turn_started.elapsed()));
+        let line = match input.next_line(timeout)? {
+            Line::Text(line) => line,
+            // The original loop would spin forever at the end of the input,
+            // so we stop the game instead.
+            Line::End => break,
+            Line::TimedOut => {
+                writeln!(output, "{}", catalog.text("timed_out"))?;
+                puzzle.on_timeout()?;
+                rounds.misses += 1;
+                rounds.times.push(turn_started.elapsed());
+                turn_started = Instant::now();
+                if out_of_attempts(puzzle, &rounds, max_attempts, output, catalog)? {
+                    rounds.result = GameResult::Lost;
+                    break;
+                }
+                continue;
+            }
+        };
+
+        puzzle.on_input(line.trim_end_matches(['\r', '\n']))?;
+
+        if let Some(reply) = puzzle.intercept(&line, &rounds.guesses)? {
+            writeln!(output, "{reply}")?;
+            continue;
+        }
+
+        let echo = match puzzle.spell_out(line.trim()) {
+            Some(words) => line.replacen(line.trim(), &format!("{} ({words})", line.trim()), 1),
+            None => line.clone(),
+        };
+        writeln!(
+            output,
+            "{}",
+            catalog.format("you_guessed", &[("line", &echo)])
+        )?;
+
+        let guess = match puzzle.read_guess(line.trim()) {
+            Ok(guess) => guess,
+            Err(reason) => {
+                writeln!(output, "{reason}")?;
+                puzzle.on_invalid(line.trim_end_matches(['\r', '\n']), &reason)?;
+                continue;
+            }
+        };
+
+        let answer = puzzle.judge(&guess);
+        puzzle.on_answer(&guess, &answer)?;
+        rounds.times.push(turn_started.elapsed());
+        turn_started = Instant::now();
+        let won = puzzle.is_win(&answer);
+        rounds.guesses.push(guess);
+
+        if won {
+            rounds.answers.push(answer);
+            writeln!(output, "{}", catalog.text("you_win"))?;
+            rounds.result = GameResult::Won;
+            break;
+        }
+        writeln!(output, "{}", puzzle.describe(&answer))?;
+        rounds.answers.push(answer);
+
+        if out_of_attempts(puzzle, &rounds, max_attempts, output, catalog)? {
+            rounds.result = GameResult::Lost;
+            break;
+        }
+    }
+
+    Ok(rounds)
+}
+
+// After an attempt that did not win: either the game is lost, or the player
+// is told how many attempts are left.
+fn out_of_attempts<P: Puzzle, W: Write>(
+    puzzle: &P,
+    rounds: &Rounds<P::Guess, P::Answer>,
+    max_attempts: Option<u32>,
+    output: &mut W,
+    catalog: &Catalog,
+) -> io::Result<bool> {
+    let Some(max) = max_attempts else {
+        return Ok(false);
+    };
+
+    let used = rounds.attempts();
+    if used >= max {
+        writeln!(output, "{}", puzzle.lose_message())?;
+        return Ok(true);
+    }
+    writeln!(
+        output,
+        "{}",
+        catalog.format("attempts_left", &[("left", &(max - used))])
+    )?;
+    Ok(false)
+}
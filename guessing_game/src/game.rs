@@ -0,0 +1,580 @@
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{self, Puzzle, Rounds};
+use crate::hint::{HintBook, HintContext};
+use crate::host::{EvilHost, FairHost, Host, HostKind};
+use crate::i18n::Catalog;
+use crate::input::LineSource;
+use crate::json::{self, JsonMessage, JsonReporter};
+use crate::numeral;
+use crate::record::{GameEvent, Recorder};
+
+// How a session ended.
+// Won: the player found the secret number.
+// Lost: the player used every allowed attempt without finding it.
+// Abandoned: the input ran out (e.g. Ctrl-D or the end of a scripted test) before the game was decided.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GameResult {
+    Won,
+    Lost,
+    Abandoned,
+}
+
+// Everything we know about a finished game.
+// Invalid lines ("Please enter a valid number.") and out-of-range numbers
+// are not counted as attempts and are not stored in guesses.
+// feedback[i] is the answer the host gave to guesses[i].
+// duration is the wall-clock time from the first prompt to the end of the game.
+// hint_points is how much of the hint budget was spent (see hint.rs).
+// In a timed game a turn can also run out of time: misses counts those turns,
+// which are attempts without a guess. guess_times has how long every attempt took,
+// misses included, so attempts == guess_times.len().
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameOutcome {
+    pub secret: u32,
+    pub attempts: u32,
+    pub guesses: Vec<u32>,
+    pub feedback: Vec<Ordering>,
+    pub misses: u32,
+    pub guess_times: Vec<Duration>,
+    pub result: GameResult,
+    pub duration: Duration,
+    pub hint_points: u32,
+    pub time_limit: Option<Duration>,
+}
+
+impl GameOutcome {
+    fn new(
+        host: &dyn Host,
+        rounds: Rounds<u32, Ordering>,
+        started: Instant,
+        hint_points: u32,
+        time_limit: Option<Duration>,
+    ) -> GameOutcome {
+        GameOutcome {
+            secret: host.reveal(),
+            attempts: rounds.attempts(),
+            guesses: rounds.guesses,
+            feedback: rounds.answers,
+            misses: rounds.misses,
+            guess_times: rounds.times,
+            result: rounds.result,
+            duration: started.elapsed(),
+            hint_points,
+            time_limit,
+        }
+    }
+
+    // 1000 for a win at the first attempt, 100 less for every further attempt
+    // and 25 less for every hint point spent. A game that was not won scores 0.
+    // Against the clock, every whole second spent on the guesses costs 10 more.
+    pub fn score(&self) -> u32 {
+        if self.result != GameResult::Won {
+            return 0;
+        }
+        let mut penalty = 100 * (self.attempts - 1) + 25 * self.hint_points;
+        if self.time_limit.is_some() {
+            let seconds: Duration = self.guess_times.iter().sum();
+            penalty += 10 * seconds.as_secs() as u32;
+        }
+        1000u32.saturating_sub(penalty)
+    }
+
+    // True if every answer the host gave is what a fixed secret would have given.
+    // Always true for a fair host, and what keeps the evil host honest.
+    pub fn is_consistent(&self) -> bool {
+        self.guesses.len() == self.feedback.len()
+            && self
+                .guesses
+                .iter()
+                .zip(&self.feedback)
+                .all(|(guess, answer)| guess.cmp(&self.secret) == *answer)
+    }
+}
+
+// The rules of one game: the inclusive range the secret is drawn from,
+// how many attempts the player gets (None means unlimited)
+// whether the secret is printed before the first guess, which is only meant for debugging,
+// which host answers the guesses, and for the timed mode how long each turn may take.
+// The fields are private so a GameSettings can only be built through new, which checks them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameSettings {
+    low: u32,
+    high: u32,
+    max_attempts: Option<u32>,
+    reveal: bool,
+    host: HostKind,
+    time_limit: Option<Duration>,
+}
+
+impl GameSettings {
+    pub fn new(low: u32, high: u32, max_attempts: Option<u32>) -> Result<GameSettings, String> {
+        if low >= high {
+            return Err(format!(
+                "the range must contain at least two numbers, got {low}-{high}"
+            ));
+        }
+        if max_attempts == Some(0) {
+            return Err(String::from(
+                "the maximum number of attempts must be at least 1",
+            ));
+        }
+
+        Ok(GameSettings {
+            low,
+            high,
+            max_attempts,
+            reveal: false,
+            host: HostKind::Fair,
+            time_limit: None,
+        })
+    }
+
+    pub fn with_reveal(mut self, reveal: bool) -> GameSettings {
+        self.reveal = reveal;
+        self
+    }
+
+    pub fn with_host(mut self, host: HostKind) -> GameSettings {
+        self.host = host;
+        self
+    }
+
+    pub fn with_time_limit(mut self, time_limit: Option<Duration>) -> GameSettings {
+        self.time_limit = time_limit;
+        self
+    }
+
+    pub fn low(&self) -> u32 {
+        self.low
+    }
+
+    pub fn high(&self) -> u32 {
+        self.high
+    }
+
+    pub fn max_attempts(&self) -> Option<u32> {
+        self.max_attempts
+    }
+
+    pub fn reveal(&self) -> bool {
+        self.reveal
+    }
+
+    pub fn host(&self) -> HostKind {
+        self.host
+    }
+
+    pub fn time_limit(&self) -> Option<Duration> {
+        self.time_limit
+    }
+
+    pub fn contains(&self, guess: u32) -> bool {
+        (self.low..=self.high).contains(&guess)
+    }
+}
+
+// The original game: 1 to 100, as many guesses as you like.
+impl Default for GameSettings {
+    fn default() -> GameSettings {
+        GameSettings {
+            low: 1,
+            high: 100,
+            max_attempts: None,
+            reveal: false,
+            host: HostKind::Fair,
+            time_limit: None,
+        }
+    }
+}
+
+// How a fair game picks its secret. It is the first thing drawn from the rng,
+// so a recorded seed is enough to find the secret again (see record::verify).
+pub fn draw_secret<R: Rng>(rng: &mut R, settings: &GameSettings) -> u32 {
+    rng.gen_range(settings.low..=settings.high)
+}
+
+// A single game of "Guess the number!".
+// The session is generic over where the randomness comes from and where the
+// input and output go, so main.rs can pass a StdRng, stdin and stdout while
+// tests pass a seeded StdRng, a byte slice and a Vec<u8>.
+// The input is any LineSource; a timed game needs one that can time out (input::TimedInput).
+// An optional Recorder writes every step of the game to an event log,
+// an optional JsonReporter tells a program what happens (see json.rs),
+// and an optional HintBook lets the player buy hints.
+// Everything the player reads comes from the catalog, English unless told otherwise.
+pub struct GameSession<R, I, W> {
+    settings: GameSettings,
+    rng: R,
+    input: I,
+    output: W,
+    recorder: Option<Recorder>,
+    json: Option<JsonReporter>,
+    hints: HintBook,
+    catalog: Catalog,
+}
+
+impl<R: Rng, I: LineSource, W: Write> GameSession<R, I, W> {
+    pub fn new(rng: R, input: I, output: W) -> GameSession<R, I, W> {
+        GameSession::with_settings(GameSettings::default(), rng, input, output)
+    }
+
+    pub fn with_settings(
+        settings: GameSettings,
+        rng: R,
+        input: I,
+        output: W,
+    ) -> GameSession<R, I, W> {
+        GameSession {
+            settings,
+            rng,
+            input,
+            output,
+            recorder: None,
+            json: None,
+            hints: HintBook::default(),
+            catalog: Catalog::english(),
+        }
+    }
+
+    pub fn record_to(mut self, recorder: Recorder) -> GameSession<R, I, W> {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    pub fn report_to(mut self, reporter: JsonReporter) -> GameSession<R, I, W> {
+        self.json = Some(reporter);
+        self
+    }
+
+    pub fn with_hints(mut self, hints: HintBook) -> GameSession<R, I, W> {
+        self.hints = hints;
+        self
+    }
+
+    pub fn with_catalog(mut self, catalog: Catalog) -> GameSession<R, I, W> {
+        self.catalog = catalog;
+        self
+    }
+
+    fn record(&mut self, event: GameEvent) -> io::Result<()> {
+        match self.recorder.as_mut() {
+            Some(recorder) => recorder.log(event),
+            None => Ok(()),
+        }
+    }
+
+    fn send(&mut self, message: JsonMessage) -> io::Result<()> {
+        match self.json.as_mut() {
+            Some(reporter) => reporter.send(&message),
+            None => Ok(()),
+        }
+    }
+
+    fn finish(
+        &mut self,
+        host: &dyn Host,
+        rounds: Rounds<u32, Ordering>,
+        started: Instant,
+    ) -> io::Result<GameOutcome> {
+        let result = rounds.result;
+        let outcome = GameOutcome::new(
+            host,
+            rounds,
+            started,
+            self.hints.spent(),
+            self.settings.time_limit,
+        );
+        if result == GameResult::Won {
+            let score = self.catalog.format("score", &[("score", &outcome.score())]);
+            writeln!(self.output, "{score}")?;
+        }
+        self.record(GameEvent::End {
+            result,
+            secret: outcome.secret,
+            attempts: outcome.attempts,
+        })?;
+        self.send(JsonMessage::GameOver {
+            result,
+            secret: outcome.secret,
+            attempts: outcome.attempts,
+            score: outcome.score(),
+        })?;
+        Ok(outcome)
+    }
+
+    // Plays one full game and returns its outcome.
+    // Errors only come from reading the input or writing the output;
+    // bad guesses are part of the game and are reported to the player.
+    pub fn play(&mut self) -> io::Result<GameOutcome> {
+        let GameSettings { low, high, .. } = self.settings;
+        let started = Instant::now();
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.start(&self.settings)?;
+        }
+        self.send(JsonMessage::Start {
+            low,
+            high,
+            max_attempts: self.settings.max_attempts,
+        })?;
+
+        let catalog = &self.catalog;
+        writeln!(self.output, "{}", catalog.text("title"))?;
+        writeln!(
+            self.output,
+            "{}",
+            catalog.format("range", &[("low", &low), ("high", &high)])
+        )?;
+        if let Some(max) = self.settings.max_attempts {
+            writeln!(
+                self.output,
+                "{}",
+                catalog.format("attempts", &[("max", &max)])
+            )?;
+        }
+        self.hints.reset();
+        if !self.hints.is_empty() {
+            writeln!(
+                self.output,
+                "{}",
+                catalog.format("hint_intro", &[("points", &self.hints.budget())])
+            )?;
+        }
+        if let Some(limit) = self.settings.time_limit {
+            writeln!(
+                self.output,
+                "{}",
+                catalog.format("time_limit", &[("seconds", &limit.as_secs_f64())])
+            )?;
+        }
+
+        // The evil host has no secret to reveal: it decides as late as possible.
+        let host: Box<dyn Host> = match self.settings.host {
+            HostKind::Fair => {
+                let secret_number = draw_secret(&mut self.rng, &self.settings);
+                if self.settings.reveal {
+                    writeln!(
+                        self.output,
+                        "{}",
+                        catalog.format("reveal_secret", &[("secret", &secret_number)])
+                    )?;
+                }
+                Box::new(FairHost::new(secret_number))
+            }
+            HostKind::Evil => Box::new(EvilHost::new(low, high)),
+        };
+
+        let mut puzzle = NumberPuzzle {
+            settings: self.settings,
+            host,
+            hints: &mut self.hints,
+            recorder: self.recorder.as_mut(),
+            json: self.json.as_mut(),
+            catalog,
+        };
+        let rounds = engine::play_rounds(
+            &mut puzzle,
+            self.settings.max_attempts,
+            self.settings.time_limit,
+            &mut self.input,
+            &mut self.output,
+            catalog,
+        )?;
+
+        let host = puzzle.host;
+        self.finish(host.as_ref(), rounds, started)
+    }
+}
+
+// The rules of the number game for the shared game loop in engine.rs.
+// It borrows the session's hints, recorder and JSON reporter for the length of one game.
+struct NumberPuzzle<'a> {
+    settings: GameSettings,
+    host: Box<dyn Host>,
+    hints: &'a mut HintBook,
+    recorder: Option<&'a mut Recorder>,
+    json: Option<&'a mut JsonReporter>,
+    catalog: &'a Catalog,
+}
+
+impl NumberPuzzle<'_> {
+    fn record(&mut self, event: GameEvent) -> io::Result<()> {
+        match self.recorder.as_mut() {
+            Some(recorder) => recorder.log(event),
+            None => Ok(()),
+        }
+    }
+
+    fn send(&mut self, message: JsonMessage) -> io::Result<()> {
+        match self.json.as_mut() {
+            Some(reporter) => reporter.send(&message),
+            None => Ok(()),
+        }
+    }
+
+    // Answers "hint" with the menu and "hint <name>" with the hint, if the budget allows it.
+    fn give_hint(&mut self, name: &str, guesses: &[u32]) -> io::Result<String> {
+        // The evil host has no secret yet, so any hint would tie its hands.
+        if self.settings.host == HostKind::Evil {
+            return Ok(self.catalog.text("hint_evil_host"));
+        }
+        if name.is_empty() {
+            return Ok(self.hints.menu(self.catalog));
+        }
+
+        let context = HintContext {
+            secret: self.host.reveal(),
+            low: self.settings.low,
+            high: self.settings.high,
+            guesses,
+            catalog: self.catalog,
+        };
+        let before = self.hints.spent();
+        match self.hints.request(name, &context) {
+            Ok(text) => {
+                let cost = self.hints.spent() - before;
+                self.record(GameEvent::Hint {
+                    name: name.to_lowercase(),
+                    cost,
+                    text: text.clone(),
+                })?;
+                Ok(self.catalog.format(
+                    "hint_given",
+                    &[
+                        ("text", &text as &dyn Display),
+                        ("points", &self.hints.remaining()),
+                    ],
+                ))
+            }
+            Err(reason) => {
+                self.record(GameEvent::Invalid {
+                    reason: reason.clone(),
+                })?;
+                Ok(reason)
+            }
+        }
+    }
+}
+
+impl Puzzle for NumberPuzzle<'_> {
+    type Guess = u32;
+    type Answer = Ordering;
+
+    fn read_guess(&self, line: &str) -> Result<u32, String> {
+        // A program sends {"guess": 42}, a person can write a number any way numeral knows.
+        let guess = match self.json {
+            Some(_) => json::read_guess(line)?,
+            None => numeral::parse(line).map_err(|e| e.message(self.catalog))?,
+        };
+
+        // A number outside the range can't be the secret, so it would be unfair
+        // to answer "Too big!" and count it against the player.
+        if !self.settings.contains(guess) {
+            let GameSettings { low, high, .. } = self.settings;
+            return Err(self.catalog.format(
+                "out_of_range",
+                &[("guess", &guess), ("low", &low), ("high", &high)],
+            ));
+        }
+        Ok(guess)
+    }
+
+    fn judge(&mut self, guess: &u32) -> Ordering {
+        self.host.judge(*guess)
+    }
+
+    fn is_win(&self, answer: &Ordering) -> bool {
+        *answer == Ordering::Equal
+    }
+
+    fn describe(&self, answer: &Ordering) -> String {
+        match answer {
+            Ordering::Less => self.catalog.text("too_small"),
+            Ordering::Greater => self.catalog.text("too_big"),
+            Ordering::Equal => self.catalog.text("you_win"),
+        }
+    }
+
+    fn lose_message(&self) -> String {
+        self.catalog
+            .format("you_lose", &[("secret", &self.host.reveal())])
+    }
+
+    fn intercept(&mut self, line: &str, guesses: &[u32]) -> io::Result<Option<String>> {
+        if self.hints.is_empty() {
+            return Ok(None);
+        }
+        match hint_request(line) {
+            Some(name) => self.give_hint(name, guesses).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn on_input(&mut self, line: &str) -> io::Result<()> {
+        self.record(GameEvent::Input {
+            line: line.to_string(),
+        })
+    }
+
+    fn on_prompt(&mut self, attempt: u32) -> io::Result<()> {
+        let attempts_left = self.settings.max_attempts.map(|max| max + 1 - attempt);
+        self.send(JsonMessage::Prompt {
+            attempt,
+            attempts_left,
+        })
+    }
+
+    fn on_invalid(&mut self, line: &str, reason: &str) -> io::Result<()> {
+        self.record(GameEvent::Invalid {
+            reason: reason.to_string(),
+        })?;
+        self.send(JsonMessage::InvalidInput {
+            line: line.to_string(),
+            reason: reason.to_string(),
+        })
+    }
+
+    fn on_answer(&mut self, guess: &u32, answer: &Ordering) -> io::Result<()> {
+        self.record(GameEvent::Feedback {
+            guess: *guess,
+            feedback: (*answer).into(),
+        })?;
+        self.send(JsonMessage::Feedback {
+            guess: *guess,
+            feedback: (*answer).into(),
+        })
+    }
+
+    fn on_timeout(&mut self) -> io::Result<()> {
+        self.record(GameEvent::Missed)?;
+        self.send(JsonMessage::TimedOut)
+    }
+
+    // Nothing to add when the player already wrote the number out in words.
+    fn spell_out(&self, line: &str) -> Option<String> {
+        let number = numeral::parse(line).ok()?;
+        let words = numeral::spell(number, self.catalog.locale());
+        (!words.eq_ignore_ascii_case(line)).then_some(words)
+    }
+}
+
+// "hint" asks for the menu (Some("")), "hint parity" for the parity hint (Some("parity")).
+fn hint_request(line: &str) -> Option<&str> {
+    let line = line.trim();
+    let rest = line
+        .get(..4)
+        .filter(|word| word.eq_ignore_ascii_case("hint"))
+        .map(|_| &line[4..])?;
+    if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+        Some(rest.trim())
+    } else {
+        None
+    }
+}
@@ -0,0 +1,359 @@
+// Optional hints on top of "Too small!" and "Too big!".
+//
+// Every hint has a cost in points. A game starts with a budget of points and the player
+// types "hint" to see the menu or "hint <name>" to buy one. Hints are not attempts,
+// but the points spent on them are taken off the final score (see GameOutcome::score).
+//
+// Which hints are on offer is decided by the configuration (--hints), so new providers
+// only need to implement the Hint trait and get a name in HintKind.
+// The names are what the player types, so they stay the same in every language;
+// the texts come from the message catalog (see i18n.rs).
+
+use std::fmt::Display;
+
+use crate::i18n::Catalog;
+
+pub struct HintContext<'a> {
+    pub secret: u32,
+    pub low: u32,
+    pub high: u32,
+    // The valid guesses so far, oldest first.
+    pub guesses: &'a [u32],
+    pub catalog: &'a Catalog,
+}
+
+pub trait Hint {
+    fn name(&self) -> String;
+
+    fn cost(&self) -> u32;
+
+    // None when the hint has nothing to say yet, e.g. warmer/colder before the second guess.
+    // The player is not charged in that case.
+    fn give(&self, context: &HintContext) -> Option<String>;
+}
+
+// Is the last guess closer to the secret than the one before it?
+pub struct WarmerColder;
+
+impl Hint for WarmerColder {
+    fn name(&self) -> String {
+        String::from("warmer-colder")
+    }
+
+    fn cost(&self) -> u32 {
+        1
+    }
+
+    fn give(&self, context: &HintContext) -> Option<String> {
+        let [.., previous, last] = context.guesses else {
+            return None;
+        };
+        let before = previous.abs_diff(context.secret);
+        let now = last.abs_diff(context.secret);
+
+        let key = match now.cmp(&before) {
+            std::cmp::Ordering::Less => "hint_warmer",
+            std::cmp::Ordering::Greater => "hint_colder",
+            std::cmp::Ordering::Equal => "hint_same_distance",
+        };
+        Some(
+            context
+                .catalog
+                .format(key, &[("last", last), ("previous", previous)]),
+        )
+    }
+}
+
+pub struct Parity;
+
+impl Hint for Parity {
+    fn name(&self) -> String {
+        String::from("parity")
+    }
+
+    fn cost(&self) -> u32 {
+        1
+    }
+
+    fn give(&self, context: &HintContext) -> Option<String> {
+        if context.secret.is_multiple_of(2) {
+            Some(context.catalog.text("hint_even"))
+        } else {
+            Some(context.catalog.text("hint_odd"))
+        }
+    }
+}
+
+pub struct DigitSum;
+
+impl Hint for DigitSum {
+    fn name(&self) -> String {
+        String::from("digit-sum")
+    }
+
+    fn cost(&self) -> u32 {
+        2
+    }
+
+    fn give(&self, context: &HintContext) -> Option<String> {
+        let sum: u32 = context
+            .secret
+            .to_string()
+            .chars()
+            .filter_map(|c| c.to_digit(10))
+            .sum();
+        Some(context.catalog.format("hint_digit_sum", &[("sum", &sum)]))
+    }
+}
+
+// Is the last guess at most `distance` away from the secret?
+pub struct Within {
+    pub distance: u32,
+}
+
+impl Hint for Within {
+    fn name(&self) -> String {
+        format!("within-{}", self.distance)
+    }
+
+    fn cost(&self) -> u32 {
+        2
+    }
+
+    fn give(&self, context: &HintContext) -> Option<String> {
+        let last = context.guesses.last()?;
+        let key = if last.abs_diff(context.secret) <= self.distance {
+            "hint_within"
+        } else {
+            "hint_not_within"
+        };
+        Some(
+            context
+                .catalog
+                .format(key, &[("last", last), ("distance", &self.distance)]),
+        )
+    }
+}
+
+// The hints that can be picked from the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HintKind {
+    WarmerColder,
+    Parity,
+    DigitSum,
+    Within(u32),
+}
+
+impl HintKind {
+    pub const ALL: [HintKind; 4] = [
+        HintKind::WarmerColder,
+        HintKind::Parity,
+        HintKind::DigitSum,
+        HintKind::Within(10),
+    ];
+
+    // "within-N" works for any N, e.g. within-5.
+    pub fn parse(name: &str) -> Result<HintKind, String> {
+        let name = name.trim().to_lowercase();
+        match name.as_str() {
+            "warmer-colder" => return Ok(HintKind::WarmerColder),
+            "parity" => return Ok(HintKind::Parity),
+            "digit-sum" => return Ok(HintKind::DigitSum),
+            _ => {}
+        }
+        if let Some(distance) = name.strip_prefix("within-")
+            && let Ok(distance) = distance.parse()
+        {
+            return Ok(HintKind::Within(distance));
+        }
+        Err(format!(
+            "unknown hint '{name}', expected warmer-colder, parity, digit-sum or within-N"
+        ))
+    }
+
+    // A comma-separated list, or "all".
+    pub fn parse_list(value: &str) -> Result<Vec<HintKind>, String> {
+        if value.trim().eq_ignore_ascii_case("all") {
+            return Ok(HintKind::ALL.to_vec());
+        }
+        value.split(',').map(HintKind::parse).collect()
+    }
+
+    pub fn build(&self) -> Box<dyn Hint> {
+        match self {
+            HintKind::WarmerColder => Box::new(WarmerColder),
+            HintKind::Parity => Box::new(Parity),
+            HintKind::DigitSum => Box::new(DigitSum),
+            HintKind::Within(distance) => Box::new(Within {
+                distance: *distance,
+            }),
+        }
+    }
+}
+
+// The hints on offer in a game and the points left to spend on them.
+#[derive(Default)]
+pub struct HintBook {
+    hints: Vec<Box<dyn Hint>>,
+    budget: u32,
+    spent: u32,
+}
+
+impl HintBook {
+    pub fn new(budget: u32) -> HintBook {
+        HintBook {
+            hints: Vec::new(),
+            budget,
+            spent: 0,
+        }
+    }
+
+    pub fn from_kinds(kinds: &[HintKind], budget: u32) -> HintBook {
+        let mut book = HintBook::new(budget);
+        for kind in kinds {
+            book.add(kind.build());
+        }
+        book
+    }
+
+    pub fn add(&mut self, hint: Box<dyn Hint>) {
+        self.hints.push(hint);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hints.is_empty()
+    }
+
+    pub fn budget(&self) -> u32 {
+        self.budget
+    }
+
+    pub fn spent(&self) -> u32 {
+        self.spent
+    }
+
+    pub fn remaining(&self) -> u32 {
+        self.budget - self.spent
+    }
+
+    // Every game starts with the full budget again.
+    pub fn reset(&mut self) {
+        self.spent = 0;
+    }
+
+    pub fn menu(&self, catalog: &Catalog) -> String {
+        let mut menu = catalog.format("hint_menu", &[("points", &self.remaining())]);
+        for hint in &self.hints {
+            menu.push('\n');
+            menu.push_str(&catalog.format(
+                "hint_menu_item",
+                &[
+                    ("name", &hint.name() as &dyn Display),
+                    ("cost", &hint.cost()),
+                ],
+            ));
+        }
+        menu
+    }
+
+    // Ok with the hint text, or Err with the reason nothing was bought.
+    pub fn request(&mut self, name: &str, context: &HintContext) -> Result<String, String> {
+        let name = name.trim().to_lowercase();
+        let catalog = context.catalog;
+        let Some(hint) = self.hints.iter().find(|h| h.name() == name) else {
+            return Err(catalog.format("hint_unknown", &[("name", &name)]));
+        };
+
+        let cost = hint.cost();
+        if cost > self.remaining() {
+            return Err(catalog.format(
+                "hint_too_expensive",
+                &[
+                    ("name", &name as &dyn Display),
+                    ("cost", &cost),
+                    ("points", &self.remaining()),
+                ],
+            ));
+        }
+
+        let text = hint
+            .give(context)
+            .ok_or_else(|| catalog.format("hint_not_yet", &[("name", &name)]))?;
+        self.spent += cost;
+        Ok(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context<'a>(secret: u32, guesses: &'a [u32], catalog: &'a Catalog) -> HintContext<'a> {
+        HintContext {
+            secret,
+            low: 1,
+            high: 100,
+            guesses,
+            catalog,
+        }
+    }
+
+    #[test]
+    fn hints_tell_the_truth() {
+        let en = Catalog::english();
+        assert_eq!(
+            Parity.give(&context(42, &[], &en)).unwrap(),
+            "The number is even."
+        );
+        assert_eq!(
+            DigitSum.give(&context(47, &[], &en)).unwrap(),
+            "The digits of the number add up to 11."
+        );
+        assert!(
+            WarmerColder
+                .give(&context(47, &[10, 40], &en))
+                .unwrap()
+                .starts_with("Warmer")
+        );
+        assert!(
+            WarmerColder
+                .give(&context(47, &[40, 10], &en))
+                .unwrap()
+                .starts_with("Colder")
+        );
+        assert!(
+            Within { distance: 10 }
+                .give(&context(47, &[40], &en))
+                .unwrap()
+                .contains("within 10")
+        );
+    }
+
+    #[test]
+    fn some_hints_need_guesses() {
+        let en = Catalog::english();
+        assert_eq!(WarmerColder.give(&context(47, &[10], &en)), None);
+        assert_eq!(Within { distance: 10 }.give(&context(47, &[], &en)), None);
+    }
+
+    #[test]
+    fn the_budget_runs_out() {
+        let en = Catalog::english();
+        let mut book = HintBook::from_kinds(&[HintKind::Parity, HintKind::DigitSum], 2);
+
+        assert!(book.request("digit-sum", &context(5, &[], &en)).is_ok());
+        assert_eq!(book.remaining(), 0);
+        assert!(book.request("parity", &context(5, &[], &en)).is_err());
+        assert!(book.request("astrology", &context(5, &[], &en)).is_err());
+    }
+
+    #[test]
+    fn parse_hint_lists() {
+        assert_eq!(
+            HintKind::parse_list("parity,within-5").unwrap(),
+            vec![HintKind::Parity, HintKind::Within(5)]
+        );
+        assert_eq!(HintKind::parse_list("all").unwrap().len(), 4);
+        assert!(HintKind::parse_list("parity,tarot").is_err());
+    }
+}
@@ -0,0 +1,134 @@
+// The host is the side of the game that knows (or pretends to know) the secret
+// and answers every guess with "Too small!", "Too big!" or "You win!".
+//
+// FairHost is the original game: the secret is drawn once and never changes.
+// EvilHost never commits to a secret. It only remembers which numbers are still
+// consistent with everything it has said so far, and answers so that as many of them
+// as possible stay alive. Because its answers are always consistent, at the end it can
+// still reveal a secret that matches every answer it gave.
+
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+pub trait Host {
+    // guess.cmp(&secret): Less means "Too small!", Greater means "Too big!".
+    fn judge(&mut self, guess: u32) -> Ordering;
+
+    // A secret that is consistent with every answer given so far.
+    fn reveal(&self) -> u32;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FairHost {
+    secret: u32,
+}
+
+impl FairHost {
+    pub fn new(secret: u32) -> FairHost {
+        FairHost { secret }
+    }
+}
+
+impl Host for FairHost {
+    fn judge(&mut self, guess: u32) -> Ordering {
+        guess.cmp(&self.secret)
+    }
+
+    fn reveal(&self) -> u32 {
+        self.secret
+    }
+}
+
+// The numbers still consistent with the answers always form a single range,
+// so two bounds are enough to remember them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EvilHost {
+    low: u32,
+    high: u32,
+}
+
+impl EvilHost {
+    pub fn new(low: u32, high: u32) -> EvilHost {
+        EvilHost { low, high }
+    }
+
+    pub fn candidates(&self) -> u32 {
+        self.high - self.low + 1
+    }
+}
+
+impl Host for EvilHost {
+    fn judge(&mut self, guess: u32) -> Ordering {
+        // Guesses outside the candidates get the only answer that is still true
+        // and don't rule anything out.
+        if guess < self.low {
+            return Ordering::Less;
+        }
+        if guess > self.high {
+            return Ordering::Greater;
+        }
+
+        let above = self.high - guess;
+        let below = guess - self.low;
+
+        if above == 0 && below == 0 {
+            // Only one number is left and the player found it: the host has to concede.
+            Ordering::Equal
+        } else if above >= below {
+            self.low = guess + 1;
+            Ordering::Less
+        } else {
+            self.high = guess - 1;
+            Ordering::Greater
+        }
+    }
+
+    // Any remaining candidate would do; the middle one is as good as any.
+    fn reveal(&self) -> u32 {
+        self.low + (self.high - self.low) / 2
+    }
+}
+
+// Which host a game is played against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HostKind {
+    #[default]
+    Fair,
+    Evil,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evil_host_keeps_the_bigger_half() {
+        let mut host = EvilHost::new(1, 100);
+
+        assert_eq!(host.judge(30), Ordering::Less);
+        assert_eq!(host.candidates(), 70);
+        assert_eq!(host.judge(90), Ordering::Greater);
+        assert_eq!(host.candidates(), 59);
+    }
+
+    #[test]
+    fn evil_host_only_concedes_the_last_number() {
+        let mut host = EvilHost::new(1, 3);
+
+        assert_ne!(host.judge(2), Ordering::Equal);
+        let last = host.reveal();
+        assert_eq!(host.candidates(), 1);
+        assert_eq!(host.judge(last), Ordering::Equal);
+    }
+
+    #[test]
+    fn guesses_outside_the_candidates_change_nothing() {
+        let mut host = EvilHost::new(40, 60);
+
+        assert_eq!(host.judge(10), Ordering::Less);
+        assert_eq!(host.judge(99), Ordering::Greater);
+        assert_eq!(host.candidates(), 21);
+    }
+}
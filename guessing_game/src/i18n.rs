@@ -0,0 +1,193 @@
+// Translated messages.
+//
+// Every message the player sees during a game is looked up by key in a Catalog.
+// The catalogs are the JSON files in locales/ (one object of key -> message per language),
+// built into the binary so the game works from any directory.
+//
+// Messages can have placeholders like {guess}, filled in by Catalog::format in one pass
+// over the message, so a value that looks like a placeholder (a player called {secret})
+// comes out as it is.
+// A key missing from a translation falls back to English, and a key missing from
+// English too is printed as it is, so a typo shows up instead of an empty line.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+const ENGLISH: &str = include_str!("../locales/en.json");
+const ITALIAN: &str = include_str!("../locales/it.json");
+
+pub const DEFAULT_LOCALE: &str = "en";
+
+// The locales that come with the game, as (code, file contents).
+const BUILT_IN: [(&str, &str); 2] = [("en", ENGLISH), ("it", ITALIAN)];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Catalog {
+    locale: String,
+    messages: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+impl Catalog {
+    pub fn english() -> Catalog {
+        Catalog::for_locale(DEFAULT_LOCALE)
+    }
+
+    // A built-in catalog. Unknown locales get English.
+    pub fn for_locale(locale: &str) -> Catalog {
+        let (locale, json) = BUILT_IN
+            .iter()
+            .find(|(code, _)| *code == locale)
+            .unwrap_or(&BUILT_IN[0]);
+        Catalog::from_json(locale, json).expect("the built-in translations are valid JSON")
+    }
+
+    // A catalog from the contents of a translation file, with English as the fallback.
+    pub fn from_json(locale: &str, json: &str) -> Result<Catalog, String> {
+        let messages: HashMap<String, String> = serde_json::from_str(json)
+            .map_err(|e| format!("invalid translation file for '{locale}': {e}"))?;
+        let fallback =
+            serde_json::from_str(ENGLISH).expect("the built-in translations are valid JSON");
+
+        Ok(Catalog {
+            locale: locale.to_string(),
+            messages,
+            fallback,
+        })
+    }
+
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    pub fn text(&self, key: &str) -> String {
+        self.format(key, &[])
+    }
+
+    // format("range", &[("low", &1), ("high", &100)]) -> "The number is between 1 and 100."
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let template = self
+            .messages
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .map(String::as_str)
+            .unwrap_or(key);
+
+        let mut message = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(open) = rest.find('{') {
+            message.push_str(&rest[..open]);
+            let after = &rest[open + 1..];
+            let value = after.find('}').and_then(|close| {
+                let (_, value) = args.iter().find(|(name, _)| *name == &after[..close])?;
+                Some((value, close))
+            });
+            match value {
+                Some((value, close)) => {
+                    message.push_str(&value.to_string());
+                    rest = &after[close + 1..];
+                }
+                // Not one of ours: the brace stays, and the search goes on after it.
+                None => {
+                    message.push('{');
+                    rest = after;
+                }
+            }
+        }
+        message.push_str(rest);
+        message
+    }
+}
+
+impl Default for Catalog {
+    fn default() -> Catalog {
+        Catalog::english()
+    }
+}
+
+// The language part of a locale like LANG: "it_IT.UTF-8" -> "it".
+// "C" and "POSIX" (no locale set up) mean English.
+pub fn language_of(locale: &str) -> String {
+    let language = locale
+        .split(['_', '.', '@', '-'])
+        .next()
+        .unwrap_or("")
+        .to_lowercase();
+    match language.as_str() {
+        "" | "c" | "posix" => String::from(DEFAULT_LOCALE),
+        _ => language,
+    }
+}
+
+// True if the game comes with a translation for this language.
+pub fn is_available(locale: &str) -> bool {
+    BUILT_IN.iter().any(|(code, _)| *code == locale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_in_placeholders() {
+        let catalog = Catalog::english();
+
+        assert_eq!(
+            catalog.format("range", &[("low", &1), ("high", &100)]),
+            "The number is between 1 and 100."
+        );
+        assert_eq!(
+            Catalog::for_locale("it").format("attempts_left", &[("left", &3)]),
+            "Tentativi rimasti: 3"
+        );
+    }
+
+    #[test]
+    fn values_are_not_filled_in_again() {
+        let catalog = Catalog::english();
+
+        assert_eq!(
+            catalog.format("room_joined", &[("name", &"{secret}")]),
+            "{secret} joined the room."
+        );
+        assert_eq!(
+            catalog.format(
+                "you_lose",
+                &[("secret", &"{secret}"), ("unused", &"{secret}")]
+            ),
+            "You lose! The secret number was {secret}."
+        );
+        // Braces that are not placeholders, and placeholders without a value, stay as they are.
+        let catalog = Catalog::from_json("xx", r#"{ "odd": "{ {a} {b} {a" }"#).unwrap();
+        assert_eq!(catalog.format("odd", &[("a", &1)]), "{ 1 {b} {a");
+    }
+
+    #[test]
+    fn falls_back_to_english() {
+        let catalog = Catalog::from_json("xx", r#"{ "too_small": "Trop petit !" }"#).unwrap();
+
+        assert_eq!(catalog.text("too_small"), "Trop petit !");
+        assert_eq!(catalog.text("too_big"), "Too big!");
+        assert_eq!(catalog.text("no_such_key"), "no_such_key");
+        assert_eq!(Catalog::for_locale("klingon").locale(), "en");
+    }
+
+    #[test]
+    fn every_translation_has_every_key() {
+        let english: HashMap<String, String> = serde_json::from_str(ENGLISH).unwrap();
+        for (locale, json) in BUILT_IN {
+            let messages: HashMap<String, String> = serde_json::from_str(json).unwrap();
+            for key in english.keys() {
+                assert!(messages.contains_key(key), "{locale} is missing '{key}'");
+            }
+        }
+    }
+
+    #[test]
+    fn language_from_lang() {
+        assert_eq!(language_of("it_IT.UTF-8"), "it");
+        assert_eq!(language_of("en_GB"), "en");
+        assert_eq!(language_of("C"), "en");
+        assert_eq!(language_of(""), "en");
+    }
+}
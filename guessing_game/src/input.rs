@@ -0,0 +1,89 @@
+// Where the game loop gets its lines from.
+//
+// Any BufRead works (stdin, a byte slice in the tests...), but read_line waits forever.
+// The timed mode needs to give up on a turn when its deadline passes, so TimedInput
+// reads on a thread of its own and hands the lines over through a channel,
+// which the game loop can wait on with a timeout.
+
+use std::io::{self, BufRead};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Line {
+    // The line with its line ending, as read_line returns it.
+    Text(String),
+    TimedOut,
+    // The input is over (Ctrl-D, end of a script...).
+    End,
+}
+
+pub trait LineSource {
+    // Waits at most `timeout` for the next line; None waits as long as it takes.
+    fn next_line(&mut self, timeout: Option<Duration>) -> io::Result<Line>;
+}
+
+// A plain reader can't time out, so the timeout is ignored.
+impl<T: BufRead> LineSource for T {
+    fn next_line(&mut self, _timeout: Option<Duration>) -> io::Result<Line> {
+        let mut line = String::new();
+        if self.read_line(&mut line)? == 0 {
+            Ok(Line::End)
+        } else {
+            Ok(Line::Text(line))
+        }
+    }
+}
+
+pub struct TimedInput {
+    lines: Receiver<io::Result<String>>,
+}
+
+impl TimedInput {
+    // The reader moves to a background thread, which keeps reading until the input ends.
+    // A line that comes in after its turn timed out is not thrown away: by then the game has
+    // said "Time's up!" and asked again, so it is the answer to the next turn, the same as a
+    // line typed after the new prompt. Lines are only told apart by when they arrive, so
+    // there is nothing else to go by.
+    pub fn spawn<R: BufRead + Send + 'static>(mut reader: R) -> TimedInput {
+        let (sender, lines) = mpsc::channel();
+
+        thread::spawn(move || {
+            loop {
+                let mut line = String::new();
+                let read = reader.read_line(&mut line).map(|bytes| (bytes, line));
+                let done = !matches!(read, Ok((bytes, _)) if bytes > 0);
+                // The game is over when nobody is listening any more.
+                if sender.send(read.map(|(_, line)| line)).is_err() || done {
+                    break;
+                }
+            }
+        });
+
+        TimedInput { lines }
+    }
+}
+
+impl LineSource for TimedInput {
+    fn next_line(&mut self, timeout: Option<Duration>) -> io::Result<Line> {
+        let received = match timeout {
+            Some(timeout) => match self.lines.recv_timeout(timeout) {
+                Ok(received) => received,
+                Err(RecvTimeoutError::Timeout) => return Ok(Line::TimedOut),
+                Err(RecvTimeoutError::Disconnected) => return Ok(Line::End),
+            },
+            None => match self.lines.recv() {
+                Ok(received) => received,
+                Err(_) => return Ok(Line::End),
+            },
+        };
+
+        let line = received?;
+        if line.is_empty() {
+            Ok(Line::End)
+        } else {
+            Ok(Line::Text(line))
+        }
+    }
+}
@@ -0,0 +1,128 @@
+// The --json mode: the number game as JSON lines, for bots and other programs.
+//
+// Every line the program reads is a guess:
+//
+//   {"guess": 42}
+//
+// and every line it writes is one JSON object, tagged with its "type":
+//
+//   {"type":"start","low":1,"high":100,"max_attempts":7}
+//   {"type":"prompt","attempt":1,"attempts_left":7}
+//   {"type":"feedback","guess":42,"feedback":"less"}
+//   {"type":"invalid_input","line":"{\"guess\": 500}","reason":"..."}
+//   {"type":"timed_out"}
+//   {"type":"game_over","result":"won","secret":57,"attempts":3,"score":800}
+//
+// "less" means the guess is less than the secret (the "Too small!" of the text game).
+// The text a person would read is not written at all: main gives the session io::sink()
+// as its output, and the JsonReporter gets stdout instead.
+
+use std::io::{self, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::GameResult;
+use crate::record::Feedback;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JsonMessage {
+    Start {
+        low: u32,
+        high: u32,
+        max_attempts: Option<u32>,
+    },
+    // The game waits for the next guess. attempts_left is missing when there is no limit.
+    Prompt {
+        attempt: u32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        attempts_left: Option<u32>,
+    },
+    Feedback {
+        guess: u32,
+        feedback: Feedback,
+    },
+    // The line was not a guess, or not a guess we can take. It did not cost an attempt.
+    InvalidInput {
+        line: String,
+        reason: String,
+    },
+    // A timed turn ran out: that is an attempt without a guess.
+    TimedOut,
+    GameOver {
+        result: GameResult,
+        secret: u32,
+        attempts: u32,
+        score: u32,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GuessRequest {
+    guess: u32,
+}
+
+// {"guess": 42} -> 42
+pub fn read_guess(line: &str) -> Result<u32, String> {
+    serde_json::from_str::<GuessRequest>(line)
+        .map(|request| request.guess)
+        .map_err(|e| format!("expected {{\"guess\": <number>}}: {e}"))
+}
+
+// Writes the messages of one game as they happen, one per line.
+pub struct JsonReporter {
+    writer: Box<dyn Write>,
+}
+
+impl JsonReporter {
+    pub fn new(writer: Box<dyn Write>) -> JsonReporter {
+        JsonReporter { writer }
+    }
+
+    pub fn stdout() -> JsonReporter {
+        JsonReporter::new(Box::new(io::stdout()))
+    }
+
+    // Flushed right away: the program on the other end waits for every line.
+    pub fn send(&mut self, message: &JsonMessage) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, message)?;
+        writeln!(self.writer)?;
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_guesses() {
+        assert_eq!(read_guess(r#"{"guess": 42}"#), Ok(42));
+        assert_eq!(read_guess(r#" {"guess":7} "#), Ok(7));
+        assert!(read_guess("42").is_err());
+        assert!(read_guess(r#"{"guess": -1}"#).is_err());
+        assert!(read_guess(r#"{"guess": "42"}"#).is_err());
+        assert!(read_guess(r#"{"guess": 42, "cheat": true}"#).is_err());
+    }
+
+    #[test]
+    fn messages_are_tagged() {
+        let message = JsonMessage::Feedback {
+            guess: 42,
+            feedback: Feedback::Less,
+        };
+        assert_eq!(
+            serde_json::to_string(&message).unwrap(),
+            r#"{"type":"feedback","guess":42,"feedback":"less"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&JsonMessage::Prompt {
+                attempt: 1,
+                attempts_left: None
+            })
+            .unwrap(),
+            r#"{"type":"prompt","attempt":1}"#
+        );
+    }
+}
@@ -0,0 +1,539 @@
+// Player profiles and the local results file.
+//
+// Every finished game becomes a GameRecord. All the records are kept in a single JSON file
+// (an array of objects) so it stays easy to read and edit by hand.
+// The stats subcommand reads the same file and summarises it per player.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::{GameOutcome, GameResult, GameSettings};
+use crate::i18n::Catalog;
+
+pub const DEFAULT_RESULTS_FILE: &str = "guessing_game_results.json";
+
+pub const ANONYMOUS: &str = "anonymous";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameRecord {
+    pub player: String,
+    pub low: u32,
+    pub high: u32,
+    pub attempts: u32,
+    pub duration_ms: u64,
+    pub result: GameResult,
+    // Seconds since the Unix epoch, used to order a player's games for the win streaks.
+    pub finished_at: u64,
+    // Hint points spent. Older results files don't have it, hence the default.
+    #[serde(default)]
+    pub hint_points: u32,
+    // The final score (see GameOutcome::score) and how long every attempt took,
+    // in order, to see where players hesitate. Also missing from older files.
+    #[serde(default)]
+    pub score: u32,
+    #[serde(default)]
+    pub guess_times_ms: Vec<u64>,
+    // The date of the daily challenge ("2026-10-17") if this was one, see daily.rs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily: Option<String>,
+}
+
+impl GameRecord {
+    pub fn new(player: &str, settings: &GameSettings, outcome: &GameOutcome) -> GameRecord {
+        let finished_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        GameRecord {
+            player: player.to_string(),
+            low: settings.low(),
+            high: settings.high(),
+            attempts: outcome.attempts,
+            duration_ms: outcome.duration.as_millis() as u64,
+            result: outcome.result,
+            finished_at,
+            hint_points: outcome.hint_points,
+            score: outcome.score(),
+            guess_times_ms: outcome
+                .guess_times
+                .iter()
+                .map(|t| t.as_millis() as u64)
+                .collect(),
+            daily: None,
+        }
+    }
+
+    // In u64: the whole range of u32 has one number more than u32 can count.
+    fn range_size(&self) -> u64 {
+        u64::from(self.high.abs_diff(self.low)) + 1
+    }
+}
+
+#[derive(Debug)]
+pub enum LeaderboardError {
+    Io(io::Error),
+    Corrupted(serde_json::Error),
+}
+
+impl fmt::Display for LeaderboardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LeaderboardError::Io(e) => write!(f, "could not access the results file: {e}"),
+            LeaderboardError::Corrupted(e) => write!(f, "the results file is corrupted: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for LeaderboardError {}
+
+impl From<io::Error> for LeaderboardError {
+    fn from(e: io::Error) -> LeaderboardError {
+        LeaderboardError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for LeaderboardError {
+    fn from(e: serde_json::Error) -> LeaderboardError {
+        LeaderboardError::Corrupted(e)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Leaderboard {
+    records: Vec<GameRecord>,
+}
+
+impl Leaderboard {
+    pub fn new() -> Leaderboard {
+        Leaderboard::default()
+    }
+
+    // A missing file is not an error: it just means nobody has played yet.
+    pub fn load(path: &Path) -> Result<Leaderboard, LeaderboardError> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Leaderboard::new()),
+            // Not text at all: as broken as bad JSON, and just as worth keeping.
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                return Err(LeaderboardError::Corrupted(serde::de::Error::custom(e)));
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        if contents.trim().is_empty() {
+            return Ok(Leaderboard::new());
+        }
+
+        let records: Vec<GameRecord> = serde_json::from_str(&contents)?;
+        // Valid JSON, but not a game anybody played: the file was edited by hand.
+        if let Some(bad) = records.iter().find(|r| r.low > r.high) {
+            return Err(LeaderboardError::Corrupted(serde::de::Error::custom(
+                format!(
+                    "a game of {} has the range {}-{}",
+                    bad.player, bad.low, bad.high
+                ),
+            )));
+        }
+        Ok(Leaderboard { records })
+    }
+
+    // Like load, but a corrupted file is moved aside to "<file>.corrupted" and an empty board
+    // is started, so the next save does not overwrite data someone may want to fix by hand.
+    // The returned message explains what happened and is meant to be shown to the player.
+    // A file that can't be read, or a corrupted one that can't be moved aside, is an error:
+    // an empty board saved over it would lose every result in it.
+    pub fn load_or_recover(path: &Path) -> Result<(Leaderboard, Option<String>), LeaderboardError> {
+        match Leaderboard::load(path) {
+            Ok(board) => Ok((board, None)),
+            Err(LeaderboardError::Corrupted(e)) => {
+                let backup = corrupted_backup_path(path);
+                fs::rename(path, &backup)?;
+                let warning = format!(
+                    "The results file {} is corrupted ({e}). It was moved to {} and a new one will be started.",
+                    path.display(),
+                    backup.display()
+                );
+                Ok((Leaderboard::new(), Some(warning)))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    // Writes to a temporary file first and then renames it,
+    // so a crash halfway through never leaves a half-written results file behind.
+    pub fn save(&self, path: &Path) -> Result<(), LeaderboardError> {
+        let json = serde_json::to_string_pretty(&self.records)?;
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        fs::write(&tmp, json)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    pub fn record(&mut self, record: GameRecord) {
+        self.records.push(record);
+    }
+
+    pub fn records(&self) -> &[GameRecord] {
+        &self.records
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    // One entry per player, sorted by name.
+    pub fn player_stats(&self) -> Vec<PlayerStats> {
+        let mut by_player: HashMap<&str, Vec<&GameRecord>> = HashMap::new();
+        for record in &self.records {
+            by_player.entry(&record.player).or_default().push(record);
+        }
+
+        let mut stats: Vec<PlayerStats> = by_player
+            .into_iter()
+            .map(|(player, games)| PlayerStats::from_games(player, games))
+            .collect();
+        stats.sort_by(|a, b| a.player.cmp(&b.player));
+        stats
+    }
+
+    // The ten best won games: fewest attempts first,
+    // then the larger range (it is harder), then the faster game.
+    pub fn top_ten(&self) -> Vec<&GameRecord> {
+        let mut wins: Vec<&GameRecord> = self
+            .records
+            .iter()
+            .filter(|r| r.result == GameResult::Won)
+            .collect();
+        wins.sort_by(|a, b| compare_games(a, b));
+        wins.truncate(10);
+        wins
+    }
+
+    // The average time taken for the 1st, 2nd, 3rd... attempt of a game,
+    // over every game that has the times. Slow spots are where players hesitate.
+    pub fn average_guess_times(&self) -> Vec<f64> {
+        let mut totals: Vec<(u64, u32)> = Vec::new();
+        for record in &self.records {
+            for (turn, ms) in record.guess_times_ms.iter().enumerate() {
+                if totals.len() <= turn {
+                    totals.push((0, 0));
+                }
+                totals[turn].0 += ms;
+                totals[turn].1 += 1;
+            }
+        }
+        totals
+            .iter()
+            .map(|(ms, games)| *ms as f64 / *games as f64 / 1000.0)
+            .collect()
+    }
+}
+
+fn compare_games(a: &GameRecord, b: &GameRecord) -> std::cmp::Ordering {
+    a.attempts
+        .cmp(&b.attempts)
+        .then(a.hint_points.cmp(&b.hint_points))
+        .then(b.range_size().cmp(&a.range_size()))
+        .then(a.duration_ms.cmp(&b.duration_ms))
+}
+
+fn corrupted_backup_path(path: &Path) -> PathBuf {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".corrupted");
+    PathBuf::from(backup)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerStats {
+    pub player: String,
+    pub games: u32,
+    pub wins: u32,
+    pub losses: u32,
+    // Average attempts over the won games only, None if the player never won.
+    pub average_attempts: Option<f64>,
+    pub best_game: Option<GameRecord>,
+    pub current_streak: u32,
+    pub longest_streak: u32,
+}
+
+impl PlayerStats {
+    fn from_games(player: &str, mut games: Vec<&GameRecord>) -> PlayerStats {
+        // The stable sort keeps the file order for games finished in the same second.
+        games.sort_by_key(|g| g.finished_at);
+
+        let wins: Vec<&&GameRecord> = games
+            .iter()
+            .filter(|g| g.result == GameResult::Won)
+            .collect();
+        let losses = games
+            .iter()
+            .filter(|g| g.result == GameResult::Lost)
+            .count() as u32;
+
+        let average_attempts = if wins.is_empty() {
+            None
+        } else {
+            let total: u32 = wins.iter().map(|g| g.attempts).sum();
+            Some(total as f64 / wins.len() as f64)
+        };
+
+        let best_game = wins
+            .iter()
+            .min_by(|a, b| compare_games(a, b))
+            .map(|g| (**g).clone());
+
+        // An abandoned or lost game breaks the streak.
+        let mut current_streak = 0;
+        let mut longest_streak = 0;
+        for game in &games {
+            if game.result == GameResult::Won {
+                current_streak += 1;
+                longest_streak = longest_streak.max(current_streak);
+            } else {
+                current_streak = 0;
+            }
+        }
+
+        PlayerStats {
+            player: player.to_string(),
+            games: games.len() as u32,
+            wins: wins.len() as u32,
+            losses,
+            average_attempts,
+            best_game,
+            current_streak,
+            longest_streak,
+        }
+    }
+}
+
+// The text printed by the stats subcommand, in the catalog's language.
+// A column is as wide as its heading if the heading is longer than the usual width.
+pub fn render_stats(board: &Leaderboard, catalog: &Catalog) -> String {
+    if board.is_empty() {
+        return format!("{}\n", catalog.text("stats_none"));
+    }
+
+    let (headings, widths) = columns(
+        catalog,
+        &[
+            ("stats_player", 16),
+            ("stats_games", 5),
+            ("stats_wins", 5),
+            ("stats_losses", 6),
+            ("stats_average", 8),
+            ("stats_best", 10),
+            ("stats_streak", 7),
+            ("stats_longest", 7),
+        ],
+    );
+    let mut out = format!("{}\n", catalog.text("stats_players"));
+    out.push_str(&table_row(&headings, &widths, 0));
+    for stats in board.player_stats() {
+        let average = stats
+            .average_attempts
+            .map(|a| format!("{a:.2}"))
+            .unwrap_or_else(|| String::from("-"));
+        let best = stats
+            .best_game
+            .as_ref()
+            .map(|g| format!("{} ({}-{})", g.attempts, g.low, g.high))
+            .unwrap_or_else(|| String::from("-"));
+        let cells = [
+            stats.player.clone(),
+            stats.games.to_string(),
+            stats.wins.to_string(),
+            stats.losses.to_string(),
+            average,
+            best,
+            stats.current_streak.to_string(),
+            stats.longest_streak.to_string(),
+        ];
+        out.push_str(&table_row(&cells, &widths, 0));
+    }
+
+    let (headings, widths) = columns(
+        catalog,
+        &[
+            ("#", 3),
+            ("stats_player", 16),
+            ("stats_attempts", 8),
+            ("stats_hints", 5),
+            ("stats_range", 11),
+            ("stats_time", 9),
+        ],
+    );
+    out.push_str(&format!("\n{}\n", catalog.text("stats_top_ten")));
+    out.push_str(&table_row(&headings, &widths, 1));
+    for (rank, game) in board.top_ten().iter().enumerate() {
+        let cells = [
+            (rank + 1).to_string(),
+            game.player.clone(),
+            game.attempts.to_string(),
+            game.hint_points.to_string(),
+            format!("{}-{}", game.low, game.high),
+            format!("{:.1}s", game.duration_ms as f64 / 1000.0),
+        ];
+        out.push_str(&table_row(&cells, &widths, 1));
+    }
+
+    let times = board.average_guess_times();
+    if !times.is_empty() {
+        out.push_str(&format!("\n{}\n", catalog.text("stats_average_time")));
+        for (turn, seconds) in times.iter().enumerate() {
+            out.push_str(&format!("{:>3} {seconds:>8.1}s\n", turn + 1));
+        }
+    }
+
+    out
+}
+
+// The headings of a table and how wide each column is. "#" is not translated.
+fn columns(catalog: &Catalog, columns: &[(&str, usize)]) -> (Vec<String>, Vec<usize>) {
+    columns
+        .iter()
+        .map(|(key, width)| {
+            let heading = if *key == "#" {
+                key.to_string()
+            } else {
+                catalog.text(key)
+            };
+            let width = (*width).max(heading.chars().count());
+            (heading, width)
+        })
+        .unzip()
+}
+
+// The cells right-aligned, except the one at `left`.
+fn table_row(cells: &[String], widths: &[usize], left: usize) -> String {
+    let cells: Vec<String> = cells
+        .iter()
+        .zip(widths)
+        .enumerate()
+        .map(|(i, (cell, width))| {
+            if i == left {
+                format!("{cell:<width$}")
+            } else {
+                format!("{cell:>width$}")
+            }
+        })
+        .collect();
+    format!("{}\n", cells.join(" "))
+}
+
+// Asks for the player's name before the game starts.
+// An empty answer (or no input at all) plays as "anonymous".
+pub fn ask_player_name<I: BufRead, W: Write>(
+    input: &mut I,
+    output: &mut W,
+    catalog: &Catalog,
+) -> io::Result<String> {
+    writeln!(output, "{}", catalog.text("ask_name"))?;
+
+    let mut name = String::new();
+    input.read_line(&mut name)?;
+
+    let name = name.trim();
+    if name.is_empty() {
+        Ok(String::from(ANONYMOUS))
+    } else {
+        Ok(name.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(player: &str, attempts: u32, result: GameResult, finished_at: u64) -> GameRecord {
+        GameRecord {
+            player: player.to_string(),
+            low: 1,
+            high: 100,
+            attempts,
+            duration_ms: 1000,
+            result,
+            finished_at,
+            hint_points: 0,
+            score: 0,
+            guess_times_ms: Vec::new(),
+            daily: None,
+        }
+    }
+
+    #[test]
+    fn streaks_follow_the_order_games_were_played() {
+        let mut board = Leaderboard::new();
+        board.record(record("ada", 3, GameResult::Won, 1));
+        board.record(record("ada", 4, GameResult::Won, 2));
+        board.record(record("ada", 7, GameResult::Lost, 3));
+        board.record(record("ada", 5, GameResult::Won, 4));
+
+        let stats = &board.player_stats()[0];
+
+        assert_eq!(stats.games, 4);
+        assert_eq!(stats.wins, 3);
+        assert_eq!(stats.losses, 1);
+        assert_eq!(stats.average_attempts, Some(4.0));
+        assert_eq!(stats.best_game.as_ref().unwrap().attempts, 3);
+        assert_eq!(stats.current_streak, 1);
+        assert_eq!(stats.longest_streak, 2);
+    }
+
+    #[test]
+    fn top_ten_only_has_wins_best_first() {
+        let mut board = Leaderboard::new();
+        for attempts in (1..=12).rev() {
+            board.record(record("bob", attempts, GameResult::Won, attempts as u64));
+        }
+        board.record(record("bob", 1, GameResult::Lost, 99));
+
+        let top = board.top_ten();
+
+        assert_eq!(top.len(), 10);
+        assert!(top.iter().all(|g| g.result == GameResult::Won));
+        assert_eq!(top[0].attempts, 1);
+        assert_eq!(top[9].attempts, 10);
+    }
+
+    #[test]
+    fn the_whole_u32_range_is_the_largest() {
+        let mut board = Leaderboard::new();
+        let mut everything = record("ada", 3, GameResult::Won, 1);
+        everything.low = 0;
+        everything.high = u32::MAX;
+        board.record(record("bob", 3, GameResult::Won, 2));
+        board.record(everything);
+
+        assert_eq!(board.top_ten()[0].player, "ada");
+    }
+
+    #[test]
+    fn average_time_per_attempt() {
+        let mut board = Leaderboard::new();
+        let mut slow = record("ada", 3, GameResult::Won, 1);
+        slow.guess_times_ms = vec![1000, 5000, 2000];
+        let mut quick = record("bob", 2, GameResult::Won, 2);
+        quick.guess_times_ms = vec![3000, 1000];
+        board.record(slow);
+        board.record(quick);
+        board.record(record("cy", 4, GameResult::Won, 3));
+
+        assert_eq!(board.average_guess_times(), vec![2.0, 3.0, 2.0]);
+    }
+
+    #[test]
+    fn empty_name_is_anonymous() {
+        let mut output = Vec::new();
+        let name = ask_player_name(&mut "\n".as_bytes(), &mut output, &Catalog::english()).unwrap();
+        assert_eq!(name, "anonymous");
+    }
+}
@@ -0,0 +1,30 @@
+// The guessing game from chapter 2 of the Rust book, organised the way chapter 12 suggests:
+// the game logic lives in this library crate and src/main.rs is only a thin wrapper
+// that plugs in the command-line options, a random number generator, stdin and stdout.
+// The multiplayer server and client in src/bin/ use the same library.
+// This also lets the integration tests in tests/ play whole games from scripted input.
+
+pub mod autoplay;
+pub mod code;
+pub mod config;
+pub mod daily;
+pub mod engine;
+pub mod game;
+pub mod hint;
+pub mod host;
+pub mod i18n;
+pub mod input;
+pub mod json;
+pub mod leaderboard;
+pub mod net;
+pub mod numeral;
+pub mod record;
+pub mod strategy;
+
+pub use config::{Command, Config, Difficulty, Mode};
+pub use game::{GameOutcome, GameResult, GameSession, GameSettings};
+pub use hint::{Hint, HintBook, HintKind};
+pub use host::{EvilHost, FairHost, Host, HostKind};
+pub use i18n::Catalog;
+pub use leaderboard::{GameRecord, Leaderboard, PlayerStats};
+pub use strategy::{Strategy, StrategyKind};
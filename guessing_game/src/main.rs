@@ -1,43 +1,230 @@
-use std::io;
-use std::cmp::Ordering;
-use rand::Rng;
+use std::env;
+use std::error::Error;
+use std::fmt::Display;
+use std::io::{self, BufReader, Write};
+use std::process;
+
+use guessing_game::autoplay;
+use guessing_game::code::{self, CodeRules};
+use guessing_game::config::USAGE;
+use guessing_game::daily::{self, Date};
+use guessing_game::input::TimedInput;
+use guessing_game::json::JsonReporter;
+use guessing_game::leaderboard::{self, GameRecord, Leaderboard};
+use guessing_game::record::{self, Recorder};
+use guessing_game::{Catalog, Command, Config, GameSession, HintBook, Mode};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 fn main() {
-    println!("Guess the number!");
+    let config = Config::build(env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("Problem parsing arguments: {err}");
+        eprintln!("{USAGE}");
+        process::exit(2);
+    });
+
+    if config.help {
+        println!("{USAGE}");
+        return;
+    }
+
+    if let Err(e) = run(config) {
+        eprintln!("Problem running the game: {e}");
+        process::exit(1);
+    }
+}
+
+fn run(config: Config) -> Result<(), Box<dyn Error>> {
+    match config.command {
+        Command::Autoplay => {
+            run_autoplay(&config);
+            Ok(())
+        }
+        Command::Replay => run_replay(&config),
+        Command::Verify => run_verify(&config),
+        Command::Stats | Command::Play => match config.mode {
+            Mode::Code(rules) if config.command == Command::Play => run_code_game(rules, &config),
+            _ => run_game(config),
+        },
+    }
+}
+
+fn run_game(config: Config) -> Result<(), Box<dyn Error>> {
+    let (mut board, warning) = Leaderboard::load_or_recover(&config.results)?;
+    if let Some(warning) = warning {
+        eprintln!("Warning: {warning}");
+    }
+
+    if config.command == Command::Stats {
+        let catalog = Catalog::for_locale(&config.lang);
+        print!("{}", leaderboard::render_stats(&board, &catalog));
+        return Ok(());
+    }
+
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let mut output = io::stdout();
+    let catalog = Catalog::for_locale(&config.lang);
+
+    // In JSON mode stdout only carries the protocol, so there is no question about the name.
+    let player = match config.name {
+        Some(name) => name,
+        None if config.json => String::from(leaderboard::ANONYMOUS),
+        None => leaderboard::ask_player_name(&mut input, &mut output, &catalog)?,
+    };
 
-    let secret_number = rand::thread_rng().gen_range(1..=100);
+    let daily = (config.mode == Mode::Daily).then(Date::today);
+    if let Some(date) = daily {
+        if daily::played_on(&board, &player, date).is_some() {
+            let args: [(&str, &dyn Display); 2] = [("player", &player), ("date", &date)];
+            println!("{}", catalog.format("daily_already_played", &args));
+            return Ok(());
+        }
+        println!("{}", catalog.format("daily_title", &[("date", &date)]));
+    }
 
-    println!("The secret number is: {secret_number}");
+    // With --seed the same secret comes out every time, which is handy for debugging.
+    // Without it we still pick a seed ourselves, so a recording can always be verified.
+    // The daily challenge takes its seed from the date.
+    let seed = match daily {
+        Some(date) => daily::seed(date),
+        None => config.seed.unwrap_or_else(|| rand::thread_rng().r#gen()),
+    };
+    let rng = StdRng::seed_from_u64(seed);
 
-    loop {
+    // Reading on a thread of its own lets a timed turn give up waiting for stdin.
+    // Without a time limit it simply waits, like read_line.
+    // The lock has to go first, or the reading thread would wait for it forever.
+    drop(input);
+    let input = TimedInput::spawn(BufReader::new(io::stdin()));
 
-        println!("Please input your guess.");
+    let hints = HintBook::from_kinds(&config.hints, config.hint_budget);
+    let text: Box<dyn Write> = if config.json {
+        Box::new(io::sink())
+    } else {
+        Box::new(output)
+    };
+    let mut session = GameSession::with_settings(config.settings, rng, input, text)
+        .with_hints(hints)
+        .with_catalog(catalog.clone());
+    if let Some(path) = &config.record {
+        session = session.record_to(Recorder::create(path, Some(seed))?);
+    }
+    if config.json {
+        session = session.report_to(JsonReporter::stdout());
+    }
+    let outcome = session.play()?;
 
-        let mut guess = String::new();
+    let mut record = GameRecord::new(&player, &config.settings, &outcome);
+    if let Some(date) = daily {
+        println!();
+        println!("{}", catalog.text("daily_share"));
+        println!(
+            "{}",
+            daily::share_text(date, &outcome, &config.settings, &catalog)
+        );
+        record.daily = Some(date.to_string());
+    }
+    board.record(record);
+    // Losing the results file should not spoil the game that was just played.
+    if let Err(e) = board.save(&config.results) {
+        eprintln!("Warning: could not save the result: {e}");
+    }
 
-        io::stdin()
-            .read_line(&mut guess)
-            .expect("Failed to read line");
+    Ok(())
+}
 
-        println!("You guessed: {guess}");
+// The code games are not in the results file: a GameRecord describes a number game.
+fn run_code_game(rules: CodeRules, config: &Config) -> Result<(), Box<dyn Error>> {
+    let seed = config.seed.unwrap_or_else(|| rand::thread_rng().r#gen());
+    let stdin = io::stdin();
+    code::play(
+        rules,
+        &mut StdRng::seed_from_u64(seed),
+        stdin.lock(),
+        io::stdout(),
+        &Catalog::for_locale(&config.lang),
+    )?;
+    Ok(())
+}
 
-        let guess: u32 = match guess.trim().parse() {
-            Ok(num) => num,
-            Err(_) => {
-                println!("Please enter a valid number.");
-                continue;
-            }
-        };
+fn run_autoplay(config: &Config) {
+    // Print the seed even when it was picked at random, so an interesting run can be repeated.
+    let seed = config.seed.unwrap_or_else(|| rand::thread_rng().r#gen());
+    let catalog = Catalog::for_locale(&config.lang);
 
-        match guess.cmp(&secret_number) {
-            Ordering::Less => println!("Too small!"),
-            Ordering::Greater => println!("Too big!"),
-            Ordering::Equal => {
-                println!("You win!");
-                break;
-            }
+    if let Mode::Code(rules) = config.mode {
+        let args: [(&str, &dyn Display); 3] = [
+            ("games", &config.games),
+            ("length", &rules.length()),
+            ("seed", &seed),
+        ];
+        println!("{}", catalog.format("autoplay_codes", &args));
+        if let Some(limit) = rules.known_worst_case() {
+            println!(
+                "{}",
+                catalog.format("autoplay_minimax", &[("limit", &limit)])
+            );
         }
+        println!();
+        let report = code::run_benchmark(rules, config.games, seed);
+        print!("{}", autoplay::render_report(&report, &catalog));
+        return;
+    }
 
+    let args: [(&str, &dyn Display); 4] = [
+        ("games", &config.games),
+        ("low", &config.settings.low()),
+        ("high", &config.settings.high()),
+        ("seed", &seed),
+    ];
+    println!("{}", catalog.format("autoplay_numbers", &args));
+
+    for kind in &config.strategies {
+        let mut strategy = kind.build(seed);
+        let report =
+            autoplay::run_benchmark(strategy.as_mut(), &config.settings, config.games, seed);
+        println!();
+        print!("{}", autoplay::render_report(&report, &catalog));
     }
+}
+
+fn recording(config: &Config) -> &std::path::Path {
+    // Config::build makes sure replay and verify always have a file.
+    config
+        .record
+        .as_deref()
+        .expect("replay and verify need a recording")
+}
+
+fn run_replay(config: &Config) -> Result<(), Box<dyn Error>> {
+    let events = record::read_log(recording(config))?;
+    let stdin = io::stdin();
+    record::replay(
+        &events,
+        config.pace,
+        stdin.lock(),
+        io::stdout(),
+        &Catalog::for_locale(&config.lang),
+    )?;
+    Ok(())
+}
+
+fn run_verify(config: &Config) -> Result<(), Box<dyn Error>> {
+    let path = recording(config);
+    let events = record::read_log(path)?;
+    let catalog = Catalog::for_locale(&config.lang);
+    let problems = record::verify(&events, &catalog);
 
-}
\ No newline at end of file
+    if problems.is_empty() {
+        println!("{}: {}", path.display(), catalog.text("verify_consistent"));
+        return Ok(());
+    }
+
+    for problem in &problems {
+        println!("{}: {problem}", path.display());
+    }
+    Err(catalog
+        .format("verify_problems", &[("count", &problems.len())])
+        .into())
+}
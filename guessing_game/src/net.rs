@@ -0,0 +1,633 @@
+// Multiplayer over TCP, built only on std::net and threads (one thread per player),
+// in the spirit of the multithreaded web server at the end of the Rust book.
+//
+// The protocol is line based: every message is one line of space-separated words.
+//
+// Client -> server
+//   JOIN <room> <name> [<lang>]  must be the first line; creates the room if it doesn't exist.
+//                                The errors for the player come in their language (en if none).
+//   GUESS <number>          a guess for the current round
+//   QUIT                    leave the room
+//
+// Server -> client
+//   WELCOME <room> <round> <low> <high>
+//   JOINED <name> / LEFT <name>
+//   GUESSED <name> <number> TOO_SMALL|TOO_BIG|CORRECT     sent to everybody in the room
+//   WINNER <name> <secret> <round>                         sent to everybody in the room
+//   ROUND <round> <low> <high>                             a new secret has been drawn
+//   ERROR <message>                                        only sent to the player at fault
+//
+// Every room has its own secret. The first correct guess wins the round and
+// a new round with a new secret starts straight away. Guesses are judged one at a time
+// under the room's lock, so when players race only the first correct guess wins.
+// Only the range of the GameSettings is used: there is no attempt limit in multiplayer.
+//
+// Once in a room, everything a player is sent goes through a thread of its own that writes
+// to their socket. Broadcasting only queues the messages, so a player who stops reading holds
+// up nobody else, and their writer gives up after WRITE_TIMEOUT and drops them from the room.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::game::GameSettings;
+use crate::i18n::{self, Catalog};
+use crate::numeral;
+
+pub const DEFAULT_ADDR: &str = "127.0.0.1:7878";
+
+// How long a player's socket may stay full before the player counts as gone.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientMessage {
+    Join {
+        room: String,
+        name: String,
+        lang: Option<String>,
+    },
+    Guess(String),
+    Quit,
+}
+
+impl ClientMessage {
+    pub fn parse(line: &str) -> Result<ClientMessage, String> {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("JOIN") => match (words.next(), words.next(), words.next(), words.next()) {
+                (Some(room), Some(name), lang, None) if lang.is_none_or(i18n::is_available) => {
+                    Ok(ClientMessage::Join {
+                        room: room.to_string(),
+                        name: name.to_string(),
+                        lang: lang.map(str::to_string),
+                    })
+                }
+                _ => Err(String::from(
+                    "JOIN needs a room, a name without spaces and maybe a language (en or it)",
+                )),
+            },
+            // The number is checked by the room, so it can answer like the single-player game.
+            Some("GUESS") => Ok(ClientMessage::Guess(words.collect::<Vec<_>>().join(" "))),
+            Some("QUIT") => Ok(ClientMessage::Quit),
+            Some(other) => Err(format!("unknown command {other}")),
+            None => Err(String::from("empty line")),
+        }
+    }
+}
+
+impl fmt::Display for ClientMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClientMessage::Join { room, name, lang } => {
+                write!(f, "JOIN {room} {name}")?;
+                match lang {
+                    Some(lang) => write!(f, " {lang}"),
+                    None => Ok(()),
+                }
+            }
+            ClientMessage::Guess(guess) => write!(f, "GUESS {guess}"),
+            ClientMessage::Quit => write!(f, "QUIT"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerMessage {
+    Welcome {
+        room: String,
+        round: u32,
+        low: u32,
+        high: u32,
+    },
+    Joined(String),
+    Left(String),
+    Guessed {
+        name: String,
+        guess: u32,
+        feedback: Ordering,
+    },
+    Winner {
+        name: String,
+        secret: u32,
+        round: u32,
+    },
+    Round {
+        round: u32,
+        low: u32,
+        high: u32,
+    },
+    Error(String),
+}
+
+impl ServerMessage {
+    pub fn parse(line: &str) -> Result<ServerMessage, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let number = |i: usize| -> Result<u32, String> {
+            words
+                .get(i)
+                .and_then(|w| w.parse().ok())
+                .ok_or_else(|| format!("malformed message: {line}"))
+        };
+        let word = |i: usize| -> Result<String, String> {
+            words
+                .get(i)
+                .map(|w| w.to_string())
+                .ok_or_else(|| format!("malformed message: {line}"))
+        };
+
+        match words.first().copied() {
+            Some("WELCOME") => Ok(ServerMessage::Welcome {
+                room: word(1)?,
+                round: number(2)?,
+                low: number(3)?,
+                high: number(4)?,
+            }),
+            Some("JOINED") => Ok(ServerMessage::Joined(word(1)?)),
+            Some("LEFT") => Ok(ServerMessage::Left(word(1)?)),
+            Some("GUESSED") => Ok(ServerMessage::Guessed {
+                name: word(1)?,
+                guess: number(2)?,
+                feedback: match words.get(3).copied() {
+                    Some("TOO_SMALL") => Ordering::Less,
+                    Some("TOO_BIG") => Ordering::Greater,
+                    Some("CORRECT") => Ordering::Equal,
+                    _ => return Err(format!("malformed message: {line}")),
+                },
+            }),
+            Some("WINNER") => Ok(ServerMessage::Winner {
+                name: word(1)?,
+                secret: number(2)?,
+                round: number(3)?,
+            }),
+            Some("ROUND") => Ok(ServerMessage::Round {
+                round: number(1)?,
+                low: number(2)?,
+                high: number(3)?,
+            }),
+            Some("ERROR") => Ok(ServerMessage::Error(words[1..].join(" "))),
+            _ => Err(format!("unknown message: {line}")),
+        }
+    }
+
+    // How the client shows the message to a person, in the catalog's language.
+    // An Error is shown as the server wrote it.
+    pub fn describe(&self, catalog: &Catalog) -> String {
+        match self {
+            ServerMessage::Welcome {
+                room,
+                round,
+                low,
+                high,
+            } => catalog.format(
+                "room_welcome",
+                &[
+                    ("room", room),
+                    ("round", round),
+                    ("low", low),
+                    ("high", high),
+                ],
+            ),
+            ServerMessage::Joined(name) => catalog.format("room_joined", &[("name", name)]),
+            ServerMessage::Left(name) => catalog.format("room_left", &[("name", name)]),
+            ServerMessage::Guessed {
+                name,
+                guess,
+                feedback,
+            } => {
+                let feedback = catalog.text(match feedback {
+                    Ordering::Less => "too_small",
+                    Ordering::Greater => "too_big",
+                    Ordering::Equal => "room_correct",
+                });
+                catalog.format(
+                    "room_guessed",
+                    &[("name", name), ("guess", guess), ("feedback", &feedback)],
+                )
+            }
+            ServerMessage::Winner {
+                name,
+                secret,
+                round,
+            } => catalog.format(
+                "room_winner",
+                &[("name", name), ("round", round), ("secret", secret)],
+            ),
+            ServerMessage::Round { round, low, high } => catalog.format(
+                "room_round",
+                &[("round", round), ("low", low), ("high", high)],
+            ),
+            ServerMessage::Error(message) => message.clone(),
+        }
+    }
+}
+
+impl fmt::Display for ServerMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ServerMessage::Welcome {
+                room,
+                round,
+                low,
+                high,
+            } => write!(f, "WELCOME {room} {round} {low} {high}"),
+            ServerMessage::Joined(name) => write!(f, "JOINED {name}"),
+            ServerMessage::Left(name) => write!(f, "LEFT {name}"),
+            ServerMessage::Guessed {
+                name,
+                guess,
+                feedback,
+            } => {
+                let feedback = match feedback {
+                    Ordering::Less => "TOO_SMALL",
+                    Ordering::Greater => "TOO_BIG",
+                    Ordering::Equal => "CORRECT",
+                };
+                write!(f, "GUESSED {name} {guess} {feedback}")
+            }
+            ServerMessage::Winner {
+                name,
+                secret,
+                round,
+            } => write!(f, "WINNER {name} {secret} {round}"),
+            ServerMessage::Round { round, low, high } => write!(f, "ROUND {round} {low} {high}"),
+            ServerMessage::Error(message) => write!(f, "ERROR {message}"),
+        }
+    }
+}
+
+fn send(stream: &mut TcpStream, message: &ServerMessage) -> io::Result<()> {
+    writeln!(stream, "{message}")
+}
+
+// The writer thread of a player who joined a room. It ends when every sender is gone
+// (the player left) or when a write fails, after which sending to the player fails too.
+fn spawn_writer(mut stream: TcpStream) -> io::Result<Sender<ServerMessage>> {
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    let (outbox, messages) = mpsc::channel();
+    thread::spawn(move || {
+        for message in messages {
+            if send(&mut stream, &message).is_err() {
+                break;
+            }
+        }
+    });
+    Ok(outbox)
+}
+
+// Queues a message for the player; an error means their connection is gone.
+fn tell(outbox: &Sender<ServerMessage>, message: ServerMessage) -> io::Result<()> {
+    outbox
+        .send(message)
+        .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "the player's writer stopped"))
+}
+
+struct Player {
+    name: String,
+    outbox: Sender<ServerMessage>,
+}
+
+struct Room {
+    round: u32,
+    secret: u32,
+    players: Vec<Player>,
+}
+
+impl Room {
+    // A player whose connection is broken is dropped from the room.
+    // Only queues the message: nothing waits for a socket while the rooms are locked.
+    fn broadcast(&mut self, message: &ServerMessage) {
+        self.players
+            .retain(|player| player.outbox.send(message.clone()).is_ok());
+    }
+}
+
+// State shared by every connection thread.
+struct Shared {
+    settings: GameSettings,
+    rng: Mutex<StdRng>,
+    rooms: Mutex<HashMap<String, Room>>,
+}
+
+impl Shared {
+    fn draw_secret(&self) -> u32 {
+        self.rng
+            .lock()
+            .unwrap()
+            .gen_range(self.settings.low()..=self.settings.high())
+    }
+}
+
+pub struct Server {
+    listener: TcpListener,
+    shared: Arc<Shared>,
+}
+
+impl Server {
+    // Bind to port 0 to let the OS pick a free port, then ask local_addr which one it was.
+    pub fn bind(
+        addr: impl ToSocketAddrs,
+        settings: GameSettings,
+        seed: Option<u64>,
+    ) -> io::Result<Server> {
+        let listener = TcpListener::bind(addr)?;
+        let rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+
+        Ok(Server {
+            listener,
+            shared: Arc::new(Shared {
+                settings,
+                rng: Mutex::new(rng),
+                rooms: Mutex::new(HashMap::new()),
+            }),
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    // Accepts players forever, one thread each.
+    pub fn run(self) {
+        for stream in self.listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    eprintln!("Connection failed: {e}");
+                    continue;
+                }
+            };
+
+            let shared = Arc::clone(&self.shared);
+            thread::spawn(move || {
+                if let Err(e) = handle_connection(stream, &shared) {
+                    eprintln!("Connection error: {e}");
+                }
+            });
+        }
+    }
+
+    // Runs the server on a background thread, which is what the tests need.
+    pub fn spawn(self) -> JoinHandle<()> {
+        thread::spawn(move || self.run())
+    }
+}
+
+fn handle_connection(stream: TcpStream, shared: &Shared) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let mut lines = BufReader::new(stream).lines();
+
+    // The first line has to say who you are and where you want to play.
+    // Until then the player's language is unknown, so those errors are in English.
+    let (room_name, name, lang) = loop {
+        let Some(line) = lines.next() else {
+            return Ok(());
+        };
+        match ClientMessage::parse(&line?) {
+            Ok(ClientMessage::Join { room, name, lang }) => break (room, name, lang),
+            Ok(ClientMessage::Quit) => return Ok(()),
+            Ok(_) => send(
+                &mut writer,
+                &ServerMessage::Error(String::from("Please JOIN a room first.")),
+            )?,
+            Err(e) => send(&mut writer, &ServerMessage::Error(e))?,
+        }
+    };
+
+    let catalog = Catalog::for_locale(lang.as_deref().unwrap_or(i18n::DEFAULT_LOCALE));
+    let Some(outbox) = join(shared, &room_name, &name, &writer)? else {
+        return send(
+            &mut writer,
+            &ServerMessage::Error(
+                catalog.format("room_name_taken", &[("name", &name), ("room", &room_name)]),
+            ),
+        );
+    };
+
+    // From here on the writer thread has the socket: every answer goes through the outbox.
+    for line in lines {
+        let message = match ClientMessage::parse(&line?) {
+            Ok(message) => message,
+            Err(e) => {
+                tell(&outbox, ServerMessage::Error(e))?;
+                continue;
+            }
+        };
+
+        match message {
+            ClientMessage::Guess(guess) => {
+                guess_in_room(shared, &room_name, &name, &guess, &outbox, &catalog)?
+            }
+            ClientMessage::Join { .. } => tell(
+                &outbox,
+                ServerMessage::Error(catalog.text("room_already_in")),
+            )?,
+            ClientMessage::Quit => break,
+        }
+    }
+
+    leave(shared, &room_name, &name);
+    Ok(())
+}
+
+// The player's outbox, or None if somebody in the room already has that name.
+fn join(
+    shared: &Shared,
+    room_name: &str,
+    name: &str,
+    writer: &TcpStream,
+) -> io::Result<Option<Sender<ServerMessage>>> {
+    let mut rooms = shared.rooms.lock().unwrap();
+    let room = rooms.entry(room_name.to_string()).or_insert_with(|| Room {
+        round: 1,
+        secret: shared.draw_secret(),
+        players: Vec::new(),
+    });
+
+    if room.players.iter().any(|p| p.name == name) {
+        return Ok(None);
+    }
+
+    room.broadcast(&ServerMessage::Joined(name.to_string()));
+
+    let outbox = spawn_writer(writer.try_clone()?)?;
+    tell(
+        &outbox,
+        ServerMessage::Welcome {
+            room: room_name.to_string(),
+            round: room.round,
+            low: shared.settings.low(),
+            high: shared.settings.high(),
+        },
+    )?;
+    room.players.push(Player {
+        name: name.to_string(),
+        outbox: outbox.clone(),
+    });
+
+    Ok(Some(outbox))
+}
+
+fn leave(shared: &Shared, room_name: &str, name: &str) {
+    let mut rooms = shared.rooms.lock().unwrap();
+    if let Some(room) = rooms.get_mut(room_name) {
+        if let Some(index) = room.players.iter().position(|p| p.name == name) {
+            room.players.remove(index);
+        }
+        room.broadcast(&ServerMessage::Left(name.to_string()));
+        if room.players.is_empty() {
+            rooms.remove(room_name);
+        }
+    }
+}
+
+fn guess_in_room(
+    shared: &Shared,
+    room_name: &str,
+    name: &str,
+    guess: &str,
+    outbox: &Sender<ServerMessage>,
+    catalog: &Catalog,
+) -> io::Result<()> {
+    let settings = &shared.settings;
+    let guess = match numeral::parse(guess) {
+        Ok(num) => num,
+        Err(e) => return tell(outbox, ServerMessage::Error(e.message(catalog))),
+    };
+    if !settings.contains(guess) {
+        return tell(
+            outbox,
+            ServerMessage::Error(catalog.format(
+                "out_of_range",
+                &[
+                    ("guess", &guess),
+                    ("low", &settings.low()),
+                    ("high", &settings.high()),
+                ],
+            )),
+        );
+    }
+
+    let mut rooms = shared.rooms.lock().unwrap();
+    let Some(room) = rooms.get_mut(room_name) else {
+        return Ok(());
+    };
+
+    let feedback = guess.cmp(&room.secret);
+    room.broadcast(&ServerMessage::Guessed {
+        name: name.to_string(),
+        guess,
+        feedback,
+    });
+
+    if feedback == Ordering::Equal {
+        room.broadcast(&ServerMessage::Winner {
+            name: name.to_string(),
+            secret: room.secret,
+            round: room.round,
+        });
+        room.round += 1;
+        room.secret = shared.draw_secret();
+        room.broadcast(&ServerMessage::Round {
+            round: room.round,
+            low: settings.low(),
+            high: settings.high(),
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_messages_round_trip() {
+        let messages = [
+            ServerMessage::Welcome {
+                room: String::from("lobby"),
+                round: 1,
+                low: 1,
+                high: 100,
+            },
+            ServerMessage::Guessed {
+                name: String::from("ada"),
+                guess: 42,
+                feedback: Ordering::Greater,
+            },
+            ServerMessage::Winner {
+                name: String::from("ada"),
+                secret: 17,
+                round: 3,
+            },
+            ServerMessage::Error(String::from("Please enter a valid number.")),
+        ];
+
+        for message in messages {
+            assert_eq!(ServerMessage::parse(&message.to_string()), Ok(message));
+        }
+    }
+
+    #[test]
+    fn described_in_the_player_language() {
+        let guessed = ServerMessage::Guessed {
+            name: String::from("ada"),
+            guess: 42,
+            feedback: Ordering::Less,
+        };
+        assert_eq!(
+            guessed.describe(&Catalog::english()),
+            "ada guessed 42: Too small!"
+        );
+        assert_eq!(
+            guessed.describe(&Catalog::for_locale("it")),
+            "ada ha provato 42: Troppo piccolo!"
+        );
+        let welcome = ServerMessage::Welcome {
+            room: String::from("lobby"),
+            round: 2,
+            low: 1,
+            high: 100,
+        };
+        assert_eq!(
+            welcome.describe(&Catalog::english()),
+            "Welcome to room lobby! Round 2: guess the number between 1 and 100."
+        );
+    }
+
+    #[test]
+    fn client_messages() {
+        assert_eq!(
+            ClientMessage::parse("JOIN lobby ada"),
+            Ok(ClientMessage::Join {
+                room: String::from("lobby"),
+                name: String::from("ada"),
+                lang: None
+            })
+        );
+        let italian = ClientMessage::Join {
+            room: String::from("lobby"),
+            name: String::from("ada"),
+            lang: Some(String::from("it")),
+        };
+        assert_eq!(ClientMessage::parse(&italian.to_string()), Ok(italian));
+        assert_eq!(
+            ClientMessage::parse("GUESS 42"),
+            Ok(ClientMessage::Guess(String::from("42")))
+        );
+        assert!(ClientMessage::parse("JOIN lobby ada lovelace").is_err());
+        assert!(ClientMessage::parse("DANCE").is_err());
+    }
+}
@@ -0,0 +1,741 @@
+// Reading numbers the way people write them, and writing them out in words.
+//
+// parse accepts, besides plain decimals like "42":
+//   - English and Italian number words: "forty two", "forty-two", "quarantadue"
+//   - Roman numerals: "XLII" (or "xlii")
+//   - hexadecimal with a 0x prefix: "0x2A"
+//   - simple arithmetic on any of those: "50-8", "6*7", "forty + 2"
+//     (* and / before + and -, and a division has to come out even)
+//
+// When a line is not a number, the error says why, so the player can fix it.
+
+use crate::i18n::Catalog;
+
+const ENGLISH_ONES: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+const ENGLISH_TENS: [&str; 8] = [
+    "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+const ENGLISH_SCALES: [(&str, u64); 3] = [
+    ("billion", 1_000_000_000),
+    ("million", 1_000_000),
+    ("thousand", 1_000),
+];
+
+const ITALIAN_ONES: [&str; 20] = [
+    "zero",
+    "uno",
+    "due",
+    "tre",
+    "quattro",
+    "cinque",
+    "sei",
+    "sette",
+    "otto",
+    "nove",
+    "dieci",
+    "undici",
+    "dodici",
+    "tredici",
+    "quattordici",
+    "quindici",
+    "sedici",
+    "diciassette",
+    "diciotto",
+    "diciannove",
+];
+const ITALIAN_TENS: [&str; 8] = [
+    "venti",
+    "trenta",
+    "quaranta",
+    "cinquanta",
+    "sessanta",
+    "settanta",
+    "ottanta",
+    "novanta",
+];
+// (one of them, several of them, value): "un milione", "due milioni".
+const ITALIAN_SCALES: [(&str, &str, u64); 2] = [
+    ("unmiliardo", "miliardi", 1_000_000_000),
+    ("unmilione", "milioni", 1_000_000),
+];
+
+const ROMAN: [(&str, u32); 13] = [
+    ("M", 1000),
+    ("CM", 900),
+    ("D", 500),
+    ("CD", 400),
+    ("C", 100),
+    ("XC", 90),
+    ("L", 50),
+    ("XL", 40),
+    ("X", 10),
+    ("IX", 9),
+    ("V", 5),
+    ("IV", 4),
+    ("I", 1),
+];
+
+// Why a line is not a number. Each one carries the part of the line it is about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NumberError {
+    Empty,
+    // Not any kind of number we know: "4.5", "12abc".
+    NotANumber(String),
+    UnknownWord(String),
+    // Number words that don't make a number together: "two three".
+    BadWords(String),
+    BadRoman(String),
+    BadHex(String),
+    // An operator without a number on one side: "50+".
+    Incomplete(String),
+    DivisionByZero,
+    NotWhole(String),
+    Negative(String),
+    TooBig(String),
+}
+
+impl NumberError {
+    // "Please enter a valid number." followed by the reason, in the catalog's language.
+    pub fn message(&self, catalog: &Catalog) -> String {
+        let reason = match self {
+            NumberError::Empty => catalog.text("number_empty"),
+            NumberError::NotANumber(text) => {
+                catalog.format("number_not_a_number", &[("text", text)])
+            }
+            NumberError::UnknownWord(word) => {
+                catalog.format("number_unknown_word", &[("word", word)])
+            }
+            NumberError::BadWords(text) => catalog.format("number_bad_words", &[("text", text)]),
+            NumberError::BadRoman(text) => catalog.format("number_bad_roman", &[("text", text)]),
+            NumberError::BadHex(text) => catalog.format("number_bad_hex", &[("text", text)]),
+            NumberError::Incomplete(text) => catalog.format("number_incomplete", &[("text", text)]),
+            NumberError::DivisionByZero => catalog.text("number_division_by_zero"),
+            NumberError::NotWhole(text) => catalog.format("number_not_whole", &[("text", text)]),
+            NumberError::Negative(text) => catalog.format("number_negative", &[("text", text)]),
+            NumberError::TooBig(text) => catalog.format("number_too_big", &[("text", text)]),
+        };
+        format!("{} {reason}", catalog.text("invalid_number"))
+    }
+}
+
+pub fn parse(text: &str) -> Result<u32, NumberError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(NumberError::Empty);
+    }
+    if text.starts_with('-') {
+        return Err(NumberError::Negative(text.to_string()));
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    if (0..chars.len()).any(|i| is_operator(&chars, i)) {
+        calculate(text, &chars)
+    } else {
+        parse_term(text)
+    }
+}
+
+// The number in words: spell(42, "en") -> "forty-two", spell(42, "it") -> "quarantadue".
+// Languages we can't spell in get English.
+pub fn spell(number: u32, locale: &str) -> String {
+    match locale {
+        "it" => italian_words(u64::from(number)),
+        _ => english_words(u64::from(number)),
+    }
+}
+
+// A '-' between two letters is the hyphen in "forty-two", not a minus.
+fn is_operator(chars: &[char], i: usize) -> bool {
+    match chars[i] {
+        '+' | '*' | '/' => true,
+        '-' => {
+            let letter_before = i > 0 && chars[i - 1].is_alphabetic();
+            let letter_after = chars.get(i + 1).is_some_and(|c| c.is_alphabetic());
+            !(letter_before && letter_after)
+        }
+        _ => false,
+    }
+}
+
+fn calculate(text: &str, chars: &[char]) -> Result<u32, NumberError> {
+    let mut operands = vec![String::new()];
+    let mut operators = Vec::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if is_operator(chars, i) {
+            operators.push(c);
+            operands.push(String::new());
+        } else {
+            operands.last_mut().unwrap().push(c);
+        }
+    }
+
+    let mut values = Vec::new();
+    for operand in &operands {
+        let operand = operand.trim();
+        if operand.is_empty() {
+            return Err(NumberError::Incomplete(text.to_string()));
+        }
+        values.push(i64::from(parse_term(operand)?));
+    }
+
+    // * and / work on the last term, + and - start a new one.
+    let too_big = || NumberError::TooBig(text.to_string());
+    let mut terms = vec![values[0]];
+    for (operator, &value) in operators.iter().zip(&values[1..]) {
+        let last = terms.last_mut().unwrap();
+        match operator {
+            '+' => terms.push(value),
+            '-' => terms.push(-value),
+            '*' => *last = last.checked_mul(value).ok_or_else(too_big)?,
+            _ => {
+                if value == 0 {
+                    return Err(NumberError::DivisionByZero);
+                }
+                if *last % value != 0 {
+                    return Err(NumberError::NotWhole(text.to_string()));
+                }
+                *last /= value;
+            }
+        }
+    }
+
+    let mut total: i64 = 0;
+    for term in terms {
+        total = total.checked_add(term).ok_or_else(too_big)?;
+    }
+    if total < 0 {
+        return Err(NumberError::Negative(text.to_string()));
+    }
+    u32::try_from(total).map_err(|_| too_big())
+}
+
+// One number, without any arithmetic.
+fn parse_term(text: &str) -> Result<u32, NumberError> {
+    let too_big = || NumberError::TooBig(text.to_string());
+
+    if text.chars().all(|c| c.is_ascii_digit()) {
+        // Only digits, so the only way to fail is overflow.
+        return text.parse().map_err(|_| too_big());
+    }
+
+    if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        if hex.is_empty() || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(NumberError::BadHex(text.to_string()));
+        }
+        return u32::from_str_radix(hex, 16).map_err(|_| too_big());
+    }
+
+    if text.chars().all(|c| "IVXLCDMivxlcdm".contains(c)) {
+        return roman(text);
+    }
+
+    if text
+        .chars()
+        .all(|c| c.is_alphabetic() || c.is_whitespace() || c == '-')
+    {
+        let lower = text.to_lowercase();
+        // Italian writes its numbers as one word: "quarantadue", "duemilaventi".
+        let compact: String = lower
+            .chars()
+            .filter(|c| c.is_alphabetic())
+            .map(|c| if c == 'é' || c == 'è' { 'e' } else { c })
+            .collect();
+        let value = match english(&lower) {
+            Ok(value) => value,
+            Err(e) => italian(&compact).ok_or(e)?,
+        };
+        return u32::try_from(value).map_err(|_| too_big());
+    }
+
+    Err(NumberError::NotANumber(text.to_string()))
+}
+
+// Only numerals written the usual way count: "IV", not "IIII".
+fn roman(text: &str) -> Result<u32, NumberError> {
+    let upper = text.to_uppercase();
+    let mut rest = upper.as_str();
+    let mut value = 0;
+    while !rest.is_empty() {
+        let Some((numeral, amount)) = ROMAN.iter().find(|(numeral, _)| rest.starts_with(numeral))
+        else {
+            break;
+        };
+        value += amount;
+        rest = &rest[numeral.len()..];
+    }
+
+    if value == 0 || value > 3999 || to_roman(value) != upper {
+        return Err(NumberError::BadRoman(text.to_string()));
+    }
+    Ok(value)
+}
+
+fn to_roman(mut value: u32) -> String {
+    let mut numeral = String::new();
+    for (letters, amount) in ROMAN {
+        while value >= amount {
+            numeral.push_str(letters);
+            value -= amount;
+        }
+    }
+    numeral
+}
+
+// "one hundred and five", "forty-two", "two million three hundred thousand".
+fn english(text: &str) -> Result<u64, NumberError> {
+    #[derive(PartialEq)]
+    enum Last {
+        Nothing,
+        Unit,
+        Teen,
+        Tens,
+        Hundred,
+        Scale,
+    }
+
+    let bad_words = || NumberError::BadWords(text.to_string());
+    let words: Vec<&str> = text
+        .split(|c: char| c.is_whitespace() || c == '-')
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    if words == ["zero"] {
+        return Ok(0);
+    }
+
+    let mut total = 0;
+    // The part below a thousand that is still being read.
+    let mut group = 0;
+    let mut last = Last::Nothing;
+    let mut last_scale = u64::MAX;
+
+    for (i, word) in words.iter().enumerate() {
+        let after_separator = matches!(last, Last::Nothing | Last::Hundred | Last::Scale);
+
+        if let Some(n) = ENGLISH_ONES.iter().position(|w| w == word) {
+            let n = n as u64;
+            let allowed = match n {
+                0 => false,
+                1..=9 => after_separator || last == Last::Tens,
+                _ => after_separator,
+            };
+            if !allowed {
+                return Err(bad_words());
+            }
+            group += n;
+            last = if n < 10 { Last::Unit } else { Last::Teen };
+        } else if let Some(n) = ENGLISH_TENS.iter().position(|w| w == word) {
+            if !after_separator {
+                return Err(bad_words());
+            }
+            group += (n as u64 + 2) * 10;
+            last = Last::Tens;
+        } else if *word == "hundred" {
+            if last != Last::Unit || group >= 10 {
+                return Err(bad_words());
+            }
+            group *= 100;
+            last = Last::Hundred;
+        } else if let Some((_, scale)) = ENGLISH_SCALES.iter().find(|(w, _)| w == word) {
+            if matches!(last, Last::Nothing | Last::Scale) || *scale >= last_scale {
+                return Err(bad_words());
+            }
+            total += group * scale;
+            group = 0;
+            last_scale = *scale;
+            last = Last::Scale;
+        } else if *word == "and" {
+            // "one hundred and five": only between two parts of a number.
+            if !matches!(last, Last::Hundred | Last::Scale) || i + 1 == words.len() {
+                return Err(bad_words());
+            }
+        } else {
+            return Err(NumberError::UnknownWord(word.to_string()));
+        }
+    }
+
+    if last == Last::Nothing {
+        return Err(bad_words());
+    }
+    Ok(total + group)
+}
+
+// The text has no spaces and no accents: "duemilacentoventitre".
+fn italian(text: &str) -> Option<u64> {
+    if text == "zero" {
+        return Some(0);
+    }
+    italian_above_zero(text)
+}
+
+fn italian_above_zero(text: &str) -> Option<u64> {
+    for (one, many, scale) in ITALIAN_SCALES {
+        let (count, rest) = if let Some(rest) = text.strip_prefix(one) {
+            (1, rest)
+        } else if let Some(at) = text.find(many) {
+            let count = italian_below_1000(&text[..at]).filter(|&n| n >= 2)?;
+            (count, &text[at + many.len()..])
+        } else {
+            continue;
+        };
+        return Some(count * scale + italian_rest(rest, scale)?);
+    }
+
+    let (count, rest) = if let Some(rest) = text.strip_prefix("mille") {
+        (1, rest)
+    } else if let Some(at) = text.find("mila") {
+        let count = italian_below_1000(&text[..at]).filter(|&n| n >= 2)?;
+        (count, &text[at + "mila".len()..])
+    } else {
+        return italian_below_1000(text);
+    };
+    Some(count * 1000 + italian_rest(rest, 1000)?)
+}
+
+// What follows "mila", "milioni"...: nothing, or a smaller number.
+fn italian_rest(rest: &str, scale: u64) -> Option<u64> {
+    if rest.is_empty() {
+        return Some(0);
+    }
+    italian_above_zero(rest).filter(|&n| n < scale)
+}
+
+fn italian_below_1000(text: &str) -> Option<u64> {
+    for (hundreds, unit) in ITALIAN_ONES.iter().enumerate().take(10).skip(1) {
+        let word = match hundreds {
+            1 => String::from("cento"),
+            _ => format!("{unit}cento"),
+        };
+        for rest in strip_italian(text, &word) {
+            if rest.is_empty() {
+                return Some(hundreds as u64 * 100);
+            }
+            if let Some(n) = italian_below_100(rest) {
+                return Some(hundreds as u64 * 100 + n);
+            }
+        }
+    }
+    italian_below_100(text)
+}
+
+fn italian_below_100(text: &str) -> Option<u64> {
+    if let Some(n) = ITALIAN_ONES[1..].iter().position(|w| *w == text) {
+        return Some(n as u64 + 1);
+    }
+    for (i, tens) in ITALIAN_TENS.iter().enumerate() {
+        let value = (i as u64 + 2) * 10;
+        for rest in strip_italian(text, tens) {
+            if rest.is_empty() {
+                return Some(value);
+            }
+            if let Some(unit) = ITALIAN_ONES[1..10].iter().position(|w| *w == rest) {
+                return Some(value + unit as u64 + 1);
+            }
+        }
+    }
+    None
+}
+
+// What can follow a word, with and without its last vowel:
+// "venti" + "uno" is written "ventuno", "cento" + "otto" is "centotto".
+fn strip_italian<'a>(text: &'a str, word: &str) -> Vec<&'a str> {
+    let mut rests = Vec::new();
+    if let Some(rest) = text.strip_prefix(word) {
+        rests.push(rest);
+    }
+    if let Some(rest) = text.strip_prefix(&word[..word.len() - 1])
+        && (rest.starts_with('u') || rest.starts_with('o'))
+    {
+        rests.push(rest);
+    }
+    rests
+}
+
+fn english_words(mut number: u64) -> String {
+    if number == 0 {
+        return String::from("zero");
+    }
+
+    let mut parts = Vec::new();
+    for (name, scale) in ENGLISH_SCALES {
+        if number >= scale {
+            parts.push(format!("{} {name}", english_below_1000(number / scale)));
+            number %= scale;
+        }
+    }
+    if number > 0 {
+        parts.push(english_below_1000(number));
+    }
+    parts.join(" ")
+}
+
+fn english_below_1000(number: u64) -> String {
+    let (hundreds, rest) = (number / 100, number % 100);
+    let mut parts = Vec::new();
+    if hundreds > 0 {
+        parts.push(format!("{} hundred", ENGLISH_ONES[hundreds as usize]));
+    }
+    if rest >= 20 {
+        let tens = ENGLISH_TENS[rest as usize / 10 - 2];
+        match rest % 10 {
+            0 => parts.push(tens.to_string()),
+            unit => parts.push(format!("{tens}-{}", ENGLISH_ONES[unit as usize])),
+        }
+    } else if rest > 0 {
+        parts.push(ENGLISH_ONES[rest as usize].to_string());
+    }
+    parts.join(" ")
+}
+
+// Millions and billions are separate words ("due milioni trecentomila"),
+// everything below a million is one word.
+fn italian_words(mut number: u64) -> String {
+    if number == 0 {
+        return String::from("zero");
+    }
+
+    let mut parts = Vec::new();
+    for (name, scale) in [("miliard", 1_000_000_000), ("milion", 1_000_000)] {
+        let count = number / scale;
+        match count {
+            0 => {}
+            1 => parts.push(format!(
+                "un {name}{}",
+                if scale == 1_000_000 { "e" } else { "o" }
+            )),
+            _ => parts.push(format!("{} {name}i", italian_below_1000_words(count))),
+        }
+        number %= scale;
+    }
+
+    let mut below_million = String::new();
+    match number / 1000 {
+        0 => {}
+        1 => below_million.push_str("mille"),
+        // The accent of "tré" only goes on the last syllable of the whole word.
+        thousands => {
+            below_million.push_str(&italian_below_1000_words(thousands).replace('é', "e"));
+            below_million.push_str("mila");
+        }
+    }
+    below_million.push_str(&italian_below_1000_words(number % 1000));
+    if !below_million.is_empty() {
+        parts.push(below_million);
+    }
+    parts.join(" ")
+}
+
+fn italian_below_1000_words(number: u64) -> String {
+    let (hundreds, rest) = (number / 100, number % 100);
+    let mut word = match hundreds {
+        0 => String::new(),
+        1 => String::from("cento"),
+        _ => format!("{}cento", ITALIAN_ONES[hundreds as usize]),
+    };
+
+    let tail = if rest >= 20 {
+        let tens = ITALIAN_TENS[rest as usize / 10 - 2];
+        match rest % 10 {
+            0 => tens.to_string(),
+            // "ventuno", "ventotto": the vowels run together.
+            unit @ (1 | 8) => format!("{}{}", &tens[..tens.len() - 1], ITALIAN_ONES[unit as usize]),
+            3 => format!("{tens}tré"),
+            unit => format!("{tens}{}", ITALIAN_ONES[unit as usize]),
+        }
+    } else if rest > 0 {
+        ITALIAN_ONES[rest as usize].to_string()
+    } else {
+        String::new()
+    };
+
+    // "centotto", "centottanta".
+    if !word.is_empty() && tail.starts_with('o') {
+        word.pop();
+    }
+    word.push_str(&tail);
+    word
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_numbers_and_hex() {
+        assert_eq!(parse("42"), Ok(42));
+        assert_eq!(parse("  7 "), Ok(7));
+        assert_eq!(parse("0x2A"), Ok(42));
+        assert_eq!(parse("0Xff"), Ok(255));
+        assert_eq!(parse("0x"), Err(NumberError::BadHex(String::from("0x"))));
+        assert_eq!(
+            parse("0xzz"),
+            Err(NumberError::BadHex(String::from("0xzz")))
+        );
+        assert_eq!(
+            parse("99999999999"),
+            Err(NumberError::TooBig(String::from("99999999999")))
+        );
+    }
+
+    #[test]
+    fn english_words() {
+        assert_eq!(parse("forty two"), Ok(42));
+        assert_eq!(parse("Forty-Two"), Ok(42));
+        assert_eq!(parse("zero"), Ok(0));
+        assert_eq!(parse("seventeen"), Ok(17));
+        assert_eq!(parse("one hundred and five"), Ok(105));
+        assert_eq!(parse("two thousand twenty-six"), Ok(2026));
+        assert_eq!(parse("three million four hundred thousand"), Ok(3_400_000));
+
+        assert_eq!(
+            parse("two three"),
+            Err(NumberError::BadWords(String::from("two three")))
+        );
+        assert_eq!(
+            parse("thousand million"),
+            Err(NumberError::BadWords(String::from("thousand million")))
+        );
+        assert_eq!(
+            parse("banana"),
+            Err(NumberError::UnknownWord(String::from("banana")))
+        );
+    }
+
+    #[test]
+    fn italian_words() {
+        assert_eq!(parse("quarantadue"), Ok(42));
+        assert_eq!(parse("ventuno"), Ok(21));
+        assert_eq!(parse("ventotto"), Ok(28));
+        assert_eq!(parse("trentatré"), Ok(33));
+        assert_eq!(parse("cento"), Ok(100));
+        assert_eq!(parse("centotto"), Ok(108));
+        assert_eq!(parse("centottanta"), Ok(180));
+        assert_eq!(parse("novecentonovantanove"), Ok(999));
+        assert_eq!(parse("mille"), Ok(1000));
+        assert_eq!(parse("duemilaventisei"), Ok(2026));
+        assert_eq!(parse("un milione duecentomila"), Ok(1_200_000));
+        assert!(parse("quarantaquaranta").is_err());
+    }
+
+    #[test]
+    fn roman_numerals() {
+        assert_eq!(parse("XLII"), Ok(42));
+        assert_eq!(parse("mmxxvi"), Ok(2026));
+        assert_eq!(parse("MMMCMXCIX"), Ok(3999));
+        assert_eq!(
+            parse("IIII"),
+            Err(NumberError::BadRoman(String::from("IIII")))
+        );
+        assert_eq!(parse("IC"), Err(NumberError::BadRoman(String::from("IC"))));
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(parse("50+7"), Ok(57));
+        assert_eq!(parse("50 - 8"), Ok(42));
+        assert_eq!(parse("2+5*8"), Ok(42));
+        assert_eq!(parse("84/2"), Ok(42));
+        assert_eq!(parse("forty + 0x2"), Ok(42));
+        assert_eq!(parse("XL+II"), Ok(42));
+
+        assert_eq!(parse("7/0"), Err(NumberError::DivisionByZero));
+        assert_eq!(
+            parse("7/2"),
+            Err(NumberError::NotWhole(String::from("7/2")))
+        );
+        assert_eq!(
+            parse("5-9"),
+            Err(NumberError::Negative(String::from("5-9")))
+        );
+        assert_eq!(parse("-5"), Err(NumberError::Negative(String::from("-5"))));
+        assert_eq!(
+            parse("50+"),
+            Err(NumberError::Incomplete(String::from("50+")))
+        );
+        assert_eq!(
+            parse("65536*65536"),
+            Err(NumberError::TooBig(String::from("65536*65536")))
+        );
+    }
+
+    #[test]
+    fn other_lines_are_not_numbers() {
+        assert_eq!(parse(""), Err(NumberError::Empty));
+        assert_eq!(
+            parse("4.5"),
+            Err(NumberError::NotANumber(String::from("4.5")))
+        );
+        assert_eq!(
+            parse("12abc"),
+            Err(NumberError::NotANumber(String::from("12abc")))
+        );
+    }
+
+    #[test]
+    fn spells_numbers() {
+        assert_eq!(spell(0, "en"), "zero");
+        assert_eq!(spell(42, "en"), "forty-two");
+        assert_eq!(spell(105, "en"), "one hundred five");
+        assert_eq!(spell(2_000_017, "en"), "two million seventeen");
+
+        assert_eq!(spell(42, "it"), "quarantadue");
+        assert_eq!(spell(21, "it"), "ventuno");
+        assert_eq!(spell(33, "it"), "trentatré");
+        assert_eq!(spell(180, "it"), "centottanta");
+        assert_eq!(spell(1001, "it"), "milleuno");
+        assert_eq!(spell(23_000, "it"), "ventitremila");
+        assert_eq!(spell(2_300_000, "it"), "due milioni trecentomila");
+        assert_eq!(spell(1_000_000_000, "it"), "un miliardo");
+
+        assert_eq!(spell(7, "xx"), "seven");
+    }
+
+    #[test]
+    fn spelled_numbers_read_back() {
+        for number in (0..5000).chain([123_456, 1_000_001, 987_654_321, u32::MAX]) {
+            assert_eq!(
+                parse(&spell(number, "en")),
+                Ok(number),
+                "{number} in English"
+            );
+            assert_eq!(
+                parse(&spell(number, "it")),
+                Ok(number),
+                "{number} in Italian"
+            );
+        }
+    }
+
+    #[test]
+    fn reasons_are_translated() {
+        let english = NumberError::UnknownWord(String::from("banana")).message(&Catalog::english());
+        assert_eq!(
+            english,
+            "Please enter a valid number. 'banana' is not a number word."
+        );
+
+        let italian = NumberError::DivisionByZero.message(&Catalog::for_locale("it"));
+        assert!(italian.starts_with("Inserisci un numero valido."));
+    }
+}
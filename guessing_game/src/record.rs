@@ -0,0 +1,486 @@
+// Recording a game to an event log and playing it back.
+//
+// A log is a JSON-lines file: one TimedEvent per line, in the order things happened.
+// It starts with a Start event (range, seed, host...), then every raw input line the
+// player typed, including the ones that were rejected, every answer the host gave,
+// and finally an End event with the result and the secret.
+//
+// replay prints the game again, either one step at a time or at the speed it was played.
+// verify checks that the log tells a consistent story, e.g. that every "Too small!"
+// really was smaller than the secret revealed at the end.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+
+use crate::game::{self, GameResult, GameSettings};
+use crate::host::HostKind;
+use crate::i18n::Catalog;
+
+// Ordering doesn't implement Serialize, so the logs use this mirror of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Feedback {
+    Less,
+    Greater,
+    Equal,
+}
+
+impl From<Ordering> for Feedback {
+    fn from(ordering: Ordering) -> Feedback {
+        match ordering {
+            Ordering::Less => Feedback::Less,
+            Ordering::Greater => Feedback::Greater,
+            Ordering::Equal => Feedback::Equal,
+        }
+    }
+}
+
+impl Feedback {
+    // The word for the answer in the catalog.
+    fn key(self) -> &'static str {
+        match self {
+            Feedback::Less => "verify_less",
+            Feedback::Greater => "verify_greater",
+            Feedback::Equal => "verify_equal",
+        }
+    }
+}
+
+impl From<Feedback> for Ordering {
+    fn from(feedback: Feedback) -> Ordering {
+        match feedback {
+            Feedback::Less => Ordering::Less,
+            Feedback::Greater => Ordering::Greater,
+            Feedback::Equal => Ordering::Equal,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GameEvent {
+    Start {
+        low: u32,
+        high: u32,
+        max_attempts: Option<u32>,
+        host: HostKind,
+        seed: Option<u64>,
+        // Seconds since the Unix epoch.
+        started_at: u64,
+        // Only in timed games. Older logs don't have it.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        time_limit_ms: Option<u64>,
+    },
+    // A line exactly as it was typed, without the line ending.
+    Input {
+        line: String,
+    },
+    // The message shown when the line was rejected.
+    Invalid {
+        reason: String,
+    },
+    Feedback {
+        guess: u32,
+        feedback: Feedback,
+    },
+    // A timed turn that ran out before a valid guess: an attempt without a guess.
+    Missed,
+    // A hint the player bought, and how many points it cost.
+    Hint {
+        name: String,
+        cost: u32,
+        text: String,
+    },
+    End {
+        result: GameResult,
+        secret: u32,
+        attempts: u32,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimedEvent {
+    // Milliseconds since the start of the game.
+    pub at_ms: u64,
+    #[serde(flatten)]
+    pub event: GameEvent,
+}
+
+// Writes the events of one game as they happen.
+pub struct Recorder {
+    writer: Box<dyn Write>,
+    seed: Option<u64>,
+    started: Instant,
+}
+
+impl Recorder {
+    // The seed is only written to the log: GameSession never sees it, just the rng built from it.
+    pub fn new(writer: Box<dyn Write>, seed: Option<u64>) -> Recorder {
+        Recorder {
+            writer,
+            seed,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn create(path: &Path, seed: Option<u64>) -> io::Result<Recorder> {
+        Ok(Recorder::new(Box::new(File::create(path)?), seed))
+    }
+
+    pub fn start(&mut self, settings: &GameSettings) -> io::Result<()> {
+        self.started = Instant::now();
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        self.log(GameEvent::Start {
+            low: settings.low(),
+            high: settings.high(),
+            max_attempts: settings.max_attempts(),
+            host: settings.host(),
+            seed: self.seed,
+            started_at,
+            time_limit_ms: settings.time_limit().map(|limit| limit.as_millis() as u64),
+        })
+    }
+
+    pub fn log(&mut self, event: GameEvent) -> io::Result<()> {
+        let timed = TimedEvent {
+            at_ms: self.started.elapsed().as_millis() as u64,
+            event,
+        };
+        let json = serde_json::to_string(&timed).map_err(io::Error::other)?;
+        writeln!(self.writer, "{json}")?;
+        // Flush every line, so the log is useful even if the game is killed halfway.
+        self.writer.flush()
+    }
+}
+
+#[derive(Debug)]
+pub enum RecordError {
+    Io(io::Error),
+    Malformed {
+        line: usize,
+        error: serde_json::Error,
+    },
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordError::Io(e) => write!(f, "could not read the recording: {e}"),
+            RecordError::Malformed { line, error } => {
+                write!(
+                    f,
+                    "line {line} of the recording is not a valid event: {error}"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for RecordError {}
+
+impl From<io::Error> for RecordError {
+    fn from(e: io::Error) -> RecordError {
+        RecordError::Io(e)
+    }
+}
+
+pub fn read_log(path: &Path) -> Result<Vec<TimedEvent>, RecordError> {
+    parse_log(BufReader::new(File::open(path)?))
+}
+
+pub fn parse_log<R: BufRead>(reader: R) -> Result<Vec<TimedEvent>, RecordError> {
+    let mut events = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let event = serde_json::from_str(&line).map_err(|error| RecordError::Malformed {
+            line: index + 1,
+            error,
+        })?;
+        events.push(event);
+    }
+    Ok(events)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pace {
+    // Wait for Enter before every input line.
+    Step,
+    // Sleep as long as the player took.
+    Recorded,
+    // No waiting at all.
+    Instant,
+}
+
+impl Pace {
+    pub fn parse(name: &str) -> Result<Pace, String> {
+        match name.to_lowercase().as_str() {
+            "step" => Ok(Pace::Step),
+            "recorded" => Ok(Pace::Recorded),
+            "instant" => Ok(Pace::Instant),
+            other => Err(format!(
+                "unknown pace '{other}', expected step, recorded or instant"
+            )),
+        }
+    }
+}
+
+// Prints the recorded game the way it looked while it was played, in the catalog's language.
+// In Step mode the controls are read from `controls`: Enter shows the next input, q stops.
+// What the player typed wrong is shown as it was recorded, in the language of the game.
+pub fn replay<C: BufRead, W: Write>(
+    events: &[TimedEvent],
+    pace: Pace,
+    mut controls: C,
+    mut output: W,
+    catalog: &Catalog,
+) -> io::Result<()> {
+    let mut last_ms = 0;
+
+    for timed in events {
+        let at = format!("[{:>7.1}s]", timed.at_ms as f64 / 1000.0);
+        match &timed.event {
+            GameEvent::Start {
+                low,
+                high,
+                max_attempts,
+                host,
+                seed,
+                time_limit_ms,
+                ..
+            } => {
+                writeln!(
+                    output,
+                    "{}",
+                    catalog.format("replay_start", &[("low", low), ("high", high)])
+                )?;
+                if let Some(limit) = time_limit_ms {
+                    let seconds = format!("{:.1}", *limit as f64 / 1000.0);
+                    writeln!(
+                        output,
+                        "{}",
+                        catalog.format("replay_time_limit", &[("seconds", &seconds)])
+                    )?;
+                }
+                if let Some(max) = max_attempts {
+                    writeln!(
+                        output,
+                        "{}",
+                        catalog.format("replay_attempts", &[("max", max)])
+                    )?;
+                }
+                if *host == HostKind::Evil {
+                    writeln!(output, "{}", catalog.text("replay_evil_host"))?;
+                }
+                if let Some(seed) = seed {
+                    writeln!(
+                        output,
+                        "{}",
+                        catalog.format("replay_seed", &[("seed", seed)])
+                    )?;
+                }
+            }
+            GameEvent::Input { line } => {
+                match pace {
+                    Pace::Step => {
+                        write!(output, "{}", catalog.text("replay_controls"))?;
+                        output.flush()?;
+                        let mut answer = String::new();
+                        if controls.read_line(&mut answer)? == 0 || answer.trim() == "q" {
+                            return Ok(());
+                        }
+                    }
+                    Pace::Recorded => {
+                        thread::sleep(Duration::from_millis(timed.at_ms.saturating_sub(last_ms)))
+                    }
+                    Pace::Instant => {}
+                }
+                writeln!(
+                    output,
+                    "{at} {}",
+                    catalog.format("you_guessed", &[("line", line)])
+                )?;
+            }
+            GameEvent::Invalid { reason } => writeln!(output, "{reason}")?,
+            GameEvent::Missed => writeln!(output, "{at} {}", catalog.text("timed_out"))?,
+            GameEvent::Hint { name, cost, text } => writeln!(
+                output,
+                "{}",
+                catalog.format(
+                    "replay_hint",
+                    &[("name", name), ("cost", cost), ("text", text)]
+                )
+            )?,
+            GameEvent::Feedback { feedback, .. } => {
+                let key = match feedback {
+                    Feedback::Less => "too_small",
+                    Feedback::Greater => "too_big",
+                    Feedback::Equal => "you_win",
+                };
+                writeln!(output, "{}", catalog.text(key))?
+            }
+            GameEvent::End {
+                result,
+                secret,
+                attempts,
+            } => {
+                let key = match result {
+                    GameResult::Won => "replay_won",
+                    GameResult::Lost => "replay_lost",
+                    GameResult::Abandoned => "replay_abandoned",
+                };
+                writeln!(
+                    output,
+                    "{}",
+                    catalog.format(key, &[("attempts", attempts), ("secret", secret)])
+                )?;
+            }
+        }
+        last_ms = timed.at_ms;
+    }
+
+    Ok(())
+}
+
+fn result_key(result: GameResult) -> &'static str {
+    match result {
+        GameResult::Won => "verify_won",
+        GameResult::Lost => "verify_lost",
+        GameResult::Abandoned => "verify_abandoned",
+    }
+}
+
+// Checks a recording and returns every problem found, in the catalog's language.
+// An empty list means the log is consistent.
+pub fn verify(events: &[TimedEvent], catalog: &Catalog) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    let Some(TimedEvent {
+        event:
+            GameEvent::Start {
+                low,
+                high,
+                max_attempts,
+                host,
+                seed,
+                ..
+            },
+        ..
+    }) = events.first()
+    else {
+        return vec![catalog.text("verify_no_start")];
+    };
+
+    let Some(TimedEvent {
+        event:
+            GameEvent::End {
+                result,
+                secret,
+                attempts,
+            },
+        ..
+    }) = events.last()
+    else {
+        return vec![catalog.text("verify_no_end")];
+    };
+
+    if !(low..=high).contains(&secret) {
+        problems.push(catalog.format(
+            "verify_secret_out_of_range",
+            &[("secret", secret), ("low", low), ("high", high)],
+        ));
+    }
+
+    // A fair host draws the secret first thing, so with the seed we can check it was not swapped.
+    if let (HostKind::Fair, Some(seed), Ok(settings)) =
+        (host, seed, GameSettings::new(*low, *high, *max_attempts))
+    {
+        let expected = game::draw_secret(&mut StdRng::seed_from_u64(*seed), &settings);
+        if expected != *secret {
+            problems.push(catalog.format(
+                "verify_wrong_seed",
+                &[("seed", seed), ("expected", &expected), ("secret", secret)],
+            ));
+        }
+    }
+
+    let mut counted = 0;
+    let mut last_feedback = None;
+    for (index, timed) in events.iter().enumerate() {
+        let event = index + 1;
+        match &timed.event {
+            GameEvent::Start { .. } if index != 0 => {
+                problems.push(catalog.format("verify_second_start", &[("event", &event)]))
+            }
+            GameEvent::End { .. } if index != events.len() - 1 => {
+                problems.push(catalog.format("verify_early_end", &[("event", &event)]))
+            }
+            GameEvent::Missed => counted += 1,
+            GameEvent::Feedback { guess, feedback } => {
+                counted += 1;
+                last_feedback = Some(*feedback);
+                if !(low..=high).contains(&guess) {
+                    problems.push(catalog.format(
+                        "verify_answered_out_of_range",
+                        &[("event", &event), ("guess", guess)],
+                    ));
+                }
+                let expected = Feedback::from(guess.cmp(secret));
+                if *feedback != expected {
+                    let args: [(&str, &dyn fmt::Display); 5] = [
+                        ("event", &event),
+                        ("guess", guess),
+                        ("feedback", &catalog.text(feedback.key())),
+                        ("secret", secret),
+                        ("expected", &catalog.text(expected.key())),
+                    ];
+                    problems.push(catalog.format("verify_wrong_answer", &args));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if counted != *attempts {
+        problems.push(catalog.format(
+            "verify_wrong_attempts",
+            &[("attempts", attempts), ("counted", &counted)],
+        ));
+    }
+
+    let won = last_feedback == Some(Feedback::Equal);
+    let out_of_attempts = max_attempts.is_some_and(|max| counted >= max);
+    let expected_result = if won {
+        GameResult::Won
+    } else if out_of_attempts {
+        GameResult::Lost
+    } else {
+        GameResult::Abandoned
+    };
+    if *result != expected_result {
+        problems.push(catalog.format(
+            "verify_wrong_result",
+            &[
+                ("result", &catalog.text(result_key(*result))),
+                ("expected", &catalog.text(result_key(expected_result))),
+            ],
+        ));
+    }
+
+    problems
+}
@@ -0,0 +1,206 @@
+// Computer players for the guessing game.
+//
+// A Strategy only sees what a human sees: the range at the start
+// and then the Ordering of each guess compared with the secret,
+// i.e. guess.cmp(&secret) -> Less ("Too small!"), Greater ("Too big!") or Equal ("You win!").
+
+use std::cmp::Ordering;
+
+use rand::Rng;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+pub trait Strategy {
+    fn name(&self) -> &str;
+
+    // Called before every game with the inclusive range of the secret.
+    fn start(&mut self, low: u32, high: u32);
+
+    fn next_guess(&mut self) -> u32;
+
+    // The feedback the game gave for the last guess.
+    fn feedback(&mut self, guess: u32, ordering: Ordering);
+}
+
+// Always guesses the middle of the numbers that are still possible,
+// halving them after every answer.
+#[derive(Debug, Default)]
+pub struct BinarySearch {
+    low: u32,
+    high: u32,
+}
+
+impl BinarySearch {
+    pub fn new() -> BinarySearch {
+        BinarySearch::default()
+    }
+}
+
+impl Strategy for BinarySearch {
+    fn name(&self) -> &str {
+        "binary"
+    }
+
+    fn start(&mut self, low: u32, high: u32) {
+        self.low = low;
+        self.high = high;
+    }
+
+    fn next_guess(&mut self) -> u32 {
+        self.low + (self.high - self.low) / 2
+    }
+
+    fn feedback(&mut self, guess: u32, ordering: Ordering) {
+        narrow(&mut self.low, &mut self.high, guess, ordering);
+    }
+}
+
+// Picks a random number among the ones still possible.
+// It never repeats a guess it already knows is wrong, but it is not as clever as BinarySearch.
+pub struct RandomGuess<R> {
+    rng: R,
+    low: u32,
+    high: u32,
+}
+
+impl<R: Rng> RandomGuess<R> {
+    pub fn new(rng: R) -> RandomGuess<R> {
+        RandomGuess {
+            rng,
+            low: 0,
+            high: 0,
+        }
+    }
+}
+
+impl<R: Rng> Strategy for RandomGuess<R> {
+    fn name(&self) -> &str {
+        "random"
+    }
+
+    fn start(&mut self, low: u32, high: u32) {
+        self.low = low;
+        self.high = high;
+    }
+
+    fn next_guess(&mut self) -> u32 {
+        self.rng.gen_range(self.low..=self.high)
+    }
+
+    fn feedback(&mut self, guess: u32, ordering: Ordering) {
+        narrow(&mut self.low, &mut self.high, guess, ordering);
+    }
+}
+
+// Counts up from the bottom of the range. The feedback is only ever "Too small!"
+// until it hits the secret, so this is the worst sensible strategy.
+#[derive(Debug, Default)]
+pub struct Linear {
+    next: u32,
+}
+
+impl Linear {
+    pub fn new() -> Linear {
+        Linear::default()
+    }
+}
+
+impl Strategy for Linear {
+    fn name(&self) -> &str {
+        "linear"
+    }
+
+    fn start(&mut self, low: u32, _high: u32) {
+        self.next = low;
+    }
+
+    fn next_guess(&mut self) -> u32 {
+        self.next
+    }
+
+    fn feedback(&mut self, guess: u32, _ordering: Ordering) {
+        self.next = guess + 1;
+    }
+}
+
+// Shrinks [low, high] to the numbers that are still consistent with the answer.
+fn narrow(low: &mut u32, high: &mut u32, guess: u32, ordering: Ordering) {
+    match ordering {
+        Ordering::Less => *low = guess + 1,
+        Ordering::Greater => *high = guess.saturating_sub(1),
+        Ordering::Equal => {
+            *low = guess;
+            *high = guess;
+        }
+    }
+}
+
+// The strategies that can be picked from the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrategyKind {
+    Binary,
+    Random,
+    Linear,
+}
+
+impl StrategyKind {
+    pub const ALL: [StrategyKind; 3] = [
+        StrategyKind::Binary,
+        StrategyKind::Random,
+        StrategyKind::Linear,
+    ];
+
+    pub fn parse(name: &str) -> Result<StrategyKind, String> {
+        match name.to_lowercase().as_str() {
+            "binary" => Ok(StrategyKind::Binary),
+            "random" => Ok(StrategyKind::Random),
+            "linear" => Ok(StrategyKind::Linear),
+            other => Err(format!(
+                "unknown strategy '{other}', expected binary, random, linear or all"
+            )),
+        }
+    }
+
+    // The seed is only used by the random strategy. It is scrambled first: autoplay draws
+    // the secrets from the same seed, and a StdRng seeded the same way would
+    // guess every secret on the first try.
+    pub fn build(&self, seed: u64) -> Box<dyn Strategy> {
+        match self {
+            StrategyKind::Binary => Box::new(BinarySearch::new()),
+            StrategyKind::Random => Box::new(RandomGuess::new(StdRng::seed_from_u64(
+                seed ^ 0x9E37_79B9_7F4A_7C15,
+            ))),
+            StrategyKind::Linear => Box::new(Linear::new()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::autoplay::autoplay_game;
+
+    #[test]
+    fn binary_search_starts_in_the_middle() {
+        let mut binary = BinarySearch::new();
+        binary.start(1, 100);
+        assert_eq!(binary.next_guess(), 50);
+        binary.feedback(50, Ordering::Less);
+        assert_eq!(binary.next_guess(), 75);
+    }
+
+    #[test]
+    fn linear_needs_one_guess_per_number() {
+        let mut linear = Linear::new();
+        assert_eq!(autoplay_game(&mut linear, 1, 100, 1), 1);
+        assert_eq!(autoplay_game(&mut linear, 1, 100, 100), 100);
+    }
+
+    #[test]
+    fn random_only_guesses_possible_numbers() {
+        let mut random = RandomGuess::new(StdRng::seed_from_u64(1));
+        for secret in 1..=100 {
+            assert!(autoplay_game(&mut random, 1, 100, secret) <= 100);
+        }
+    }
+}
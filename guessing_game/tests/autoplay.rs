@@ -0,0 +1,62 @@
+use guessing_game::GameSettings;
+use guessing_game::autoplay::{self, optimal_worst_case};
+use guessing_game::strategy::{BinarySearch, StrategyKind};
+
+// Not sampled: binary search plays against every possible secret.
+fn binary_worst_case(low: u32, high: u32) -> u32 {
+    let mut binary = BinarySearch::new();
+    (low..=high)
+        .map(|secret| autoplay::autoplay_game(&mut binary, low, high, secret))
+        .max()
+        .unwrap()
+}
+
+#[test]
+fn binary_search_always_wins_within_the_optimal_bound() {
+    for (low, high) in [(1, 50), (1, 100), (1, 128), (1, 1000), (500, 510)] {
+        let size = high - low + 1;
+        assert_eq!(
+            binary_worst_case(low, high),
+            optimal_worst_case(size),
+            "range {low}-{high}"
+        );
+    }
+}
+
+#[test]
+fn presets_give_binary_search_enough_attempts() {
+    for difficulty in ["easy", "normal", "hard"] {
+        let difficulty = guessing_game::Difficulty::parse(difficulty).unwrap();
+        let (low, high) = difficulty.range();
+        assert!(binary_worst_case(low, high) <= difficulty.max_attempts());
+    }
+}
+
+#[test]
+fn same_seed_same_report() {
+    let settings = GameSettings::default();
+    for kind in StrategyKind::ALL {
+        let first = autoplay::run_benchmark(kind.build(3).as_mut(), &settings, 200, 3);
+        let second = autoplay::run_benchmark(kind.build(3).as_mut(), &settings, 200, 3);
+        assert_eq!(first, second);
+        assert_eq!(first.histogram.values().sum::<u32>(), 200);
+    }
+}
+
+#[test]
+fn binary_beats_linear_on_average() {
+    let settings = GameSettings::default();
+    let binary = autoplay::run_benchmark(StrategyKind::Binary.build(1).as_mut(), &settings, 500, 1);
+    let linear = autoplay::run_benchmark(StrategyKind::Linear.build(1).as_mut(), &settings, 500, 1);
+
+    assert!(binary.mean < linear.mean);
+    assert!(binary.worst <= 7);
+}
+
+#[test]
+fn random_does_not_know_the_secrets() {
+    let settings = GameSettings::default();
+    let random = autoplay::run_benchmark(StrategyKind::Random.build(9).as_mut(), &settings, 500, 9);
+
+    assert!(random.mean > 2.0, "mean was {}", random.mean);
+}
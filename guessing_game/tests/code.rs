@@ -0,0 +1,84 @@
+use guessing_game::code::{self, CodeRules, MinimaxSolver};
+use guessing_game::{Catalog, GameResult};
+
+mod common;
+
+fn play(rules: CodeRules, seed: u64, script: &str) -> (code::CodeOutcome, String) {
+    let mut output = Vec::new();
+    let outcome = code::play(
+        rules,
+        &mut common::seeded(seed),
+        script.as_bytes(),
+        &mut output,
+        &Catalog::english(),
+    )
+    .expect("in-memory input and output should not fail");
+    (outcome, String::from_utf8(output).unwrap())
+}
+
+#[test]
+fn breaks_the_code_with_the_shared_game_loop() {
+    let rules = CodeRules::bulls_and_cows();
+    let secret = rules.draw_secret(&mut common::seeded(4));
+    let (outcome, output) = play(rules, 4, &format!("12\n1123\n{secret}\n"));
+
+    assert_eq!(outcome.result, GameResult::Won);
+    assert_eq!(outcome.attempts(), 1);
+    assert!(output.contains("The code has 4 digits, you entered 2."));
+    assert!(output.contains("The digits of the code are all different."));
+    assert!(
+        output.ends_with("You win!\n"),
+        "unexpected output: {output}"
+    );
+}
+
+#[test]
+fn running_out_of_attempts_reveals_the_code() {
+    let rules = CodeRules::mastermind().with_max_attempts(Some(2));
+    let secret = rules.draw_secret(&mut common::seeded(8));
+    let wrong = if secret.to_string() == "0000" {
+        "1111"
+    } else {
+        "0000"
+    };
+    let (outcome, output) = play(rules, 8, &format!("{wrong}\n{wrong}\n"));
+
+    assert_eq!(outcome.result, GameResult::Lost);
+    assert_eq!(outcome.scores.len(), 2);
+    assert!(output.contains("Attempts left: 1"));
+    assert!(output.contains(&format!("You lose! The code was {secret}.")));
+}
+
+#[test]
+fn minimax_breaks_every_mastermind_code_in_five_guesses() {
+    let rules = CodeRules::mastermind();
+    let mut solver = MinimaxSolver::new(rules);
+
+    for secret in rules.all_codes() {
+        let guesses = solver.solve(&secret);
+        assert_eq!(guesses.last(), Some(&secret));
+        assert!(
+            guesses.len() as u32 <= rules.known_worst_case().unwrap(),
+            "{secret} took {} guesses",
+            guesses.len()
+        );
+    }
+}
+
+// All 5040 codes take a while without optimisations: cargo test --release -- --ignored
+#[test]
+#[ignore]
+fn minimax_breaks_every_bulls_and_cows_code_in_seven_guesses() {
+    let rules = CodeRules::bulls_and_cows();
+    let mut solver = MinimaxSolver::new(rules);
+
+    for secret in rules.all_codes() {
+        let guesses = solver.solve(&secret);
+        assert_eq!(guesses.last(), Some(&secret));
+        assert!(
+            guesses.len() as u32 <= rules.known_worst_case().unwrap(),
+            "{secret} took {} guesses",
+            guesses.len()
+        );
+    }
+}
@@ -0,0 +1,39 @@
+// Every file in tests/ is its own crate and uses only some of these helpers.
+#![allow(dead_code)]
+
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+pub fn seeded(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+
+// The secret a fresh session draws first from seeded(seed).
+pub fn secret_for_seed(seed: u64) -> u32 {
+    seeded(seed).gen_range(1..=100)
+}
+
+// A Write that can still be read after whoever owns it as a Box<dyn Write>
+// (a Recorder, a JsonReporter) is gone.
+#[derive(Clone, Default)]
+pub struct SharedBuffer(pub Rc<RefCell<Vec<u8>>>);
+
+impl SharedBuffer {
+    pub fn contents(&self) -> String {
+        String::from_utf8(self.0.borrow().clone()).unwrap()
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
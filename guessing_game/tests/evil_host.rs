@@ -0,0 +1,86 @@
+use std::cmp::Ordering;
+
+use guessing_game::autoplay::{self, optimal_worst_case};
+use guessing_game::strategy::{BinarySearch, Linear};
+use guessing_game::{EvilHost, GameResult, GameSession, GameSettings, Host, HostKind, Strategy};
+
+mod common;
+
+fn evil_settings(low: u32, high: u32, max_attempts: Option<u32>) -> GameSettings {
+    GameSettings::new(low, high, max_attempts)
+        .unwrap()
+        .with_host(HostKind::Evil)
+}
+
+#[test]
+fn binary_search_is_forced_into_its_worst_case() {
+    for (low, high) in [(1, 10), (1, 100), (1, 1000)] {
+        let mut host = EvilHost::new(low, high);
+        let attempts = autoplay::autoplay_against(&mut BinarySearch::new(), &mut host, low, high);
+        assert_eq!(attempts, optimal_worst_case(high - low + 1));
+    }
+}
+
+#[test]
+fn linear_has_to_try_every_number() {
+    let mut host = EvilHost::new(1, 100);
+    let attempts = autoplay::autoplay_against(&mut Linear::new(), &mut host, 1, 100);
+    assert_eq!(attempts, 100);
+}
+
+#[test]
+fn a_lucky_first_guess_never_wins() {
+    for guess in 1..=100 {
+        let mut host = EvilHost::new(1, 100);
+        assert_ne!(host.judge(guess), Ordering::Equal);
+    }
+}
+
+#[test]
+fn revealed_secret_matches_every_answer() {
+    let script = "50\n20\n80\n81\n99\n3\n";
+    let mut output = Vec::new();
+    let outcome = GameSession::with_settings(
+        evil_settings(1, 100, Some(5)),
+        common::seeded(1),
+        script.as_bytes(),
+        &mut output,
+    )
+    .play()
+    .unwrap();
+
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(outcome.result, GameResult::Lost);
+    assert!(outcome.is_consistent());
+    assert!(output.contains(&format!("The secret number was {}.", outcome.secret)));
+}
+
+#[test]
+fn perfect_play_still_wins_against_the_evil_host() {
+    // Feed the session the guesses a binary search would make against the evil host.
+    let mut host = EvilHost::new(1, 100);
+    let mut binary = BinarySearch::new();
+    binary.start(1, 100);
+    let mut script = String::new();
+    loop {
+        let guess = binary.next_guess();
+        script.push_str(&format!("{guess}\n"));
+        match host.judge(guess) {
+            Ordering::Equal => break,
+            answer => binary.feedback(guess, answer),
+        }
+    }
+
+    let outcome = GameSession::with_settings(
+        evil_settings(1, 100, Some(7)),
+        common::seeded(2),
+        script.as_bytes(),
+        Vec::new(),
+    )
+    .play()
+    .unwrap();
+
+    assert_eq!(outcome.result, GameResult::Won);
+    assert_eq!(outcome.attempts, 7);
+    assert!(outcome.is_consistent());
+}
@@ -0,0 +1,205 @@
+use guessing_game::{
+    Catalog, GameOutcome, GameResult, GameSession, GameSettings, HintBook, HintKind,
+};
+
+mod common;
+
+fn play(seed: u64, script: &str) -> (GameOutcome, String) {
+    let mut output = Vec::new();
+    let outcome = GameSession::new(common::seeded(seed), script.as_bytes(), &mut output)
+        .play()
+        .expect("in-memory input and output should not fail");
+    (outcome, String::from_utf8(output).unwrap())
+}
+
+#[test]
+fn wins_on_the_first_guess() {
+    let secret = common::secret_for_seed(7);
+    let (outcome, output) = play(7, &format!("{secret}\n"));
+
+    assert_eq!(outcome.result, GameResult::Won);
+    assert_eq!(outcome.attempts, 1);
+    assert_eq!(outcome.guesses, vec![secret]);
+    assert_eq!(outcome.secret, secret);
+    assert!(
+        output.ends_with("You win!\nYour score: 1000\n"),
+        "unexpected output: {output}"
+    );
+}
+
+#[test]
+fn reports_too_small_and_too_big() {
+    let secret = common::secret_for_seed(42);
+    let script = format!("{}\n{}\n{secret}\n", secret - 1, secret + 1);
+    let (outcome, output) = play(42, &script);
+
+    assert_eq!(outcome.guesses, vec![secret - 1, secret + 1, secret]);
+    assert_eq!(outcome.attempts, 3);
+    assert!(output.contains("Too small!"));
+    assert!(output.contains("Too big!"));
+}
+
+#[test]
+fn invalid_lines_are_not_counted() {
+    let secret = common::secret_for_seed(3);
+    let (outcome, output) = play(3, &format!("banana\n\n{secret}\n"));
+
+    assert_eq!(outcome.attempts, 1);
+    assert_eq!(output.matches("Please enter a valid number.").count(), 2);
+}
+
+#[test]
+fn running_out_of_input_abandons_the_game() {
+    let secret = common::secret_for_seed(9);
+    let wrong = if secret == 1 { 2 } else { 1 };
+    let (outcome, _) = play(9, &format!("{wrong}\n"));
+
+    assert_eq!(outcome.result, GameResult::Abandoned);
+    assert_eq!(outcome.guesses, vec![wrong]);
+}
+
+#[test]
+fn same_seed_same_secret() {
+    let (first, _) = play(11, "");
+    let (second, _) = play(11, "");
+
+    assert_eq!(first.secret, second.secret);
+}
+
+fn play_with(settings: GameSettings, seed: u64, script: &str) -> (GameOutcome, String) {
+    let mut output = Vec::new();
+    let outcome = GameSession::with_settings(
+        settings,
+        common::seeded(seed),
+        script.as_bytes(),
+        &mut output,
+    )
+    .play()
+    .expect("in-memory input and output should not fail");
+    (outcome, String::from_utf8(output).unwrap())
+}
+
+#[test]
+fn secret_is_hidden_unless_revealed() {
+    let (_, output) = play(5, "");
+    assert!(!output.contains("The secret number is"));
+
+    let settings = GameSettings::default().with_reveal(true);
+    let (outcome, output) = play_with(settings, 5, "");
+    assert!(output.contains(&format!("The secret number is: {}", outcome.secret)));
+}
+
+#[test]
+fn running_out_of_attempts_loses() {
+    let settings = GameSettings::new(1, 100, Some(2)).unwrap();
+    let secret = common::secret_for_seed(21);
+    let wrong: Vec<u32> = (1..=100).filter(|n| *n != secret).take(3).collect();
+    let script = format!("{}\n{}\n{}\n", wrong[0], wrong[1], wrong[2]);
+
+    let (outcome, output) = play_with(settings, 21, &script);
+
+    assert_eq!(outcome.result, GameResult::Lost);
+    assert_eq!(outcome.guesses, vec![wrong[0], wrong[1]]);
+    assert!(output.contains(&format!("You lose! The secret number was {secret}.")));
+}
+
+#[test]
+fn out_of_range_guesses_are_rejected_and_not_counted() {
+    let settings = GameSettings::new(1, 100, Some(1)).unwrap();
+    let secret = common::secret_for_seed(8);
+    let (outcome, output) = play_with(settings, 8, &format!("0\n101\n{secret}\n"));
+
+    assert_eq!(outcome.result, GameResult::Won);
+    assert_eq!(outcome.attempts, 1);
+    assert!(output.contains("101 is out of range. Please enter a number between 1 and 100."));
+    assert!(!output.contains("Too big!"));
+}
+
+#[test]
+fn custom_range_draws_the_secret_inside_it() {
+    for seed in 0..50 {
+        let settings = GameSettings::new(500, 510, None).unwrap();
+        let (outcome, _) = play_with(settings, seed, "");
+        assert!((500..=510).contains(&outcome.secret));
+    }
+}
+
+fn play_with_hints(seed: u64, budget: u32, script: &str) -> (GameOutcome, String) {
+    let mut output = Vec::new();
+    let outcome = GameSession::new(common::seeded(seed), script.as_bytes(), &mut output)
+        .with_hints(HintBook::from_kinds(&HintKind::ALL, budget))
+        .play()
+        .expect("in-memory input and output should not fail");
+    (outcome, String::from_utf8(output).unwrap())
+}
+
+#[test]
+fn hints_cost_points_but_not_attempts() {
+    let secret = common::secret_for_seed(21);
+    let parity = if secret.is_multiple_of(2) {
+        "even"
+    } else {
+        "odd"
+    };
+    let (outcome, output) = play_with_hints(21, 5, &format!("hint\nhint parity\n{secret}\n"));
+
+    assert!(output.contains("hint digit-sum (2 points)"));
+    assert!(output.contains(&format!("The number is {parity}. (4 hint points left)")));
+    assert_eq!(outcome.attempts, 1);
+    assert_eq!(outcome.hint_points, 1);
+    assert_eq!(outcome.score(), 975);
+}
+
+#[test]
+fn hints_stop_when_the_budget_is_spent() {
+    let secret = common::secret_for_seed(22);
+    let script = format!("hint digit-sum\nhint parity\nhint warmer-colder\n{secret}\n");
+    let (outcome, output) = play_with_hints(22, 2, &script);
+
+    assert!(output.contains("costs 1 points, but you only have 0 left"));
+    assert_eq!(outcome.hint_points, 2);
+    assert_eq!(outcome.attempts, 1);
+}
+
+#[test]
+fn without_hints_hint_is_just_an_invalid_guess() {
+    let secret = common::secret_for_seed(23);
+    let (outcome, output) = play(23, &format!("hint\n{secret}\n"));
+
+    assert!(output.contains("Please enter a valid number."));
+    assert_eq!(outcome.hint_points, 0);
+}
+
+#[test]
+fn speaks_italian() {
+    let secret = common::secret_for_seed(31);
+    let script = format!("{}\nboh\n{secret}\n", secret + 1);
+    let mut output = Vec::new();
+    GameSession::new(common::seeded(31), script.as_bytes(), &mut output)
+        .with_catalog(Catalog::for_locale("it"))
+        .play()
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.starts_with("Indovina il numero!\nIl numero è compreso tra 1 e 100.\n"));
+    assert!(output.contains("Troppo grande!"));
+    assert!(output.contains("Inserisci un numero valido."));
+    assert!(output.contains("Hai vinto!"));
+}
+
+#[test]
+fn accepts_words_numerals_and_sums() {
+    let secret = common::secret_for_seed(11);
+    let wrong = if secret == 1 { 2 } else { 1 };
+    let script = format!("0x{wrong:x}\nforty two and\n{}+1\n", secret - 1);
+    let (outcome, output) = play(11, &script);
+
+    assert_eq!(outcome.result, GameResult::Won);
+    assert_eq!(outcome.guesses, vec![wrong, secret]);
+    assert!(output.contains(&format!("You guessed: 0x{wrong:x} (")));
+    assert!(
+        output.contains(
+            "Please enter a valid number. The words 'forty two and' don't make a number."
+        )
+    );
+}
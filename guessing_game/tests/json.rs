@@ -0,0 +1,99 @@
+use std::io;
+
+use guessing_game::json::{JsonMessage, JsonReporter};
+use guessing_game::record::Feedback;
+use guessing_game::{GameResult, GameSession, GameSettings};
+
+mod common;
+
+use common::SharedBuffer;
+
+fn play_json(settings: GameSettings, seed: u64, script: &str) -> Vec<JsonMessage> {
+    let out = SharedBuffer::default();
+    GameSession::with_settings(
+        settings,
+        common::seeded(seed),
+        script.as_bytes(),
+        io::sink(),
+    )
+    .report_to(JsonReporter::new(Box::new(out.clone())))
+    .play()
+    .unwrap();
+
+    // Every line has to be a JSON object on its own.
+    out.contents()
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("every line is a message"))
+        .collect()
+}
+
+#[test]
+fn a_whole_game_in_json() {
+    let secret = common::secret_for_seed(8);
+    let wrong = if secret == 1 { 2 } else { 1 };
+    let script = format!("{{\"guess\": {wrong}}}\n{{\"guess\": {secret}}}\n");
+    let settings = GameSettings::new(1, 100, Some(7)).unwrap();
+    let messages = play_json(settings, 8, &script);
+
+    assert_eq!(
+        messages,
+        vec![
+            JsonMessage::Start {
+                low: 1,
+                high: 100,
+                max_attempts: Some(7)
+            },
+            JsonMessage::Prompt {
+                attempt: 1,
+                attempts_left: Some(7)
+            },
+            JsonMessage::Feedback {
+                guess: wrong,
+                feedback: Feedback::Less
+            },
+            JsonMessage::Prompt {
+                attempt: 2,
+                attempts_left: Some(6)
+            },
+            JsonMessage::Feedback {
+                guess: secret,
+                feedback: Feedback::Equal
+            },
+            JsonMessage::GameOver {
+                result: GameResult::Won,
+                secret,
+                attempts: 2,
+                score: 900
+            },
+        ]
+    );
+}
+
+#[test]
+fn bad_lines_are_invalid_input() {
+    let secret = common::secret_for_seed(9);
+    let script = format!("42\n{{\"guess\": 500}}\n{{\"guess\": {secret}}}\n");
+    let messages = play_json(GameSettings::default(), 9, &script);
+
+    let invalid: Vec<&JsonMessage> = messages
+        .iter()
+        .filter(|m| matches!(m, JsonMessage::InvalidInput { .. }))
+        .collect();
+    assert_eq!(invalid.len(), 2);
+    assert!(matches!(
+        invalid[0],
+        JsonMessage::InvalidInput { line, reason } if line == "42" && reason.starts_with("expected {\"guess\"")
+    ));
+    assert!(matches!(
+        invalid[1],
+        JsonMessage::InvalidInput { reason, .. } if reason.contains("out of range")
+    ));
+    assert!(matches!(
+        messages.last(),
+        Some(JsonMessage::GameOver {
+            result: GameResult::Won,
+            attempts: 1,
+            ..
+        })
+    ));
+}
@@ -0,0 +1,157 @@
+use std::fs;
+use std::path::PathBuf;
+
+use guessing_game::leaderboard::LeaderboardError;
+use guessing_game::{Catalog, GameRecord, GameResult, Leaderboard};
+
+// Each test gets its own file in the system temp directory,
+// so the tests can run in parallel without stepping on each other.
+fn temp_file(name: &str) -> PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("guessing_game_{}_{name}.json", std::process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn record(player: &str, attempts: u32) -> GameRecord {
+    GameRecord {
+        player: player.to_string(),
+        low: 1,
+        high: 100,
+        attempts,
+        duration_ms: 2500,
+        result: GameResult::Won,
+        finished_at: 1_700_000_000,
+        hint_points: 0,
+        score: 0,
+        guess_times_ms: Vec::new(),
+        daily: None,
+    }
+}
+
+#[test]
+fn missing_file_is_an_empty_leaderboard() {
+    let path = temp_file("missing");
+
+    let board = Leaderboard::load(&path).unwrap();
+
+    assert!(board.is_empty());
+}
+
+#[test]
+fn records_survive_a_save_and_load() {
+    let path = temp_file("roundtrip");
+    let mut board = Leaderboard::new();
+    board.record(record("ada", 4));
+    board.record(record("bob", 6));
+
+    board.save(&path).unwrap();
+    let loaded = Leaderboard::load(&path).unwrap();
+
+    assert_eq!(loaded, board);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn corrupted_file_is_moved_aside() {
+    let path = temp_file("corrupted");
+    fs::write(&path, "[{\"player\": \"ada\", \"attem").unwrap();
+
+    assert!(Leaderboard::load(&path).is_err());
+    let (board, warning) = Leaderboard::load_or_recover(&path).unwrap();
+
+    assert!(board.is_empty());
+    assert!(warning.unwrap().contains("corrupted"));
+    assert!(!path.exists());
+
+    let mut backup = path.clone().into_os_string();
+    backup.push(".corrupted");
+    assert_eq!(
+        fs::read_to_string(&backup).unwrap(),
+        "[{\"player\": \"ada\", \"attem"
+    );
+    fs::remove_file(backup).unwrap();
+}
+
+#[test]
+fn a_file_that_is_not_text_is_kept() {
+    let path = temp_file("not_utf8");
+    let bytes = [0x5b, 0xff, 0xfe, 0x5d];
+    fs::write(&path, bytes).unwrap();
+
+    assert!(matches!(
+        Leaderboard::load(&path),
+        Err(LeaderboardError::Corrupted(_))
+    ));
+    let (mut board, warning) = Leaderboard::load_or_recover(&path).unwrap();
+    assert!(warning.unwrap().contains("corrupted"));
+
+    // Saving the new board leaves the old bytes where they were moved.
+    board.record(record("ada", 4));
+    board.save(&path).unwrap();
+    let mut backup = path.clone().into_os_string();
+    backup.push(".corrupted");
+    assert_eq!(fs::read(&backup).unwrap(), bytes);
+    fs::remove_file(backup).unwrap();
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn a_file_that_cannot_be_read_is_an_error() {
+    // A directory where the file should be: reading it fails, and nothing is moved or started.
+    let path = temp_file("a_directory");
+    fs::create_dir_all(&path).unwrap();
+
+    assert!(matches!(
+        Leaderboard::load_or_recover(&path),
+        Err(LeaderboardError::Io(_))
+    ));
+    assert!(path.is_dir());
+    fs::remove_dir(path).unwrap();
+}
+
+#[test]
+fn a_range_upside_down_is_corrupted() {
+    let path = temp_file("upside_down");
+    let mut upside_down = record("ada", 4);
+    upside_down.low = 100;
+    upside_down.high = 1;
+    fs::write(&path, serde_json::to_string(&[upside_down]).unwrap()).unwrap();
+
+    assert!(matches!(
+        Leaderboard::load(&path),
+        Err(LeaderboardError::Corrupted(_))
+    ));
+    let (board, warning) = Leaderboard::load_or_recover(&path).unwrap();
+    assert!(board.is_empty());
+    assert!(warning.unwrap().contains("100-1"));
+
+    let mut backup = path.into_os_string();
+    backup.push(".corrupted");
+    fs::remove_file(backup).unwrap();
+}
+
+#[test]
+fn stats_show_every_player_and_the_top_ten() {
+    let mut board = Leaderboard::new();
+    board.record(record("ada", 4));
+    board.record(record("bob", 6));
+
+    let text = guessing_game::leaderboard::render_stats(&board, &Catalog::english());
+
+    assert!(text.contains("ada"));
+    assert!(text.contains("bob"));
+    assert!(text.contains("Top 10"));
+}
+
+#[test]
+fn stats_speak_the_player_language() {
+    let mut board = Leaderboard::new();
+    board.record(record("ada", 4));
+
+    let text = guessing_game::leaderboard::render_stats(&board, &Catalog::for_locale("it"));
+
+    assert!(text.contains("Giocatori"));
+    assert!(text.contains("I migliori 10"));
+    assert!(!text.contains("Top 10"));
+}
@@ -0,0 +1,234 @@
+use std::cmp::Ordering;
+use std::io::{BufRead, BufReader, Lines, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::sync::{Arc, Barrier};
+use std::thread;
+use std::time::Duration;
+
+use guessing_game::GameSettings;
+use guessing_game::net::{Server, ServerMessage};
+
+fn start_server(seed: u64) -> SocketAddr {
+    let server = Server::bind("127.0.0.1:0", GameSettings::default(), Some(seed)).unwrap();
+    let addr = server.local_addr().unwrap();
+    server.spawn();
+    addr
+}
+
+struct TestClient {
+    stream: TcpStream,
+    lines: Lines<BufReader<TcpStream>>,
+}
+
+impl TestClient {
+    fn join(addr: SocketAddr, room: &str, name: &str) -> TestClient {
+        TestClient::connect(addr, &format!("JOIN {room} {name}"))
+    }
+
+    fn join_speaking(addr: SocketAddr, room: &str, name: &str, lang: &str) -> TestClient {
+        TestClient::connect(addr, &format!("JOIN {room} {name} {lang}"))
+    }
+
+    fn connect(addr: SocketAddr, join: &str) -> TestClient {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        // A server that got stuck fails the test instead of hanging it.
+        stream
+            .set_read_timeout(Some(Duration::from_secs(10)))
+            .unwrap();
+        let lines = BufReader::new(stream.try_clone().unwrap()).lines();
+        writeln!(stream, "{join}").unwrap();
+        let mut client = TestClient { stream, lines };
+        assert!(matches!(client.next(), ServerMessage::Welcome { .. }));
+        client
+    }
+
+    fn send(&mut self, line: &str) {
+        writeln!(self.stream, "{line}").unwrap();
+    }
+
+    fn next(&mut self) -> ServerMessage {
+        let line = self.lines.next().unwrap().unwrap();
+        ServerMessage::parse(&line).unwrap()
+    }
+
+    // Reads messages until the first winner, returning everything seen on the way.
+    fn until_winner(&mut self) -> Vec<ServerMessage> {
+        let mut seen = Vec::new();
+        loop {
+            let message = self.next();
+            let done = matches!(message, ServerMessage::Winner { .. });
+            seen.push(message);
+            if done {
+                return seen;
+            }
+        }
+    }
+}
+
+#[test]
+fn one_player_can_binary_search_with_the_broadcast_feedback() {
+    let addr = start_server(1);
+    let mut client = TestClient::join(addr, "solo", "ada");
+
+    let (mut low, mut high) = (1, 100);
+    for _ in 0..7 {
+        let guess = low + (high - low) / 2;
+        client.send(&format!("GUESS {guess}"));
+        match client.next() {
+            ServerMessage::Guessed {
+                feedback: Ordering::Less,
+                ..
+            } => low = guess + 1,
+            ServerMessage::Guessed {
+                feedback: Ordering::Greater,
+                ..
+            } => high = guess - 1,
+            ServerMessage::Guessed {
+                feedback: Ordering::Equal,
+                ..
+            } => {
+                assert_eq!(
+                    client.next(),
+                    ServerMessage::Winner {
+                        name: String::from("ada"),
+                        secret: guess,
+                        round: 1
+                    }
+                );
+                return;
+            }
+            other => panic!("unexpected message {other:?}"),
+        }
+    }
+    panic!("binary search should win within 7 guesses");
+}
+
+#[test]
+fn invalid_guesses_only_go_to_the_sender() {
+    let addr = start_server(2);
+    let mut ada = TestClient::join(addr, "room", "ada");
+    let mut bob = TestClient::join(addr, "room", "bob");
+    assert_eq!(ada.next(), ServerMessage::Joined(String::from("bob")));
+
+    bob.send("GUESS banana");
+    bob.send("GUESS 500");
+    assert_eq!(
+        bob.next(),
+        ServerMessage::Error(String::from(
+            "Please enter a valid number. 'banana' is not a number word."
+        ))
+    );
+    assert!(matches!(bob.next(), ServerMessage::Error(m) if m.contains("out of range")));
+
+    // The next thing ada hears about is bob's valid guess.
+    bob.send("GUESS 1");
+    assert!(matches!(
+        ada.next(),
+        ServerMessage::Guessed { guess: 1, .. }
+    ));
+}
+
+#[test]
+fn racing_players_agree_on_a_single_winner() {
+    const PLAYERS: u32 = 4;
+    let addr = start_server(3);
+
+    // Everybody joins before anybody guesses.
+    let mut clients: Vec<TestClient> = (0..PLAYERS)
+        .map(|i| TestClient::join(addr, "race", &format!("player{i}")))
+        .collect();
+    for (i, client) in clients.iter_mut().enumerate() {
+        for _ in i + 1..PLAYERS as usize {
+            assert!(matches!(client.next(), ServerMessage::Joined(_)));
+        }
+    }
+
+    let barrier = Arc::new(Barrier::new(PLAYERS as usize));
+    let handles: Vec<_> = clients
+        .into_iter()
+        .enumerate()
+        .map(|(i, mut client)| {
+            let barrier = Arc::clone(&barrier);
+            thread::spawn(move || {
+                barrier.wait();
+                // Each player sweeps every fourth number, so together they cover 1 to 100.
+                for guess in (1..=100).filter(|n| n % PLAYERS == i as u32) {
+                    client.send(&format!("GUESS {guess}"));
+                }
+                client.until_winner()
+            })
+        })
+        .collect();
+
+    let seen: Vec<Vec<ServerMessage>> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+    // Every player saw the same guesses in the same order, ending with the same winner.
+    for other in &seen[1..] {
+        assert_eq!(other, &seen[0]);
+    }
+
+    let ServerMessage::Winner {
+        name,
+        secret,
+        round,
+    } = seen[0].last().unwrap().clone()
+    else {
+        unreachable!();
+    };
+    assert_eq!(round, 1);
+    for message in &seen[0] {
+        if let ServerMessage::Guessed {
+            name: guesser,
+            guess,
+            feedback,
+        } = message
+        {
+            assert_eq!(*feedback, guess.cmp(&secret));
+            if *feedback == Ordering::Equal {
+                assert_eq!(guesser, &name);
+            }
+        }
+    }
+}
+
+#[test]
+fn errors_come_in_the_player_language() {
+    let addr = start_server(3);
+    let mut luca = TestClient::join_speaking(addr, "stanza", "luca", "it");
+    let _ada = TestClient::join(addr, "stanza", "ada");
+    assert_eq!(luca.next(), ServerMessage::Joined(String::from("ada")));
+
+    luca.send("GUESS 500");
+    assert_eq!(
+        luca.next(),
+        ServerMessage::Error(String::from(
+            "500 è fuori dall'intervallo. Inserisci un numero tra 1 e 100."
+        ))
+    );
+    luca.send("GUESS banana");
+    assert!(matches!(luca.next(), ServerMessage::Error(m) if m.contains("non è")));
+    luca.send("JOIN altrove luca");
+    assert_eq!(
+        luca.next(),
+        ServerMessage::Error(String::from("Sei già in una stanza."))
+    );
+}
+
+#[test]
+fn a_player_who_stops_reading_holds_up_nobody() {
+    let addr = start_server(4);
+    // Joins and never reads again: the server's writes to it soon stop going through.
+    let _asleep = TestClient::join(addr, "quiet", "asleep");
+
+    // Megabytes of JOINED and LEFT for the sleeper, far more than the socket buffers take.
+    let long_name = "x".repeat(64 * 1024);
+    for n in 0..100 {
+        let mut visitor = TestClient::join(addr, "quiet", &format!("{n}{long_name}"));
+        visitor.send("QUIT");
+    }
+
+    // Every room still answers.
+    let mut ada = TestClient::join(addr, "busy", "ada");
+    ada.send("GUESS 50");
+    assert!(matches!(ada.next(), ServerMessage::Guessed { .. }));
+}
@@ -0,0 +1,211 @@
+use guessing_game::record::{self, GameEvent, Pace, Recorder};
+use guessing_game::{Catalog, GameResult, GameSession, GameSettings, HostKind};
+use std::io;
+
+mod common;
+
+use common::SharedBuffer;
+
+fn record_game(settings: GameSettings, seed: u64, script: &str) -> Vec<record::TimedEvent> {
+    let log = SharedBuffer::default();
+    GameSession::with_settings(
+        settings,
+        common::seeded(seed),
+        script.as_bytes(),
+        io::sink(),
+    )
+    .record_to(Recorder::new(Box::new(log.clone()), Some(seed)))
+    .play()
+    .unwrap();
+
+    let bytes = log.0.borrow().clone();
+    record::parse_log(bytes.as_slice()).unwrap()
+}
+
+#[test]
+fn every_input_line_is_recorded_including_invalid_ones() {
+    let secret = common::secret_for_seed(4);
+    let script = format!("hello\n0\n{secret}\n");
+    let events: Vec<GameEvent> = record_game(GameSettings::default(), 4, &script)
+        .into_iter()
+        .map(|timed| timed.event)
+        .collect();
+
+    assert!(matches!(
+        events[0],
+        GameEvent::Start {
+            seed: Some(4),
+            low: 1,
+            high: 100,
+            ..
+        }
+    ));
+    assert_eq!(
+        events[1],
+        GameEvent::Input {
+            line: String::from("hello")
+        }
+    );
+    assert_eq!(
+        events[2],
+        GameEvent::Invalid {
+            reason: String::from("Please enter a valid number. 'hello' is not a number word.")
+        }
+    );
+    assert_eq!(
+        events[3],
+        GameEvent::Input {
+            line: String::from("0")
+        }
+    );
+    assert!(matches!(events[4], GameEvent::Invalid { .. }));
+    assert!(matches!(events[6], GameEvent::Feedback { .. }));
+    assert_eq!(
+        events[7],
+        GameEvent::End {
+            result: GameResult::Won,
+            secret,
+            attempts: 1
+        }
+    );
+}
+
+#[test]
+fn recorded_games_verify() {
+    let settings = GameSettings::new(1, 100, Some(3)).unwrap();
+    let events = record_game(settings, 12, "50\nnope\n25\n75\n");
+    assert_eq!(
+        record::verify(&events, &Catalog::english()),
+        Vec::<String>::new()
+    );
+
+    let evil = GameSettings::new(1, 100, Some(5))
+        .unwrap()
+        .with_host(HostKind::Evil);
+    let events = record_game(evil, 12, "10\n90\n50\n51\n52\n");
+    assert_eq!(
+        record::verify(&events, &Catalog::english()),
+        Vec::<String>::new()
+    );
+}
+
+#[test]
+fn tampered_feedback_is_caught() {
+    let secret = common::secret_for_seed(6);
+    let mut events = record_game(
+        GameSettings::default(),
+        6,
+        &format!("{}\n{secret}\n", secret + 1),
+    );
+
+    for timed in events.iter_mut() {
+        if let GameEvent::Feedback { guess, feedback } = &mut timed.event
+            && *guess != secret
+        {
+            *feedback = record::Feedback::Less;
+        }
+    }
+
+    let problems = record::verify(&events, &Catalog::english());
+    assert_eq!(problems.len(), 1, "{problems:?}");
+}
+
+#[test]
+fn swapped_secret_is_caught() {
+    let secret = common::secret_for_seed(6);
+    let mut events = record_game(GameSettings::default(), 6, &format!("{secret}\n"));
+
+    // Change the secret and the answer together, so only the seed gives it away.
+    let fake = if secret == 100 { 99 } else { secret + 1 };
+    for timed in events.iter_mut() {
+        match &mut timed.event {
+            GameEvent::Feedback { guess, .. } => *guess = fake,
+            GameEvent::End { secret, .. } => *secret = fake,
+            _ => {}
+        }
+    }
+
+    let problems = record::verify(&events, &Catalog::english());
+    assert!(
+        problems.iter().any(|p| p.contains("seed 6")),
+        "{problems:?}"
+    );
+}
+
+#[test]
+fn problems_are_reported_in_the_player_language() {
+    let secret = common::secret_for_seed(6);
+    let mut events = record_game(GameSettings::default(), 6, &format!("{secret}\n"));
+    events.pop();
+
+    let problems = record::verify(&events, &Catalog::for_locale("it"));
+    assert_eq!(
+        problems,
+        vec![String::from(
+            "la registrazione non finisce con un evento di fine"
+        )]
+    );
+}
+
+#[test]
+fn instant_replay_shows_the_whole_game() {
+    let secret = common::secret_for_seed(2);
+    let events = record_game(GameSettings::default(), 2, &format!("abc\n{secret}\n"));
+
+    let mut output = Vec::new();
+    record::replay(
+        &events,
+        Pace::Instant,
+        io::empty(),
+        &mut output,
+        &Catalog::english(),
+    )
+    .unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains("You guessed: abc"));
+    assert!(output.contains("Please enter a valid number."));
+    assert!(output.contains("You win!"));
+    assert!(output.contains(&format!("The secret number was {secret}.")));
+}
+
+#[test]
+fn replay_speaks_the_player_language() {
+    let secret = common::secret_for_seed(2);
+    let events = record_game(GameSettings::default(), 2, &format!("{secret}\n"));
+
+    let mut output = Vec::new();
+    record::replay(
+        &events,
+        Pace::Instant,
+        io::empty(),
+        &mut output,
+        &Catalog::for_locale("it"),
+    )
+    .unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains(&format!("Hai scritto: {secret}")));
+    assert!(output.contains("Hai vinto!"));
+    assert!(output.contains(&format!("Il numero segreto era {secret}.")));
+    assert!(!output.contains("You win!"));
+}
+
+#[test]
+fn step_replay_stops_on_q() {
+    let events = record_game(GameSettings::default(), 2, "1\n2\n3\n");
+
+    let mut output = Vec::new();
+    record::replay(
+        &events,
+        Pace::Step,
+        "\nq\n".as_bytes(),
+        &mut output,
+        &Catalog::english(),
+    )
+    .unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains("You guessed: 1"));
+    assert!(!output.contains("You guessed: 2"));
+}
@@ -0,0 +1,113 @@
+use std::collections::VecDeque;
+use std::io::{self, BufReader, Read};
+use std::thread;
+use std::time::Duration;
+
+use guessing_game::input::{Line, LineSource, TimedInput};
+use guessing_game::{GameOutcome, GameResult, GameSession, GameSettings};
+
+mod common;
+
+// A player who takes their time: every line arrives after its delay.
+struct SlowPlayer {
+    lines: VecDeque<(Duration, String)>,
+}
+
+impl Read for SlowPlayer {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let Some((delay, line)) = self.lines.pop_front() else {
+            return Ok(0);
+        };
+        thread::sleep(delay);
+        // The lines in these tests are short enough for any buffer.
+        buf[..line.len()].copy_from_slice(line.as_bytes());
+        Ok(line.len())
+    }
+}
+
+fn play_timed(
+    seed: u64,
+    limit: Duration,
+    max_attempts: Option<u32>,
+    lines: &[(u64, String)],
+) -> (GameOutcome, String) {
+    let player = SlowPlayer {
+        lines: lines
+            .iter()
+            .map(|(ms, line)| (Duration::from_millis(*ms), format!("{line}\n")))
+            .collect(),
+    };
+    let settings = GameSettings::new(1, 100, max_attempts)
+        .unwrap()
+        .with_time_limit(Some(limit));
+
+    let mut output = Vec::new();
+    let outcome = GameSession::with_settings(
+        settings,
+        common::seeded(seed),
+        TimedInput::spawn(BufReader::new(player)),
+        &mut output,
+    )
+    .play()
+    .unwrap();
+    (outcome, String::from_utf8(output).unwrap())
+}
+
+#[test]
+fn a_late_answer_is_a_miss() {
+    let secret = common::secret_for_seed(5);
+    let wrong = if secret == 1 { 2 } else { 1 };
+    // The first line comes 200ms after the first deadline, well inside the second turn.
+    let lines = [(600, wrong.to_string()), (0, secret.to_string())];
+    let (outcome, output) = play_timed(5, Duration::from_millis(400), None, &lines);
+
+    assert_eq!(outcome.result, GameResult::Won);
+    assert_eq!(outcome.misses, 1);
+    assert_eq!(outcome.guesses, vec![wrong, secret]);
+    assert_eq!(outcome.attempts, 3);
+    assert_eq!(outcome.guess_times.len(), 3);
+    assert!(outcome.guess_times[0] >= Duration::from_millis(400));
+    assert!(output.contains("Time's up! That turn counts as a miss."));
+    assert!(output.contains("Every guess must come within 0.4s."));
+}
+
+#[test]
+fn misses_use_up_the_attempts() {
+    let lines = [(5000, String::from("50"))];
+    let (outcome, output) = play_timed(6, Duration::from_millis(100), Some(2), &lines);
+
+    assert_eq!(outcome.result, GameResult::Lost);
+    assert_eq!(outcome.misses, 2);
+    assert!(outcome.guesses.is_empty());
+    assert!(output.contains("You lose!"));
+}
+
+#[test]
+fn the_clock_costs_points() {
+    let secret = common::secret_for_seed(7);
+    let lines = [(1100, secret.to_string())];
+    let (outcome, _) = play_timed(7, Duration::from_secs(3), None, &lines);
+
+    assert_eq!(outcome.result, GameResult::Won);
+    // First attempt, no hints: 1000, minus 10 for the one whole second.
+    assert_eq!(outcome.score(), 990);
+}
+
+#[test]
+fn a_line_after_the_deadline_answers_the_next_turn() {
+    let player = SlowPlayer {
+        lines: VecDeque::from([(Duration::from_millis(300), String::from("42\n"))]),
+    };
+    let mut input = TimedInput::spawn(BufReader::new(player));
+
+    assert_eq!(
+        input.next_line(Some(Duration::from_millis(100))).unwrap(),
+        Line::TimedOut
+    );
+    // Typed too late for the first turn, it is the next turn's guess.
+    assert_eq!(
+        input.next_line(Some(Duration::from_secs(5))).unwrap(),
+        Line::Text(String::from("42\n"))
+    );
+    assert_eq!(input.next_line(None).unwrap(), Line::End);
+}
@@ -0,0 +1,118 @@
+// The back of the house: the kitchen, and what it cooks.
+//
+// The kitchen display (kitchen.rs) takes the orders apart into tickets, one per dish, and
+// hands them to the cooks of each station. cook_order and fix_incorrect_order are the two
+// ways a dish gets there: the first time, or again because it came out wrong.
+// Both take the ingredients out of the kitchen's stock (stock.rs), if it keeps one, and then
+// switch off on the menu whatever the pantry can't make any more.
+
+use std::time::Instant;
+
+use crate::calendar::Date;
+use crate::menu::Menu;
+use crate::season::{FruitRules, Hemisphere, Season};
+use crate::serving::{OrderId, Orders};
+
+mod kitchen;
+pub mod stock;
+
+pub use kitchen::{Cooked, Dish, Kitchen, KitchenError, Routing, Station, Ticket, TicketId};
+
+// Sends a placed order to the kitchen; it is InKitchen from now on.
+pub fn cook_order(
+    kitchen: &mut Kitchen,
+    orders: &mut Orders,
+    menu: &mut Menu,
+    id: OrderId,
+) -> Result<Vec<TicketId>, KitchenError> {
+    let tickets = kitchen.cook_order(orders, id, Instant::now())?;
+    if let Some(stock) = kitchen.stock() {
+        stock.update_menu(menu);
+    }
+    Ok(tickets)
+}
+
+// The dish came out wrong: it is made again, before anything else waiting at its station.
+// A remake goes out on its own: only the first round of an order makes it Ready
+// (Kitchen::pass calls super::deliver_order for that).
+pub fn fix_incorrect_order(
+    kitchen: &mut Kitchen,
+    orders: &Orders,
+    menu: &mut Menu,
+    id: OrderId,
+    item: &str,
+) -> Result<TicketId, KitchenError> {
+    let ticket = kitchen.remake(orders, id, item)?;
+    if let Some(stock) = kitchen.stock() {
+        stock.update_menu(menu);
+    }
+    Ok(ticket)
+}
+
+// We can use the pub keyword to make a struct public.
+// If we use pub before a struct definition, we make the struct public, but the struct’s fields will still be private.
+// We can make each field public or not on a case-by-case basis.
+
+// Also, note that because back_of_house::Breakfast has a private field,
+// the struct needs to provide a public associated function that constructs an instance of Breakfast (we’ve named it summer here).
+// If Breakfast didn’t have such a function, we couldn’t create an instance of Breakfast in eat_at_restaurant because we couldn’t set
+// the value of the private seasonal_fruit field in eat_at_restaurant.
+// The kitchen picks the fruit from the date (see season.rs); the guest can look at it,
+// with seasonal_fruit(), but still not choose it.
+#[derive(Debug)]
+pub struct Breakfast {
+    pub toast: String,
+    seasonal_fruit: String,
+}
+
+impl Breakfast {
+    // Its id on the menu and in the recipes.
+    pub const MENU_ID: &'static str = "breakfast";
+
+    // The breakfast of the book: the first summer fruit.
+    pub fn summer(toast: &str) -> Breakfast {
+        let rules = FruitRules::default();
+        Breakfast {
+            toast: String::from(toast),
+            seasonal_fruit: rules.fruits(Season::Summer)[0].clone(),
+        }
+    }
+
+    // The breakfast of that day, with the fruit of the default table.
+    pub fn for_date(toast: &str, date: Date, hemisphere: Hemisphere) -> Breakfast {
+        Breakfast::with_rules(toast, date, hemisphere, &FruitRules::default())
+            .expect("the default table has fruit for every season")
+    }
+
+    // None if the table has no fruit for the season of that day.
+    pub fn with_rules(
+        toast: &str,
+        date: Date,
+        hemisphere: Hemisphere,
+        rules: &FruitRules,
+    ) -> Option<Breakfast> {
+        Some(Breakfast {
+            toast: String::from(toast),
+            seasonal_fruit: rules.fruit_for(date, hemisphere)?.to_string(),
+        })
+    }
+
+    pub fn seasonal_fruit(&self) -> &str {
+        &self.seasonal_fruit
+    }
+}
+
+// In contrast, if we make an enum public, all of its variants are public as well.
+pub enum Appetizer {
+    Soup,
+    Salad,
+}
+
+impl Appetizer {
+    pub fn menu_id(&self) -> &'static str {
+        match self {
+            Appetizer::Soup => "soup",
+            Appetizer::Salad => "salad",
+        }
+    }
+}
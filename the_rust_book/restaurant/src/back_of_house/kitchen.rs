@@ -0,0 +1,769 @@
+// The kitchen display: tickets, stations and the cooks working them.
+//
+// Every dish of an order becomes a Ticket for the station that makes it (the Routing says
+// which one, and how long it takes). Each station runs on its own threads:
+//
+//   Kitchen --Command--> dispatcher --Ticket--> cook 1, cook 2, ...
+//                            ^                      |
+//                            +------- Ready --------+      cook --Cooked--> Kitchen
+//
+// The dispatcher keeps the tickets that are waiting, and hands one to a cook whenever a cook
+// says Ready. Remakes wait in a line of their own that always goes first, so they jump
+// ahead of everything still waiting (not of what a cook already has on the fire).
+// Nothing but channels goes between the threads: a ticket is only ever in one place,
+// the dispatcher's queue, one cook's channel, or the cooked channel.
+//
+// Cooking is a sleep of the dish's cook time divided by the kitchen's speed, so tests and
+// demos can run an evening in milliseconds.
+//
+// A kitchen with a Stock (with_stock) takes the ingredients of every ticket out of it, and
+// refuses what the pantry can't make.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use super::stock::{Stock, StockError};
+use crate::serving::{OrderError, OrderId, OrderStatus, Orders};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Station {
+    Grill,
+    Fryer,
+    Stove,
+    ColdPrep,
+}
+
+impl fmt::Display for Station {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Station::Grill => "grill",
+            Station::Fryer => "fryer",
+            Station::Stove => "stove",
+            Station::ColdPrep => "cold prep",
+        };
+        write!(f, "{name}")
+    }
+}
+
+// Where a dish is made and how long it takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dish {
+    pub station: Station,
+    pub cook_time: Duration,
+}
+
+// Which station makes which dish of the menu.
+#[derive(Debug, Clone, Default)]
+pub struct Routing {
+    dishes: HashMap<String, Dish>,
+}
+
+impl Routing {
+    pub fn new() -> Routing {
+        Routing::default()
+    }
+
+    // The dishes of the house menu (menu.toml).
+    pub fn house() -> Routing {
+        let minutes = |m: u64| Duration::from_secs(m * 60);
+        Routing::new()
+            .route("breakfast", Station::Stove, minutes(8))
+            .route("soup", Station::Stove, minutes(5))
+            .route("salad", Station::ColdPrep, minutes(4))
+            .route("pasta", Station::Stove, minutes(12))
+            .route("burger", Station::Grill, minutes(10))
+            .route("fries", Station::Fryer, minutes(5))
+            .route("tiramisu", Station::ColdPrep, minutes(2))
+            .route("coffee", Station::ColdPrep, minutes(1))
+    }
+
+    pub fn route(mut self, item: &str, station: Station, cook_time: Duration) -> Routing {
+        self.dishes
+            .insert(item.to_string(), Dish { station, cook_time });
+        self
+    }
+
+    pub fn dish(&self, item: &str) -> Option<Dish> {
+        self.dishes.get(item).copied()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TicketId(u32);
+
+impl fmt::Display for TicketId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "K{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ticket {
+    pub id: TicketId,
+    pub order: OrderId,
+    pub item: String,
+    pub station: Station,
+    pub cook_time: Duration,
+    // Made again because the first one came out wrong.
+    pub remake: bool,
+}
+
+// A ticket that came off the line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cooked {
+    pub ticket: Ticket,
+    // Which cook of the station made it, counting from 0.
+    pub cook: usize,
+    // This was the last dish the order was waiting for (never for a remake).
+    pub order_done: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KitchenError {
+    Order(OrderError),
+    // Not in the routing: the kitchen doesn't know how to make it.
+    UnknownDish(String),
+    // The station that makes it has nobody working it.
+    NoCooks(Station),
+    Stock(StockError),
+    NotInOrder { order: OrderId, item: String },
+    // Only an order that has been cooked can have a dish made again.
+    NotCooked { order: OrderId, status: OrderStatus },
+    // The station's threads are gone.
+    StationClosed(Station),
+}
+
+impl fmt::Display for KitchenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KitchenError::Order(e) => write!(f, "{e}"),
+            KitchenError::UnknownDish(item) => write!(f, "the kitchen doesn't make {item}"),
+            KitchenError::NoCooks(station) => write!(f, "nobody is working the {station}"),
+            KitchenError::Stock(e) => write!(f, "{e}"),
+            KitchenError::NotInOrder { order, item } => {
+                write!(f, "order {order} has no {item}")
+            }
+            KitchenError::NotCooked { order, status } => {
+                write!(
+                    f,
+                    "order {order} is {status}, so there is nothing to make again"
+                )
+            }
+            KitchenError::StationClosed(station) => write!(f, "the {station} is closed"),
+        }
+    }
+}
+
+impl std::error::Error for KitchenError {}
+
+impl From<OrderError> for KitchenError {
+    fn from(e: OrderError) -> KitchenError {
+        KitchenError::Order(e)
+    }
+}
+
+impl From<StockError> for KitchenError {
+    fn from(e: StockError) -> KitchenError {
+        KitchenError::Stock(e)
+    }
+}
+
+// What the dispatcher of a station hears.
+enum Command {
+    Cook(Ticket),
+    Remake(Ticket),
+    // That cook has nothing to do.
+    Ready(usize),
+    // Finish what is waiting, then go home.
+    Close,
+}
+
+struct Crew {
+    commands: Sender<Command>,
+    // The cooks, then the dispatcher.
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl Crew {
+    // The dispatcher only stops when it is told to close, so a station whose dispatcher is
+    // still running takes tickets.
+    fn is_open(&self) -> bool {
+        self.threads.last().is_some_and(|t| !t.is_finished())
+    }
+}
+
+pub struct Kitchen {
+    routing: Routing,
+    crews: HashMap<Station, Crew>,
+    cooked: Receiver<Cooked>,
+    next_ticket: u32,
+    // How many tickets each order still waits for (remakes not counted).
+    waiting: HashMap<OrderId, usize>,
+    stock: Option<Stock>,
+}
+
+impl Kitchen {
+    // cooks: how many cooks work each station. speed: how many times faster than real life
+    // the dishes cook (1 for real time).
+    pub fn open(routing: Routing, cooks: &[(Station, usize)], speed: u32) -> Kitchen {
+        let mut per_station: HashMap<Station, usize> = HashMap::new();
+        for (station, count) in cooks {
+            *per_station.entry(*station).or_default() += count;
+        }
+
+        let (cooked_sender, cooked) = mpsc::channel();
+        let crews = per_station
+            .into_iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(station, count)| {
+                let crew = open_station(count, speed.max(1), &cooked_sender);
+                (station, crew)
+            })
+            .collect();
+
+        Kitchen {
+            routing,
+            crews,
+            cooked,
+            next_ticket: 0,
+            waiting: HashMap::new(),
+            stock: None,
+        }
+    }
+
+    // From now on every ticket takes its ingredients out of the stock.
+    pub fn with_stock(mut self, stock: Stock) -> Kitchen {
+        self.stock = Some(stock);
+        self
+    }
+
+    pub fn routing(&self) -> &Routing {
+        &self.routing
+    }
+
+    pub fn stock(&self) -> Option<&Stock> {
+        self.stock.as_ref()
+    }
+
+    // For the deliveries.
+    pub fn stock_mut(&mut self) -> Option<&mut Stock> {
+        self.stock.as_mut()
+    }
+
+    // One ticket per dish of the order, and the order is InKitchen.
+    // Nothing is sent (or taken from the stock) unless every dish can be made at a station
+    // that is open.
+    pub fn cook_order(
+        &mut self,
+        orders: &mut Orders,
+        id: OrderId,
+        at: Instant,
+    ) -> Result<Vec<TicketId>, KitchenError> {
+        let order = orders.get(id).ok_or(OrderError::UnknownOrder(id))?;
+        if order.status() != OrderStatus::Placed {
+            return Err(OrderError::IllegalTransition {
+                order: id,
+                from: order.status(),
+                to: OrderStatus::InKitchen,
+            }
+            .into());
+        }
+        let dishes = order
+            .items()
+            .iter()
+            .map(|item| Ok((item.clone(), self.dish_for(item)?)))
+            .collect::<Result<Vec<_>, KitchenError>>()?;
+        if let Some(stock) = &mut self.stock {
+            stock.take_for(order.items())?;
+        }
+
+        orders.advance(id, OrderStatus::InKitchen, at)?;
+        let mut tickets = Vec::with_capacity(dishes.len());
+        for (item, dish) in dishes {
+            let ticket = self.ticket(id, item, dish, false);
+            let ticket_id = ticket.id;
+            // Every station was open a moment ago; if one closed since, the backlog still
+            // counts what did go out.
+            if let Err(e) = self.send(Command::Cook(ticket), dish.station) {
+                if !tickets.is_empty() {
+                    self.waiting.insert(id, tickets.len());
+                }
+                return Err(e);
+            }
+            tickets.push(ticket_id);
+        }
+        self.waiting.insert(id, tickets.len());
+        Ok(tickets)
+    }
+
+    // The dish is made again, ahead of every ticket still waiting at its station.
+    // Only while the order is in the kitchen, ready or served: before that nothing came out
+    // wrong yet, and a paid or cancelled order is done with.
+    pub fn remake(
+        &mut self,
+        orders: &Orders,
+        id: OrderId,
+        item: &str,
+    ) -> Result<TicketId, KitchenError> {
+        let order = orders.get(id).ok_or(OrderError::UnknownOrder(id))?;
+        let status = order.status();
+        if !matches!(
+            status,
+            OrderStatus::InKitchen | OrderStatus::Ready | OrderStatus::Served
+        ) {
+            return Err(KitchenError::NotCooked { order: id, status });
+        }
+        if !order.items().iter().any(|i| i == item) {
+            return Err(KitchenError::NotInOrder {
+                order: id,
+                item: item.to_string(),
+            });
+        }
+        let dish = self.dish_for(item)?;
+        if let Some(stock) = &mut self.stock {
+            stock.take_for(&[item.to_string()])?;
+        }
+        let ticket = self.ticket(id, item.to_string(), dish, true);
+        let ticket_id = ticket.id;
+        self.send(Command::Remake(ticket), dish.station)?;
+        Ok(ticket_id)
+    }
+
+    // The next dish off the line, waiting at most that long for it.
+    pub fn receive(&mut self, timeout: Duration) -> Option<Cooked> {
+        match self.cooked.recv_timeout(timeout) {
+            Ok(cooked) => Some(self.book(cooked)),
+            Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => None,
+        }
+    }
+
+    // Like receive, and when that was the last dish of an order the order goes out to the
+    // waiters (crate::deliver_order makes it Ready). A cancelled order just stays cancelled.
+    pub fn pass(&mut self, orders: &mut Orders, timeout: Duration) -> Option<Cooked> {
+        let cooked = self.receive(timeout)?;
+        if cooked.order_done {
+            let _ = crate::deliver_order(orders, cooked.ticket.order);
+        }
+        Some(cooked)
+    }
+
+    // Tickets sent and not cooked yet, remakes not counted.
+    pub fn backlog(&self) -> usize {
+        self.waiting.values().sum()
+    }
+
+    // Cooks everything still waiting, sends the cooks home and hands back what came off the
+    // line and was not received yet.
+    pub fn close(mut self) -> Vec<Cooked> {
+        let crews = std::mem::take(&mut self.crews);
+        for crew in crews.values() {
+            let _ = crew.commands.send(Command::Close);
+        }
+        for crew in crews.into_values() {
+            for thread in crew.threads {
+                let _ = thread.join();
+            }
+        }
+        let cooked: Vec<Cooked> = self.cooked.try_iter().collect();
+        cooked.into_iter().map(|c| self.book(c)).collect()
+    }
+
+    fn dish_for(&self, item: &str) -> Result<Dish, KitchenError> {
+        let dish = self
+            .routing
+            .dish(item)
+            .ok_or_else(|| KitchenError::UnknownDish(item.to_string()))?;
+        match self.crews.get(&dish.station) {
+            None => Err(KitchenError::NoCooks(dish.station)),
+            Some(crew) if !crew.is_open() => Err(KitchenError::StationClosed(dish.station)),
+            Some(_) => Ok(dish),
+        }
+    }
+
+    fn ticket(&mut self, order: OrderId, item: String, dish: Dish, remake: bool) -> Ticket {
+        self.next_ticket += 1;
+        Ticket {
+            id: TicketId(self.next_ticket),
+            order,
+            item,
+            station: dish.station,
+            cook_time: dish.cook_time,
+            remake,
+        }
+    }
+
+    fn send(&self, command: Command, station: Station) -> Result<(), KitchenError> {
+        self.crews
+            .get(&station)
+            .ok_or(KitchenError::NoCooks(station))?
+            .commands
+            .send(command)
+            .map_err(|_| KitchenError::StationClosed(station))
+    }
+
+    // Counts the dish off its order.
+    fn book(&mut self, mut cooked: Cooked) -> Cooked {
+        if !cooked.ticket.remake
+            && let Some(left) = self.waiting.get_mut(&cooked.ticket.order)
+        {
+            *left -= 1;
+            if *left == 0 {
+                self.waiting.remove(&cooked.ticket.order);
+                cooked.order_done = true;
+            }
+        }
+        cooked
+    }
+}
+
+// A kitchen that is dropped without close() still sends its cooks home.
+impl Drop for Kitchen {
+    fn drop(&mut self) {
+        for crew in self.crews.values() {
+            let _ = crew.commands.send(Command::Close);
+        }
+    }
+}
+
+fn open_station(cooks: usize, speed: u32, cooked: &Sender<Cooked>) -> Crew {
+    let (commands, inbox) = mpsc::channel();
+    let mut to_cooks = Vec::with_capacity(cooks);
+    let mut threads = Vec::with_capacity(cooks + 1);
+
+    for cook in 0..cooks {
+        let (to_cook, tickets) = mpsc::channel::<Ticket>();
+        to_cooks.push(to_cook);
+        let ready = commands.clone();
+        let cooked = cooked.clone();
+        threads.push(thread::spawn(move || {
+            if ready.send(Command::Ready(cook)).is_err() {
+                return;
+            }
+            // Until the dispatcher hangs up.
+            for ticket in tickets {
+                thread::sleep(ticket.cook_time / speed);
+                let done = Cooked {
+                    ticket,
+                    cook,
+                    order_done: false,
+                };
+                if cooked.send(done).is_err() || ready.send(Command::Ready(cook)).is_err() {
+                    break;
+                }
+            }
+        }));
+    }
+    threads.push(thread::spawn(move || dispatch(inbox, to_cooks)));
+
+    Crew { commands, threads }
+}
+
+// The dispatcher of one station.
+fn dispatch(inbox: Receiver<Command>, cooks: Vec<Sender<Ticket>>) {
+    let mut remakes: VecDeque<Ticket> = VecDeque::new();
+    let mut waiting: VecDeque<Ticket> = VecDeque::new();
+    let mut idle: VecDeque<usize> = VecDeque::new();
+    let mut closing = false;
+
+    for command in &inbox {
+        match command {
+            Command::Cook(ticket) => waiting.push_back(ticket),
+            Command::Remake(ticket) => remakes.push_back(ticket),
+            Command::Ready(cook) => idle.push_back(cook),
+            Command::Close => closing = true,
+        }
+        while !idle.is_empty() {
+            let Some(ticket) = remakes.pop_front().or_else(|| waiting.pop_front()) else {
+                break;
+            };
+            let cook = idle.pop_front().expect("checked above");
+            if let Err(mpsc::SendError(ticket)) = cooks[cook].send(ticket) {
+                // That cook is gone: the ticket waits for another one.
+                remakes.push_front(ticket);
+            }
+        }
+        let all_idle = idle.len() == cooks.len();
+        if closing && remakes.is_empty() && waiting.is_empty() && all_idle {
+            break;
+        }
+    }
+    // Dropping the senders tells the cooks to go home.
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::hosting::{FloorPlan, TableId, Zone};
+
+    fn table() -> TableId {
+        FloorPlan::new().add_table(4, Zone::Dining).unwrap()
+    }
+
+    fn fast_routing() -> Routing {
+        let ms = Duration::from_millis;
+        Routing::new()
+            .route("burger", Station::Grill, ms(1))
+            .route("fries", Station::Fryer, ms(1))
+            .route("salad", Station::ColdPrep, ms(0))
+            .route("slow", Station::Grill, ms(50))
+    }
+
+    fn drain(kitchen: &mut Kitchen, orders: &mut Orders, count: usize) -> Vec<Cooked> {
+        (0..count)
+            .map(|_| {
+                kitchen
+                    .pass(orders, Duration::from_secs(5))
+                    .expect("a ticket got lost")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn cooks_an_order_and_hands_it_out() {
+        let now = Instant::now();
+        let mut orders = Orders::new();
+        let mut kitchen = Kitchen::open(
+            fast_routing(),
+            &[
+                (Station::Grill, 1),
+                (Station::Fryer, 1),
+                (Station::ColdPrep, 1),
+            ],
+            1,
+        );
+        let id = orders
+            .place(table(), &["burger", "fries", "salad"], now)
+            .unwrap();
+
+        let tickets = kitchen.cook_order(&mut orders, id, now).unwrap();
+        assert_eq!(tickets.len(), 3);
+        assert_eq!(orders.get(id).unwrap().status(), OrderStatus::InKitchen);
+
+        let cooked = drain(&mut kitchen, &mut orders, 3);
+        assert_eq!(cooked.iter().filter(|c| c.order_done).count(), 1);
+        assert!(cooked.last().unwrap().order_done);
+        assert_eq!(orders.get(id).unwrap().status(), OrderStatus::Ready);
+        assert_eq!(kitchen.backlog(), 0);
+        assert!(kitchen.close().is_empty());
+    }
+
+    #[test]
+    fn refuses_what_it_cannot_make() {
+        let now = Instant::now();
+        let mut orders = Orders::new();
+        let mut kitchen = Kitchen::open(fast_routing(), &[(Station::Grill, 1)], 1);
+
+        let sushi = orders.place(table(), &["burger", "sushi"], now).unwrap();
+        assert_eq!(
+            kitchen.cook_order(&mut orders, sushi, now),
+            Err(KitchenError::UnknownDish(String::from("sushi")))
+        );
+        // Nothing went out: the order is still only placed.
+        assert_eq!(orders.get(sushi).unwrap().status(), OrderStatus::Placed);
+
+        let fries = orders.place(table(), &["fries"], now).unwrap();
+        assert_eq!(
+            kitchen.cook_order(&mut orders, fries, now),
+            Err(KitchenError::NoCooks(Station::Fryer))
+        );
+
+        let burger = orders.place(table(), &["burger"], now).unwrap();
+        kitchen.cook_order(&mut orders, burger, now).unwrap();
+        assert!(matches!(
+            kitchen.cook_order(&mut orders, burger, now),
+            Err(KitchenError::Order(OrderError::IllegalTransition { .. }))
+        ));
+        assert_eq!(
+            kitchen.remake(&orders, burger, "salad"),
+            Err(KitchenError::NotInOrder {
+                order: burger,
+                item: String::from("salad")
+            })
+        );
+        assert_eq!(kitchen.close().len(), 1);
+    }
+
+    #[test]
+    fn remakes_jump_the_queue() {
+        let now = Instant::now();
+        let mut orders = Orders::new();
+        // One cook, so the grill can only do one thing at a time.
+        let mut kitchen = Kitchen::open(fast_routing(), &[(Station::Grill, 1)], 1);
+
+        let first = orders.place(table(), &["slow"], now).unwrap();
+        let second = orders.place(table(), &["slow"], now).unwrap();
+        let third = orders.place(table(), &["slow"], now).unwrap();
+        for id in [first, second, third] {
+            kitchen.cook_order(&mut orders, id, now).unwrap();
+        }
+        let remake = kitchen.remake(&orders, first, "slow").unwrap();
+
+        let cooked = drain(&mut kitchen, &mut orders, 4);
+        let position = |order: OrderId, remade: bool| {
+            cooked
+                .iter()
+                .position(|c| c.ticket.order == order && c.ticket.remake == remade)
+                .unwrap()
+        };
+        // Whatever the cook had on the fire gets finished, but the remake goes before
+        // the orders that were still waiting.
+        let remade = position(first, true);
+        assert_eq!(cooked[remade].ticket.id, remake);
+        assert!(remade < position(second, false));
+        assert!(remade < position(third, false));
+        assert!(!cooked[remade].order_done);
+    }
+
+    #[test]
+    fn only_cooked_orders_are_remade() {
+        use crate::back_of_house::stock::{Recipes, Unit};
+
+        let now = Instant::now();
+        let mut orders = Orders::new();
+        let stock = Stock::new(Recipes::new().recipe("burger", &[("patties", 1)])).ingredient(
+            "patties",
+            Unit::Pieces,
+            5,
+            1,
+        );
+        let mut kitchen =
+            Kitchen::open(fast_routing(), &[(Station::Grill, 1)], 1).with_stock(stock);
+
+        let placed = orders.place(table(), &["burger"], now).unwrap();
+        let cancelled = orders.place(table(), &["burger"], now).unwrap();
+        orders
+            .advance(cancelled, OrderStatus::Cancelled, now)
+            .unwrap();
+
+        assert_eq!(
+            kitchen.remake(&orders, placed, "burger"),
+            Err(KitchenError::NotCooked {
+                order: placed,
+                status: OrderStatus::Placed
+            })
+        );
+        assert_eq!(
+            kitchen.remake(&orders, cancelled, "burger"),
+            Err(KitchenError::NotCooked {
+                order: cancelled,
+                status: OrderStatus::Cancelled
+            })
+        );
+        assert_eq!(kitchen.stock().unwrap().get("patties").unwrap().on_hand, 5);
+        assert!(kitchen.close().is_empty());
+    }
+
+    #[test]
+    fn takes_from_the_stock() {
+        use crate::back_of_house::stock::{Recipes, Unit};
+
+        let now = Instant::now();
+        let mut orders = Orders::new();
+        let stock = Stock::new(Recipes::new().recipe("burger", &[("patties", 1)])).ingredient(
+            "patties",
+            Unit::Pieces,
+            2,
+            1,
+        );
+        let mut kitchen =
+            Kitchen::open(fast_routing(), &[(Station::Grill, 1)], 1).with_stock(stock);
+
+        let two = orders.place(table(), &["burger", "burger"], now).unwrap();
+        let one = orders.place(table(), &["burger"], now).unwrap();
+        kitchen.cook_order(&mut orders, two, now).unwrap();
+        assert_eq!(kitchen.stock().unwrap().get("patties").unwrap().on_hand, 0);
+        assert!(matches!(
+            kitchen.cook_order(&mut orders, one, now),
+            Err(KitchenError::Stock(StockError::OutOfStock { .. }))
+        ));
+        assert_eq!(orders.get(one).unwrap().status(), OrderStatus::Placed);
+        assert!(kitchen.remake(&orders, two, "burger").is_err());
+
+        kitchen.stock_mut().unwrap().receive("patties", 5).unwrap();
+        kitchen.cook_order(&mut orders, one, now).unwrap();
+        assert_eq!(kitchen.close().len(), 3);
+    }
+
+    #[test]
+    fn a_closed_station_takes_nothing() {
+        use crate::back_of_house::stock::{Recipes, Unit};
+
+        let now = Instant::now();
+        let mut orders = Orders::new();
+        let stock = Stock::new(Recipes::new().recipe("burger", &[("patties", 1)])).ingredient(
+            "patties",
+            Unit::Pieces,
+            2,
+            1,
+        );
+        let mut kitchen =
+            Kitchen::open(fast_routing(), &[(Station::Grill, 1)], 1).with_stock(stock);
+        let first = orders.place(table(), &["burger"], now).unwrap();
+        kitchen.cook_order(&mut orders, first, now).unwrap();
+        assert_eq!(drain(&mut kitchen, &mut orders, 1)[0].ticket.order, first);
+
+        // The grill goes home early.
+        let grill = &kitchen.crews[&Station::Grill];
+        grill.commands.send(Command::Close).unwrap();
+        while grill.is_open() {
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        let second = orders.place(table(), &["burger"], now).unwrap();
+        assert_eq!(
+            kitchen.cook_order(&mut orders, second, now),
+            Err(KitchenError::StationClosed(Station::Grill))
+        );
+        assert_eq!(orders.get(second).unwrap().status(), OrderStatus::Placed);
+        assert_eq!(
+            kitchen.remake(&orders, first, "burger"),
+            Err(KitchenError::StationClosed(Station::Grill))
+        );
+        assert_eq!(kitchen.stock().unwrap().get("patties").unwrap().on_hand, 1);
+        assert_eq!(kitchen.backlog(), 0);
+    }
+
+    #[test]
+    fn no_ticket_lost_or_cooked_twice() {
+        let now = Instant::now();
+        let mut orders = Orders::new();
+        let mut kitchen = Kitchen::open(
+            fast_routing(),
+            &[
+                (Station::Grill, 4),
+                (Station::Fryer, 3),
+                (Station::ColdPrep, 5),
+            ],
+            1,
+        );
+
+        let mut sent = Vec::new();
+        let mut order_ids = Vec::new();
+        for _ in 0..150 {
+            let id = orders
+                .place(table(), &["burger", "fries", "salad", "salad"], now)
+                .unwrap();
+            sent.extend(kitchen.cook_order(&mut orders, id, now).unwrap());
+            order_ids.push(id);
+        }
+        for id in order_ids.iter().step_by(10) {
+            sent.push(kitchen.remake(&orders, *id, "fries").unwrap());
+        }
+
+        // Half now, the rest when the kitchen closes.
+        let mut cooked = drain(&mut kitchen, &mut orders, sent.len() / 2);
+        cooked.extend(kitchen.close());
+
+        let ids: Vec<TicketId> = cooked.iter().map(|c| c.ticket.id).collect();
+        let unique: HashSet<TicketId> = ids.iter().copied().collect();
+        assert_eq!(ids.len(), sent.len(), "every ticket cooked exactly once");
+        assert_eq!(unique, sent.iter().copied().collect());
+        assert_eq!(cooked.iter().filter(|c| c.order_done).count(), 150);
+        assert!(cooked.iter().all(|c| c.cook < 5));
+    }
+}
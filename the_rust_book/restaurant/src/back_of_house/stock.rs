@@ -0,0 +1,490 @@
+// The pantry: what every dish takes, and how much of it is left.
+//
+// Recipes say how much of each ingredient one portion of a menu item needs. The Stock keeps
+// what is on hand, and a ledger of every movement: deliveries in, portions out. The kitchen
+// takes the ingredients of a whole order at once, or nothing if one of them is short, so the
+// stock never goes below zero.
+//
+// A dish with an ingredient too low for one more portion is sold out, and update_menu switches
+// it off on the menu (and on again after the delivery). The report lists what should be
+// ordered again, and how many more portions of each dish the pantry still has in it.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use crate::menu::Menu;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Grams,
+    Millilitres,
+    Pieces,
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symbol = match self {
+            Unit::Grams => "g",
+            Unit::Millilitres => "ml",
+            Unit::Pieces => "pcs",
+        };
+        write!(f, "{symbol}")
+    }
+}
+
+// One portion of a menu item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recipe {
+    pub item: String,
+    pub ingredients: Vec<(String, u32)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Recipes {
+    recipes: Vec<Recipe>,
+}
+
+impl Recipes {
+    pub fn new() -> Recipes {
+        Recipes::default()
+    }
+
+    // The dishes of the house menu (menu.toml).
+    pub fn house() -> Recipes {
+        Recipes::new()
+            .recipe(
+                "breakfast",
+                &[("bread", 2), ("eggs", 2), ("fruit", 150), ("butter", 20)],
+            )
+            .recipe("soup", &[("vegetables", 250), ("broth", 300)])
+            .recipe("salad", &[("lettuce", 120), ("tomatoes", 100)])
+            .recipe("pasta", &[("pasta", 120), ("ragù", 150)])
+            .recipe(
+                "burger",
+                &[
+                    ("patties", 1),
+                    ("buns", 1),
+                    ("potatoes", 200),
+                    ("tomatoes", 30),
+                ],
+            )
+            .recipe("fries", &[("potatoes", 250), ("oil", 30)])
+            .recipe(
+                "tiramisu",
+                &[("mascarpone", 100), ("ladyfingers", 4), ("coffee", 10)],
+            )
+            .recipe("coffee", &[("coffee", 8)])
+    }
+
+    // Replaces the recipe of the item, if it had one.
+    pub fn recipe(mut self, item: &str, ingredients: &[(&str, u32)]) -> Recipes {
+        let recipe = Recipe {
+            item: item.to_string(),
+            ingredients: ingredients
+                .iter()
+                .map(|(name, quantity)| (name.to_string(), *quantity))
+                .collect(),
+        };
+        self.recipes.retain(|r| r.item != item);
+        self.recipes.push(recipe);
+        self
+    }
+
+    pub fn get(&self, item: &str) -> Option<&Recipe> {
+        self.recipes.iter().find(|r| r.item == item)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Recipe> {
+        self.recipes.iter()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ingredient {
+    pub name: String,
+    pub unit: Unit,
+    pub on_hand: u32,
+    // Time to order more when there is less than this.
+    pub reorder_at: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Movement {
+    Delivery,
+    // Taken for a portion of this item.
+    Used(String),
+}
+
+// One line of the ledger.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StockEntry {
+    pub ingredient: String,
+    pub quantity: u32,
+    pub movement: Movement,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StockError {
+    UnknownIngredient(String),
+    NoRecipe(String),
+    // Not enough of the ingredient for the items asked for.
+    OutOfStock {
+        ingredient: String,
+        needed: u32,
+        on_hand: u32,
+    },
+    // The delivery would take the ingredient past what the pantry can count.
+    TooMuch {
+        ingredient: String,
+        delivered: u32,
+        on_hand: u32,
+    },
+}
+
+impl fmt::Display for StockError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StockError::UnknownIngredient(name) => write!(f, "the pantry has no {name}"),
+            StockError::NoRecipe(item) => write!(f, "there is no recipe for {item}"),
+            StockError::OutOfStock {
+                ingredient,
+                needed,
+                on_hand,
+            } => write!(
+                f,
+                "not enough {ingredient}: {needed} needed, {on_hand} left"
+            ),
+            StockError::TooMuch {
+                ingredient,
+                delivered,
+                on_hand,
+            } => write!(
+                f,
+                "{delivered} more {ingredient} on top of {on_hand} is more than the pantry can hold"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StockError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stock {
+    recipes: Recipes,
+    // By name, so the report comes out sorted.
+    ingredients: BTreeMap<String, Ingredient>,
+    ledger: Vec<StockEntry>,
+}
+
+impl Stock {
+    pub fn new(recipes: Recipes) -> Stock {
+        Stock {
+            recipes,
+            ingredients: BTreeMap::new(),
+            ledger: Vec::new(),
+        }
+    }
+
+    // The house recipes, and the pantry full at the start of a week.
+    pub fn house() -> Stock {
+        use Unit::*;
+        [
+            ("bread", Pieces, 40, 10),
+            ("eggs", Pieces, 60, 24),
+            ("fruit", Grams, 5_000, 1_000),
+            ("butter", Grams, 2_000, 500),
+            ("vegetables", Grams, 10_000, 2_000),
+            ("broth", Millilitres, 10_000, 2_000),
+            ("lettuce", Grams, 3_000, 1_000),
+            ("tomatoes", Grams, 4_000, 1_000),
+            ("pasta", Grams, 8_000, 2_000),
+            ("ragù", Millilitres, 6_000, 1_500),
+            ("patties", Pieces, 30, 10),
+            ("buns", Pieces, 30, 10),
+            ("potatoes", Grams, 15_000, 3_000),
+            ("oil", Millilitres, 5_000, 1_000),
+            ("mascarpone", Grams, 3_000, 1_000),
+            ("ladyfingers", Pieces, 120, 40),
+            ("coffee", Grams, 2_000, 500),
+        ]
+        .into_iter()
+        .fold(
+            Stock::new(Recipes::house()),
+            |stock, (name, unit, on_hand, reorder_at)| {
+                stock.ingredient(name, unit, on_hand, reorder_at)
+            },
+        )
+    }
+
+    // Adds (or replaces) an ingredient of the pantry.
+    pub fn ingredient(mut self, name: &str, unit: Unit, on_hand: u32, reorder_at: u32) -> Stock {
+        self.ingredients.insert(
+            name.to_string(),
+            Ingredient {
+                name: name.to_string(),
+                unit,
+                on_hand,
+                reorder_at,
+            },
+        );
+        self
+    }
+
+    pub fn recipes(&self) -> &Recipes {
+        &self.recipes
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Ingredient> {
+        self.ingredients.get(name)
+    }
+
+    pub fn ledger(&self) -> &[StockEntry] {
+        &self.ledger
+    }
+
+    pub fn receive(&mut self, name: &str, quantity: u32) -> Result<(), StockError> {
+        let ingredient = self
+            .ingredients
+            .get_mut(name)
+            .ok_or_else(|| StockError::UnknownIngredient(name.to_string()))?;
+        ingredient.on_hand =
+            ingredient
+                .on_hand
+                .checked_add(quantity)
+                .ok_or_else(|| StockError::TooMuch {
+                    ingredient: name.to_string(),
+                    delivered: quantity,
+                    on_hand: ingredient.on_hand,
+                })?;
+        self.ledger.push(StockEntry {
+            ingredient: name.to_string(),
+            quantity,
+            movement: Movement::Delivery,
+        });
+        Ok(())
+    }
+
+    // How many more portions of the item the pantry has in it.
+    // An ingredient the pantry doesn't keep counts as none left.
+    pub fn portions_left(&self, item: &str) -> Result<u32, StockError> {
+        let recipe = self
+            .recipes
+            .get(item)
+            .ok_or_else(|| StockError::NoRecipe(item.to_string()))?;
+        Ok(recipe
+            .ingredients
+            .iter()
+            .filter(|(_, quantity)| *quantity > 0)
+            .map(|(name, quantity)| self.get(name).map_or(0, |i| i.on_hand / quantity))
+            .min()
+            .unwrap_or(u32::MAX))
+    }
+
+    // Takes the ingredients for one portion of each item, all of them or none.
+    pub fn take_for(&mut self, items: &[String]) -> Result<(), StockError> {
+        let mut needed: HashMap<&str, u32> = HashMap::new();
+        for item in items {
+            let recipe = self
+                .recipes
+                .get(item)
+                .ok_or_else(|| StockError::NoRecipe(item.clone()))?;
+            for (name, quantity) in &recipe.ingredients {
+                *needed.entry(name).or_default() += quantity;
+            }
+        }
+        for (name, quantity) in &needed {
+            let on_hand = self.get(name).map_or(0, |i| i.on_hand);
+            if on_hand < *quantity {
+                return Err(StockError::OutOfStock {
+                    ingredient: name.to_string(),
+                    needed: *quantity,
+                    on_hand,
+                });
+            }
+        }
+
+        for item in items {
+            let recipe = self.recipes.get(item).expect("checked above");
+            for (name, quantity) in &recipe.ingredients {
+                if let Some(ingredient) = self.ingredients.get_mut(name) {
+                    ingredient.on_hand -= quantity;
+                }
+                self.ledger.push(StockEntry {
+                    ingredient: name.clone(),
+                    quantity: *quantity,
+                    movement: Movement::Used(item.clone()),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    // Every menu item with a recipe is available exactly when there is a portion left.
+    pub fn update_menu(&self, menu: &mut Menu) {
+        for recipe in self.recipes.iter() {
+            let in_stock = self.portions_left(&recipe.item).is_ok_and(|left| left > 0);
+            let _ = menu.set_available(&recipe.item, in_stock);
+        }
+    }
+
+    pub fn report(&self) -> StockReport {
+        StockReport {
+            to_reorder: self
+                .ingredients
+                .values()
+                .filter(|i| i.on_hand < i.reorder_at)
+                .cloned()
+                .collect(),
+            portions_left: self
+                .recipes
+                .iter()
+                .map(|r| {
+                    let left = self.portions_left(&r.item).expect("the recipe is there");
+                    (r.item.clone(), left)
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StockReport {
+    // Below their reorder level, by name.
+    pub to_reorder: Vec<Ingredient>,
+    // Every dish with a recipe, in the order of the recipes.
+    pub portions_left: Vec<(String, u32)>,
+}
+
+impl fmt::Display for StockReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "To reorder:")?;
+        if self.to_reorder.is_empty() {
+            writeln!(f, "  nothing")?;
+        }
+        for i in &self.to_reorder {
+            writeln!(
+                f,
+                "  {:<12} {} {} (reorder at {})",
+                i.name, i.on_hand, i.unit, i.reorder_at
+            )?;
+        }
+        writeln!(f, "Portions left:")?;
+        for (item, left) in &self.portions_left {
+            writeln!(f, "  {item:<12} {left}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::back_of_house::{Appetizer, Breakfast};
+
+    fn items(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    fn small_pantry() -> Stock {
+        Stock::new(Recipes::house())
+            .ingredient("vegetables", Unit::Grams, 600, 500)
+            .ingredient("broth", Unit::Millilitres, 3_000, 1_000)
+            .ingredient("lettuce", Unit::Grams, 1_000, 200)
+            .ingredient("tomatoes", Unit::Grams, 1_000, 200)
+    }
+
+    #[test]
+    fn the_book_dishes_have_recipes() {
+        let recipes = Recipes::house();
+        assert!(recipes.get(Breakfast::MENU_ID).is_some());
+        for appetizer in [Appetizer::Soup, Appetizer::Salad] {
+            assert!(recipes.get(appetizer.menu_id()).is_some());
+        }
+        // And every dish of the house menu.
+        let menu = Menu::house();
+        assert!(menu.items().iter().all(|i| recipes.get(&i.id).is_some()));
+    }
+
+    #[test]
+    fn takes_a_whole_order_or_nothing() {
+        let mut stock = small_pantry();
+        stock.take_for(&items(&["soup", "salad"])).unwrap();
+        assert_eq!(stock.get("vegetables").unwrap().on_hand, 350);
+        assert_eq!(stock.get("tomatoes").unwrap().on_hand, 900);
+        assert_eq!(stock.ledger().len(), 4);
+
+        // Two soups need 500 g of vegetables and there are 350.
+        assert_eq!(
+            stock.take_for(&items(&["salad", "soup", "soup"])),
+            Err(StockError::OutOfStock {
+                ingredient: String::from("vegetables"),
+                needed: 500,
+                on_hand: 350
+            })
+        );
+        assert_eq!(stock.get("lettuce").unwrap().on_hand, 880);
+        assert_eq!(
+            stock.take_for(&items(&["caviar"])),
+            Err(StockError::NoRecipe(String::from("caviar")))
+        );
+    }
+
+    #[test]
+    fn sold_out_dishes_leave_the_menu() {
+        let mut menu = Menu::house();
+        let mut stock = small_pantry();
+
+        stock.update_menu(&mut menu);
+        // Nothing for the pasta in this pantry.
+        assert!(!menu.get("pasta").unwrap().available);
+        assert!(menu.get("soup").unwrap().available);
+
+        stock.take_for(&items(&["soup", "soup"])).unwrap();
+        stock.update_menu(&mut menu);
+        assert!(!menu.get("soup").unwrap().available);
+
+        stock.receive("vegetables", 1_000).unwrap();
+        stock.update_menu(&mut menu);
+        assert!(menu.get("soup").unwrap().available);
+        assert_eq!(stock.ledger().last().unwrap().movement, Movement::Delivery);
+        assert_eq!(
+            stock.receive("truffles", 1),
+            Err(StockError::UnknownIngredient(String::from("truffles")))
+        );
+
+        let deliveries = stock.ledger().len();
+        assert_eq!(
+            stock.receive("vegetables", u32::MAX),
+            Err(StockError::TooMuch {
+                ingredient: String::from("vegetables"),
+                delivered: u32::MAX,
+                on_hand: 1_100
+            })
+        );
+        assert_eq!(stock.get("vegetables").unwrap().on_hand, 1_100);
+        assert_eq!(stock.ledger().len(), deliveries);
+    }
+
+    #[test]
+    fn report() {
+        let mut stock = small_pantry();
+        stock.take_for(&items(&["soup"])).unwrap();
+
+        let report = stock.report();
+        let low: Vec<&str> = report.to_reorder.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(low, ["vegetables"]);
+        let left: HashMap<&str, u32> = report
+            .portions_left
+            .iter()
+            .map(|(item, left)| (item.as_str(), *left))
+            .collect();
+        // 350 g of vegetables is one more soup; lettuce and tomatoes make 8 salads.
+        assert_eq!(left["soup"], 1);
+        assert_eq!(left["salad"], 8);
+        assert_eq!(left["pasta"], 0);
+
+        let text = report.to_string();
+        assert!(text.contains("  vegetables   350 g (reorder at 500)\n"));
+        assert!(text.contains("  salad        8\n"));
+    }
+}
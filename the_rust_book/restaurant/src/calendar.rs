@@ -0,0 +1,346 @@
+// Dates and times, without pulling in a date crate: a day of the Gregorian calendar,
+// a time of the day to the minute, and the two together.
+//
+// Internally a day is counted from 1970-01-01, which makes "the next day" or "how many days
+// between" simple sums. The conversion to year, month and day is the algorithm from
+// Howard Hinnant's "chrono-Compatible Low-Level Date Algorithms": it works in 400-year eras
+// that start on March 1st, so the leap day is the last day of its year.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DateError {
+    // Not YYYY-MM-DD.
+    Format(String),
+    // February 30th and friends.
+    NoSuchDay { year: i32, month: u32, day: u32 },
+    // Not HH:MM.
+    TimeFormat(String),
+    // 24:00, 12:60...
+    NoSuchTime { hour: u32, minute: u32 },
+}
+
+impl fmt::Display for DateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DateError::Format(text) => write!(f, "{text:?} is not a date like 2026-10-17"),
+            DateError::NoSuchDay { year, month, day } => {
+                write!(
+                    f,
+                    "{year:04}-{month:02}-{day:02} is not a day of the calendar"
+                )
+            }
+            DateError::TimeFormat(text) => write!(f, "{text:?} is not a time like 19:30"),
+            DateError::NoSuchTime { hour, minute } => {
+                write!(f, "{hour:02}:{minute:02} is not a time of the day")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DateError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Date {
+    // Days since 1970-01-01 (negative before).
+    days: i64,
+}
+
+pub fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        _ => 31,
+    }
+}
+
+impl Date {
+    pub fn new(year: i32, month: u32, day: u32) -> Result<Date, DateError> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return Err(DateError::NoSuchDay { year, month, day });
+        }
+        // The same eras as in year_month_day, backwards.
+        let year = i64::from(year) - i64::from(month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year.rem_euclid(400);
+        let month_from_march = i64::from((month + 9) % 12);
+        let day_of_year = (153 * month_from_march + 2) / 5 + i64::from(day) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        Ok(Date {
+            days: era * 146_097 + day_of_era - 719_468,
+        })
+    }
+
+    // Today in UTC.
+    pub fn today() -> Date {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Date::from_days((seconds / SECONDS_PER_DAY) as i64)
+    }
+
+    pub fn from_days(days: i64) -> Date {
+        Date { days }
+    }
+
+    pub fn days(&self) -> i64 {
+        self.days
+    }
+
+    pub fn add_days(&self, days: i64) -> Date {
+        Date::from_days(self.days + days)
+    }
+
+    pub fn year_month_day(&self) -> (i32, u32, u32) {
+        let z = self.days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        // Months counted from March.
+        let month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month + 2) / 5 + 1;
+        let month = if month < 10 { month + 3 } else { month - 9 };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        (year as i32, month as u32, day as u32)
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year_month_day().0
+    }
+
+    pub fn month(&self) -> u32 {
+        self.year_month_day().1
+    }
+
+    pub fn day(&self) -> u32 {
+        self.year_month_day().2
+    }
+
+    // 1 for January 1st.
+    pub fn day_of_year(&self) -> u32 {
+        let january_first = Date::new(self.year(), 1, 1).expect("January 1st exists");
+        (self.days - january_first.days) as u32 + 1
+    }
+}
+
+// 2026-10-17
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (year, month, day) = self.year_month_day();
+        write!(f, "{year:04}-{month:02}-{day:02}")
+    }
+}
+
+impl FromStr for Date {
+    type Err = DateError;
+
+    fn from_str(text: &str) -> Result<Date, DateError> {
+        let parts: Vec<u32> = text
+            .trim()
+            .split('-')
+            .map(|part| part.parse())
+            .collect::<Result<_, _>>()
+            .map_err(|_| DateError::Format(text.to_string()))?;
+        match parts[..] {
+            [year, month, day] if year <= 9999 => Date::new(year as i32, month, day),
+            _ => Err(DateError::Format(text.to_string())),
+        }
+    }
+}
+
+// A time of the day, to the minute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Time {
+    // Since midnight.
+    minutes: u32,
+}
+
+impl Time {
+    pub fn new(hour: u32, minute: u32) -> Result<Time, DateError> {
+        if hour >= 24 || minute >= 60 {
+            return Err(DateError::NoSuchTime { hour, minute });
+        }
+        Ok(Time {
+            minutes: hour * 60 + minute,
+        })
+    }
+
+    // None at midnight or later.
+    pub fn from_minutes(minutes: u32) -> Option<Time> {
+        (minutes < MINUTES_PER_DAY).then_some(Time { minutes })
+    }
+
+    // Since midnight.
+    pub fn minutes(&self) -> u32 {
+        self.minutes
+    }
+
+    pub fn hour(&self) -> u32 {
+        self.minutes / 60
+    }
+
+    pub fn minute(&self) -> u32 {
+        self.minutes % 60
+    }
+}
+
+// 19:30
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour(), self.minute())
+    }
+}
+
+impl FromStr for Time {
+    type Err = DateError;
+
+    fn from_str(text: &str) -> Result<Time, DateError> {
+        let parts: Vec<&str> = text.trim().split(':').collect();
+        let numbers: Vec<u32> = parts
+            .iter()
+            .map(|part| part.parse())
+            .collect::<Result<_, _>>()
+            .map_err(|_| DateError::TimeFormat(text.to_string()))?;
+        match numbers[..] {
+            [hour, minute] if parts[1].len() == 2 => Time::new(hour, minute),
+            _ => Err(DateError::TimeFormat(text.to_string())),
+        }
+    }
+}
+
+// A minute of a day. Ordered by date, then time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DateTime {
+    pub date: Date,
+    pub time: Time,
+}
+
+impl DateTime {
+    pub fn new(date: Date, time: Time) -> DateTime {
+        DateTime { date, time }
+    }
+
+    // Minutes since 1970-01-01 00:00, so that "90 minutes later" can cross midnight.
+    pub fn minutes(&self) -> i64 {
+        self.date.days * i64::from(MINUTES_PER_DAY) + i64::from(self.time.minutes)
+    }
+
+    pub fn from_minutes(minutes: i64) -> DateTime {
+        let per_day = i64::from(MINUTES_PER_DAY);
+        DateTime {
+            date: Date::from_days(minutes.div_euclid(per_day)),
+            time: Time {
+                minutes: minutes.rem_euclid(per_day) as u32,
+            },
+        }
+    }
+
+    pub fn add_minutes(&self, minutes: i64) -> DateTime {
+        DateTime::from_minutes(self.minutes() + minutes)
+    }
+}
+
+// 2026-10-17 19:30
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.date, self.time)
+    }
+}
+
+impl FromStr for DateTime {
+    type Err = DateError;
+
+    fn from_str(text: &str) -> Result<DateTime, DateError> {
+        let (date, time) = text
+            .trim()
+            .split_once(' ')
+            .ok_or_else(|| DateError::Format(text.to_string()))?;
+        Ok(DateTime::new(date.parse()?, time.parse()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_and_dates() {
+        assert_eq!(Date::from_days(0).to_string(), "1970-01-01");
+        assert_eq!(Date::from_days(19_782).to_string(), "2024-02-29");
+        assert_eq!(Date::from_days(20_743).to_string(), "2026-10-17");
+        assert_eq!(Date::from_days(-1).to_string(), "1969-12-31");
+        assert_eq!(Date::new(2026, 10, 17).unwrap().days(), 20_743);
+        assert_eq!(Date::new(2000, 2, 29).unwrap().days(), 11_016);
+
+        // Every day of a few centuries goes there and back.
+        for days in -200_000..200_000 {
+            let (year, month, day) = Date::from_days(days).year_month_day();
+            assert_eq!(Date::new(year, month, day).unwrap().days(), days);
+        }
+    }
+
+    #[test]
+    fn parses_and_checks() {
+        let date: Date = "2024-12-31".parse().unwrap();
+        assert_eq!(date.day_of_year(), 366);
+        assert_eq!(date.add_days(1).to_string(), "2025-01-01");
+
+        assert_eq!(
+            "2023-02-29".parse::<Date>(),
+            Err(DateError::NoSuchDay {
+                year: 2023,
+                month: 2,
+                day: 29
+            })
+        );
+        assert!(matches!(
+            "17/10/2026".parse::<Date>(),
+            Err(DateError::Format(_))
+        ));
+        assert!(matches!(
+            "2026-10-17-1".parse::<Date>(),
+            Err(DateError::Format(_))
+        ));
+    }
+
+    #[test]
+    fn times_of_the_day() {
+        let time: Time = "19:30".parse().unwrap();
+        assert_eq!((time.hour(), time.minute()), (19, 30));
+        assert_eq!(Time::new(7, 5).unwrap().to_string(), "07:05");
+        assert_eq!(
+            "24:00".parse::<Time>(),
+            Err(DateError::NoSuchTime {
+                hour: 24,
+                minute: 0
+            })
+        );
+        assert!(matches!(
+            "7:5".parse::<Time>(),
+            Err(DateError::TimeFormat(_))
+        ));
+        assert!(matches!(
+            "19".parse::<Time>(),
+            Err(DateError::TimeFormat(_))
+        ));
+        assert_eq!(Time::from_minutes(24 * 60), None);
+
+        let late: DateTime = "2026-12-31 23:30".parse().unwrap();
+        assert_eq!(late.add_minutes(45).to_string(), "2027-01-01 00:15");
+        assert_eq!(late.add_minutes(-24 * 60).to_string(), "2026-12-30 23:30");
+        assert!(late < late.add_minutes(1));
+    }
+}
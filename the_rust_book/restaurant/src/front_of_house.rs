@@ -0,0 +1,12 @@
+// The front of house, moved out of lib.rs: `mod front_of_house;` in lib.rs makes the compiler
+// look for the module here, and `pub mod hosting;` below for the submodule in src/front_of_house/hosting.rs.
+
+// If we only specify the pub keyword here,
+// if we can access the module front of the house we can access hosting.
+// But the contents of hosting are still private.
+// Making the module public, doesn't make the contents public.
+// The pub keyword on a module only lets code in its ancestor modules refer to it,
+// not access its inner code.
+pub mod billing;
+pub mod hosting;
+pub mod serving;
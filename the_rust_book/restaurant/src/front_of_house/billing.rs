@@ -0,0 +1,443 @@
+// Billing: what the served orders of a table cost, and who pays what.
+//
+// A bill adds up like this:
+//
+//   subtotal      every dish served
+//   - discount    a percentage or an amount (never more than the subtotal)
+//   + service     a percentage of what is left after the discount
+//   + tax         a percentage of that plus the service charge
+//   + tip         a percentage of the discounted subtotal, or an amount
+//   = total
+//
+// All of it in Money (money.rs), rounded to the cent at each step. Splitting the total shares
+// it out with Money::allocate, so the parts always add up to the total to the cent:
+// evenly, by who ate what (everyone pays their dishes plus the same part of the extras),
+// or by custom shares.
+
+use std::fmt;
+
+use super::hosting::TableId;
+use super::serving::{Order, OrderId, OrderStatus, Orders};
+use crate::menu::{Menu, MenuItem};
+use crate::money::{Money, Rate};
+
+const RECEIPT_WIDTH: usize = 40;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BillError {
+    // The order has a dish that is not on the menu.
+    UnknownItem(String),
+    // Only served orders go on the bill.
+    NotServed(OrderId),
+    NothingServed(TableId),
+    NegativeAmount(Money),
+    NoGuests,
+    // split_by_item got a guest for some lines but not for others.
+    Assignment { lines: usize, guests_given: usize },
+    // Every share is zero (or every dish was free): there is nothing to split by.
+    NothingToSplitBy,
+}
+
+impl fmt::Display for BillError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BillError::UnknownItem(id) => write!(f, "{id} is not on the menu"),
+            BillError::NotServed(id) => write!(f, "order {id} has not been served"),
+            BillError::NothingServed(table) => write!(f, "nothing was served at {table}"),
+            BillError::NegativeAmount(amount) => write!(f, "{amount} is below zero"),
+            BillError::NoGuests => write!(f, "a bill is split between at least one guest"),
+            BillError::Assignment {
+                lines,
+                guests_given,
+            } => write!(
+                f,
+                "the bill has {lines} lines but {guests_given} were given a guest"
+            ),
+            BillError::NothingToSplitBy => write!(f, "there is nothing to split the bill by"),
+        }
+    }
+}
+
+impl std::error::Error for BillError {}
+
+// A discount or a tip: a part of the bill, or a fixed amount.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Adjustment {
+    Percent(Rate),
+    Amount(Money),
+}
+
+impl Adjustment {
+    fn of(&self, base: Money) -> Money {
+        match self {
+            Adjustment::Percent(rate) => base.at(*rate),
+            Adjustment::Amount(amount) => *amount,
+        }
+    }
+
+    fn check(self) -> Result<Adjustment, BillError> {
+        match self {
+            Adjustment::Amount(amount) if amount.is_negative() => {
+                Err(BillError::NegativeAmount(amount))
+            }
+            _ => Ok(self),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BillLine {
+    pub item: String,
+    pub name: String,
+    pub price: Money,
+    // The order the dish came with, if it came with one.
+    pub order: Option<OrderId>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Totals {
+    pub subtotal: Money,
+    // Taken off: zero or less.
+    pub discount: Money,
+    pub service: Money,
+    pub tax: Money,
+    pub tip: Money,
+    pub total: Money,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Bill {
+    lines: Vec<BillLine>,
+    tax: Rate,
+    service: Option<Rate>,
+    discount: Option<Adjustment>,
+    tip: Option<Adjustment>,
+}
+
+impl Bill {
+    pub fn new() -> Bill {
+        Bill::default()
+    }
+
+    // Every served order of the table.
+    pub fn for_table(orders: &Orders, table: TableId, menu: &Menu) -> Result<Bill, BillError> {
+        let mut bill = Bill::new();
+        for order in orders
+            .for_table(table)
+            .filter(|o| o.status() == OrderStatus::Served)
+        {
+            bill.add_order(order, menu)?;
+        }
+        if bill.lines.is_empty() {
+            return Err(BillError::NothingServed(table));
+        }
+        Ok(bill)
+    }
+
+    pub fn add_order(&mut self, order: &Order, menu: &Menu) -> Result<(), BillError> {
+        if order.status() != OrderStatus::Served {
+            return Err(BillError::NotServed(order.id()));
+        }
+        // Check every dish before adding any, so a bad order leaves the bill as it was.
+        let items = order
+            .items()
+            .iter()
+            .map(|id| {
+                menu.get(id)
+                    .ok_or_else(|| BillError::UnknownItem(id.clone()))
+            })
+            .collect::<Result<Vec<&MenuItem>, BillError>>()?;
+        for item in items {
+            self.lines.push(BillLine {
+                item: item.id.clone(),
+                name: item.name.clone(),
+                price: item.price,
+                order: Some(order.id()),
+            });
+        }
+        Ok(())
+    }
+
+    // A dish that didn't come through an order (a bottle of wine at the bar).
+    pub fn add_item(&mut self, item: &MenuItem) {
+        self.lines.push(BillLine {
+            item: item.id.clone(),
+            name: item.name.clone(),
+            price: item.price,
+            order: None,
+        });
+    }
+
+    pub fn with_tax(mut self, rate: Rate) -> Bill {
+        self.tax = rate;
+        self
+    }
+
+    pub fn with_service_charge(mut self, rate: Rate) -> Bill {
+        self.service = Some(rate);
+        self
+    }
+
+    pub fn with_discount(mut self, discount: Adjustment) -> Result<Bill, BillError> {
+        self.discount = Some(discount.check()?);
+        Ok(self)
+    }
+
+    pub fn with_tip(mut self, tip: Adjustment) -> Result<Bill, BillError> {
+        self.tip = Some(tip.check()?);
+        Ok(self)
+    }
+
+    pub fn lines(&self) -> &[BillLine] {
+        &self.lines
+    }
+
+    pub fn totals(&self) -> Totals {
+        let subtotal: Money = self.lines.iter().map(|line| line.price).sum();
+        let discount = self
+            .discount
+            .map(|d| d.of(subtotal).min(subtotal))
+            .unwrap_or(Money::ZERO);
+        let discounted = subtotal - discount;
+        let service = self
+            .service
+            .map(|rate| discounted.at(rate))
+            .unwrap_or(Money::ZERO);
+        let tax = (discounted + service).at(self.tax);
+        let tip = self.tip.map(|t| t.of(discounted)).unwrap_or(Money::ZERO);
+        Totals {
+            subtotal,
+            discount: -discount,
+            service,
+            tax,
+            tip,
+            total: discounted + service + tax + tip,
+        }
+    }
+
+    pub fn total(&self) -> Money {
+        self.totals().total
+    }
+
+    // The same for everyone; the odd cents go to the first guests.
+    pub fn split_evenly(&self, guests: usize) -> Result<Vec<Money>, BillError> {
+        if guests == 0 {
+            return Err(BillError::NoGuests);
+        }
+        self.split_by_shares(&vec![1; guests])
+    }
+
+    // guest_of_line[i] is the guest (0, 1, 2...) who had line i. Everyone pays for their
+    // dishes and their part of the discount, service, tax and tip.
+    pub fn split_by_item(&self, guest_of_line: &[usize]) -> Result<Vec<Money>, BillError> {
+        if guest_of_line.len() != self.lines.len() {
+            return Err(BillError::Assignment {
+                lines: self.lines.len(),
+                guests_given: guest_of_line.len(),
+            });
+        }
+        let guests = guest_of_line.iter().max().map_or(0, |g| g + 1);
+        if guests == 0 {
+            return Err(BillError::NoGuests);
+        }
+        let mut eaten = vec![0u64; guests];
+        for (line, guest) in self.lines.iter().zip(guest_of_line) {
+            eaten[*guest] += line.price.cents().max(0) as u64;
+        }
+        self.split_by_shares(&eaten)
+    }
+
+    // Guest i pays shares[i] parts of the total: [2, 1] is two thirds and one third.
+    pub fn split_by_shares(&self, shares: &[u64]) -> Result<Vec<Money>, BillError> {
+        if shares.is_empty() {
+            return Err(BillError::NoGuests);
+        }
+        self.total()
+            .allocate(shares)
+            .ok_or(BillError::NothingToSplitBy)
+    }
+
+    // Every dish, then the totals, amounts lined up on the right.
+    pub fn receipt(&self) -> String {
+        fn line(receipt: &mut String, label: &str, amount: Money) {
+            let width = RECEIPT_WIDTH.saturating_sub(label.chars().count() + 1);
+            receipt.push_str(&format!("{label} {amount:>width$}\n"));
+        }
+
+        let mut receipt = String::new();
+        for bill_line in &self.lines {
+            line(&mut receipt, &bill_line.name, bill_line.price);
+        }
+        receipt.push_str(&"-".repeat(RECEIPT_WIDTH));
+        receipt.push('\n');
+
+        let totals = self.totals();
+        line(&mut receipt, "Subtotal", totals.subtotal);
+        if let Some(discount) = self.discount {
+            let label = match discount {
+                Adjustment::Percent(rate) => format!("Discount ({rate})"),
+                Adjustment::Amount(_) => String::from("Discount"),
+            };
+            line(&mut receipt, &label, totals.discount);
+        }
+        if let Some(rate) = self.service {
+            line(&mut receipt, &format!("Service ({rate})"), totals.service);
+        }
+        line(&mut receipt, &format!("Tax ({})", self.tax), totals.tax);
+        if self.tip.is_some() {
+            line(&mut receipt, "Tip", totals.tip);
+        }
+        line(&mut receipt, "TOTAL", totals.total);
+        receipt
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::super::hosting::{FloorPlan, Zone};
+    use super::*;
+
+    fn money(text: &str) -> Money {
+        text.parse().unwrap()
+    }
+
+    // Soup (6.00), pasta (14.00) and a coffee (2.50), all served at one table.
+    fn served_bill() -> Bill {
+        let now = Instant::now();
+        let menu = Menu::house();
+        let mut floor = FloorPlan::new();
+        let table = floor.add_table(4, Zone::Dining).unwrap();
+        let mut orders = Orders::new();
+
+        let id = orders.place(table, &["soup", "pasta"], now).unwrap();
+        for to in [
+            OrderStatus::InKitchen,
+            OrderStatus::Ready,
+            OrderStatus::Served,
+        ] {
+            orders.advance(id, to, now).unwrap();
+        }
+        // Still in the kitchen: not on the bill yet.
+        orders.place(table, &["tiramisu"], now).unwrap();
+
+        let mut bill = Bill::for_table(&orders, table, &menu).unwrap();
+        bill.add_item(menu.get("coffee").unwrap());
+        bill
+    }
+
+    #[test]
+    fn adds_up() {
+        let bill = served_bill()
+            .with_tax(Rate::basis_points(825))
+            .with_service_charge(Rate::percent(10))
+            .with_discount(Adjustment::Percent(Rate::percent(10)))
+            .unwrap()
+            .with_tip(Adjustment::Amount(money("3")))
+            .unwrap();
+
+        let totals = bill.totals();
+        assert_eq!(totals.subtotal, money("22.50"));
+        assert_eq!(totals.discount, money("-2.25"));
+        // 10% of 20.25 is 2.025
+        assert_eq!(totals.service, money("2.03"));
+        // 8.25% of 22.28 is 1.8381
+        assert_eq!(totals.tax, money("1.84"));
+        assert_eq!(totals.total, money("27.12"));
+    }
+
+    #[test]
+    fn discount_never_goes_below_zero() {
+        let bill = served_bill()
+            .with_discount(Adjustment::Amount(money("100")))
+            .unwrap();
+        assert_eq!(bill.total(), Money::ZERO);
+        assert_eq!(
+            served_bill().with_tip(Adjustment::Amount(money("-1"))),
+            Err(BillError::NegativeAmount(money("-1")))
+        );
+    }
+
+    #[test]
+    fn only_served_orders() {
+        let now = Instant::now();
+        let menu = Menu::house();
+        let mut floor = FloorPlan::new();
+        let table = floor.add_table(2, Zone::Bar).unwrap();
+        let mut orders = Orders::new();
+        let id = orders.place(table, &["caviar"], now).unwrap();
+
+        assert_eq!(
+            Bill::for_table(&orders, table, &menu),
+            Err(BillError::NothingServed(table))
+        );
+        let order = orders.get(id).unwrap();
+        assert_eq!(
+            Bill::new().add_order(order, &menu),
+            Err(BillError::NotServed(id))
+        );
+        for to in [
+            OrderStatus::InKitchen,
+            OrderStatus::Ready,
+            OrderStatus::Served,
+        ] {
+            orders.advance(id, to, now).unwrap();
+        }
+        assert_eq!(
+            Bill::for_table(&orders, table, &menu),
+            Err(BillError::UnknownItem(String::from("caviar")))
+        );
+    }
+
+    #[test]
+    fn splits_add_up_to_the_total() {
+        let bill = served_bill()
+            .with_tax(Rate::percent(10))
+            .with_tip(Adjustment::Percent(Rate::percent(15)))
+            .unwrap();
+        let total = bill.total();
+        // 22.50 + 2.25 tax + 3.38 tip (15% of 22.50 is 3.375)
+        assert_eq!(total, money("28.13"));
+
+        let even = bill.split_evenly(3).unwrap();
+        assert_eq!(even, [money("9.38"), money("9.38"), money("9.37")]);
+
+        // Guest 0 had the soup and the coffee (8.50), guest 1 the pasta (14.00).
+        let by_item = bill.split_by_item(&[0, 1, 0]).unwrap();
+        assert_eq!(by_item.iter().sum::<Money>(), total);
+        assert_eq!(by_item, [money("10.63"), money("17.50")]);
+
+        let shares = bill.split_by_shares(&[2, 1]).unwrap();
+        assert_eq!(shares, [money("18.75"), money("9.38")]);
+
+        assert_eq!(bill.split_evenly(0), Err(BillError::NoGuests));
+        assert_eq!(
+            bill.split_by_item(&[0]),
+            Err(BillError::Assignment {
+                lines: 3,
+                guests_given: 1
+            })
+        );
+        assert_eq!(
+            bill.split_by_shares(&[0, 0]),
+            Err(BillError::NothingToSplitBy)
+        );
+    }
+
+    #[test]
+    fn receipt_lines_up() {
+        let bill = served_bill()
+            .with_tax(Rate::percent(10))
+            .with_discount(Adjustment::Amount(money("2.50")))
+            .unwrap();
+        let receipt = bill.receipt();
+        let lines: Vec<&str> = receipt.lines().collect();
+
+        assert_eq!(lines[0], format!("Soup of the day{:>25}", "6.00"));
+        assert_eq!(lines[3], "-".repeat(RECEIPT_WIDTH));
+        assert_eq!(lines[5], format!("Discount{:>32}", "-2.50"));
+        assert_eq!(lines.last().unwrap(), &format!("TOTAL{:>35}", "22.00"));
+        assert!(lines.iter().all(|l| l.chars().count() == RECEIPT_WIDTH));
+    }
+}
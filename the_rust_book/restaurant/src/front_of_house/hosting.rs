@@ -0,0 +1,388 @@
+// Hosting: the people at the door who keep the waitlist and seat the guests.
+//
+// The Waitlist keeps the parties in the order they will be seated.
+// Parties with a priority (a reservation, or accessibility needs) go before the walk-ins,
+// and within each group whoever came first goes first.
+//
+// Times are std::time::Instant, so the waitlist works with the real clock as well as with
+// a made-up one (a starting Instant plus some minutes), which is what the tests use.
+//
+// The tables themselves are in the floor plan (floor.rs): seat_at_table takes the next party
+// off the waitlist and sits it down, party_leaves clears its tables again.
+//
+// Reservations (reservations.rs) book tables ahead. A reserved party that arrives joins the
+// waitlist with Priority::Reservation, and from there it is seated like everybody else.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+mod floor;
+mod reservations;
+
+pub use floor::{FloorPlan, MAX_JOINED_TABLES, Seating, SeatingError, Table, TableId, Zone};
+pub use reservations::{
+    GRACE_MINUTES, Reservation, ReservationError, ReservationId, ReservationStatus, Reservations,
+    SLOT_MINUTES,
+};
+
+// How many table turnovers the wait estimate looks at. Older ones say little about tonight.
+const TURNOVER_WINDOW: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    Reservation,
+    Accessibility,
+}
+
+// Handed out by Waitlist::add, so the host can find the party again to cancel it
+// or to tell them where they are in the line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PartyId(u32);
+
+impl PartyId {
+    // 3 for #3.
+    pub fn number(&self) -> u32 {
+        self.0
+    }
+}
+
+impl fmt::Display for PartyId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Party {
+    pub id: PartyId,
+    pub name: String,
+    pub size: u32,
+    pub arrived: Instant,
+    pub priority: Option<Priority>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WaitlistError {
+    EmptyName,
+    EmptyParty,
+}
+
+impl fmt::Display for WaitlistError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WaitlistError::EmptyName => write!(f, "a party needs a name"),
+            WaitlistError::EmptyParty => write!(f, "a party needs at least one guest"),
+        }
+    }
+}
+
+impl std::error::Error for WaitlistError {}
+
+#[derive(Debug, Clone, Default)]
+pub struct Waitlist {
+    // In the order they will be seated.
+    parties: Vec<Party>,
+    next_id: u32,
+    // When the last few tables were freed, oldest first.
+    tables_freed: VecDeque<Instant>,
+}
+
+impl Waitlist {
+    pub fn new() -> Waitlist {
+        Waitlist::default()
+    }
+
+    pub fn add(
+        &mut self,
+        name: &str,
+        size: u32,
+        priority: Option<Priority>,
+        arrived: Instant,
+    ) -> Result<PartyId, WaitlistError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(WaitlistError::EmptyName);
+        }
+        if size == 0 {
+            return Err(WaitlistError::EmptyParty);
+        }
+
+        self.next_id += 1;
+        let id = PartyId(self.next_id);
+        let party = Party {
+            id,
+            name: name.to_string(),
+            size,
+            arrived,
+            priority,
+        };
+
+        // Behind everybody of the same group who is already waiting
+        // (priority parties don't jump ahead of each other).
+        let at = match priority {
+            Some(_) => self
+                .parties
+                .iter()
+                .position(|p| p.priority.is_none())
+                .unwrap_or(self.parties.len()),
+            None => self.parties.len(),
+        };
+        self.parties.insert(at, party);
+        Ok(id)
+    }
+
+    // The party leaves the line. None if it was not (or no longer) waiting.
+    pub fn cancel(&mut self, id: PartyId) -> Option<Party> {
+        let at = self.parties.iter().position(|p| p.id == id)?;
+        Some(self.parties.remove(at))
+    }
+
+    // The party to seat next.
+    pub fn pop_next(&mut self) -> Option<Party> {
+        if self.parties.is_empty() {
+            None
+        } else {
+            Some(self.parties.remove(0))
+        }
+    }
+
+    // 1 for the party that will be seated next.
+    pub fn position(&self, id: PartyId) -> Option<usize> {
+        self.parties
+            .iter()
+            .position(|p| p.id == id)
+            .map(|at| at + 1)
+    }
+
+    pub fn get(&self, id: PartyId) -> Option<&Party> {
+        self.parties.iter().find(|p| p.id == id)
+    }
+
+    pub fn parties(&self) -> &[Party] {
+        &self.parties
+    }
+
+    pub fn len(&self) -> usize {
+        self.parties.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parties.is_empty()
+    }
+
+    // A table was cleared and can take the next party.
+    pub fn record_table_freed(&mut self, at: Instant) {
+        if self.tables_freed.len() == TURNOVER_WINDOW {
+            self.tables_freed.pop_front();
+        }
+        self.tables_freed.push_back(at);
+    }
+
+    // How often a table frees up lately: the average time between the recent turnovers.
+    // None until at least two tables have been freed.
+    pub fn turnover_interval(&self) -> Option<Duration> {
+        let first = self.tables_freed.front()?;
+        let last = self.tables_freed.back()?;
+        let gaps = self.tables_freed.len() as u32 - 1;
+        if gaps == 0 {
+            return None;
+        }
+        Some(last.duration_since(*first) / gaps)
+    }
+
+    // Every party ahead of this one, and this one too, needs a table to free up first.
+    pub fn estimated_wait(&self, id: PartyId) -> Option<Duration> {
+        let position = self.position(id)?;
+        Some(self.turnover_interval()? * position as u32)
+    }
+}
+
+// A walk-in party that just arrived.
+// we need to make also the function public
+pub fn add_to_waitlist(
+    waitlist: &mut Waitlist,
+    name: &str,
+    size: u32,
+) -> Result<PartyId, WaitlistError> {
+    waitlist.add(name, size, None, Instant::now())
+}
+
+// Seats the party at the head of the waitlist (in the zone, if one is asked for).
+// Ok(None) when nobody is waiting. When the party can't be seated the waitlist is left as it
+// was: with NoTableFree it simply waits for a table, with TooLarge the host has to talk to
+// them (and maybe cancel).
+pub fn seat_at_table(
+    waitlist: &mut Waitlist,
+    floor: &mut FloorPlan,
+    zone: Option<Zone>,
+) -> Result<Option<(Party, Seating)>, SeatingError> {
+    let Some(next) = waitlist.parties().first() else {
+        return Ok(None);
+    };
+    let seating = floor.seat(next, zone)?;
+    let party = waitlist.pop_next().expect("the party was just there");
+    Ok(Some((party, seating)))
+}
+
+// The party paid and left: its tables are free, and the waitlist learns how fast tables turn over.
+pub fn party_leaves(
+    waitlist: &mut Waitlist,
+    floor: &mut FloorPlan,
+    party: PartyId,
+    at: Instant,
+) -> Result<Seating, SeatingError> {
+    let seating = floor.release(party)?;
+    waitlist.record_table_freed(at);
+    Ok(seating)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn minutes(m: u64) -> Duration {
+        Duration::from_secs(m * 60)
+    }
+
+    #[test]
+    fn first_come_first_served() {
+        let opening = Instant::now();
+        let mut waitlist = Waitlist::new();
+        let ada = waitlist.add("Ada", 2, None, opening).unwrap();
+        let bob = waitlist.add("Bob", 4, None, opening + minutes(1)).unwrap();
+
+        assert_eq!(waitlist.position(ada), Some(1));
+        assert_eq!(waitlist.position(bob), Some(2));
+        assert_eq!(waitlist.pop_next().unwrap().name, "Ada");
+        assert_eq!(waitlist.position(bob), Some(1));
+        assert_eq!(waitlist.position(ada), None);
+    }
+
+    #[test]
+    fn priority_parties_go_first_in_arrival_order() {
+        let opening = Instant::now();
+        let mut waitlist = Waitlist::new();
+        waitlist.add("Walk-in", 2, None, opening).unwrap();
+        waitlist
+            .add(
+                "Booked",
+                2,
+                Some(Priority::Reservation),
+                opening + minutes(2),
+            )
+            .unwrap();
+        waitlist
+            .add(
+                "Ramp",
+                3,
+                Some(Priority::Accessibility),
+                opening + minutes(3),
+            )
+            .unwrap();
+
+        let order: Vec<&str> = waitlist.parties().iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(order, ["Booked", "Ramp", "Walk-in"]);
+    }
+
+    #[test]
+    fn cancel_leaves_the_line() {
+        let opening = Instant::now();
+        let mut waitlist = Waitlist::new();
+        let ada = waitlist.add("Ada", 2, None, opening).unwrap();
+        let bob = waitlist.add("Bob", 2, None, opening).unwrap();
+
+        assert_eq!(
+            waitlist.cancel(ada).map(|p| p.name),
+            Some(String::from("Ada"))
+        );
+        assert_eq!(waitlist.cancel(ada), None);
+        assert_eq!(waitlist.position(bob), Some(1));
+        assert_eq!(waitlist.len(), 1);
+    }
+
+    #[test]
+    fn rejects_bad_parties() {
+        let mut waitlist = Waitlist::new();
+        let now = Instant::now();
+
+        assert_eq!(
+            waitlist.add("  ", 2, None, now),
+            Err(WaitlistError::EmptyName)
+        );
+        assert_eq!(
+            waitlist.add("Ada", 0, None, now),
+            Err(WaitlistError::EmptyParty)
+        );
+        assert!(waitlist.is_empty());
+    }
+
+    #[test]
+    fn wait_follows_the_turnover() {
+        let opening = Instant::now();
+        let mut waitlist = Waitlist::new();
+        let ada = waitlist.add("Ada", 2, None, opening).unwrap();
+        let bob = waitlist.add("Bob", 2, None, opening).unwrap();
+        assert_eq!(waitlist.estimated_wait(ada), None);
+
+        // A table every 6 minutes.
+        for m in [0, 6, 12, 18] {
+            waitlist.record_table_freed(opening + minutes(m));
+        }
+        assert_eq!(waitlist.turnover_interval(), Some(minutes(6)));
+        assert_eq!(waitlist.estimated_wait(ada), Some(minutes(6)));
+        assert_eq!(waitlist.estimated_wait(bob), Some(minutes(12)));
+    }
+
+    #[test]
+    fn only_recent_turnover_counts() {
+        let opening = Instant::now();
+        let mut waitlist = Waitlist::new();
+
+        // Slow at first (every 30 minutes), then a table every 5 minutes.
+        for m in [0, 30, 60] {
+            waitlist.record_table_freed(opening + minutes(m));
+        }
+        for i in 1..=TURNOVER_WINDOW as u64 {
+            waitlist.record_table_freed(opening + minutes(60 + 5 * i));
+        }
+        assert_eq!(waitlist.turnover_interval(), Some(minutes(5)));
+    }
+
+    #[test]
+    fn seating_the_line() {
+        let opening = Instant::now();
+        let mut waitlist = Waitlist::new();
+        let mut floor = FloorPlan::new();
+        let small = floor.add_table(2, Zone::Dining).unwrap();
+        floor.add_table(4, Zone::Dining).unwrap();
+
+        let ada = waitlist.add("Ada", 4, None, opening).unwrap();
+        let bob = waitlist.add("Bob", 4, None, opening).unwrap();
+        let cal = waitlist.add("Cal", 2, None, opening).unwrap();
+
+        let (party, seating) = seat_at_table(&mut waitlist, &mut floor, None)
+            .unwrap()
+            .unwrap();
+        assert_eq!((party.id, seating.wasted), (ada, 0));
+
+        // Bob waits for the four-top, and Cal waits behind Bob even though T1 is free.
+        assert_eq!(
+            seat_at_table(&mut waitlist, &mut floor, None),
+            Err(SeatingError::NoTableFree { size: 4 })
+        );
+        assert_eq!(waitlist.position(bob), Some(1));
+        assert_eq!(floor.table(small).unwrap().party, None);
+
+        party_leaves(&mut waitlist, &mut floor, ada, opening + minutes(50)).unwrap();
+        let (party, _) = seat_at_table(&mut waitlist, &mut floor, None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(party.id, bob);
+        let (party, seating) = seat_at_table(&mut waitlist, &mut floor, None)
+            .unwrap()
+            .unwrap();
+        assert_eq!((party.id, seating.tables), (cal, vec![small]));
+        assert_eq!(seat_at_table(&mut waitlist, &mut floor, None), Ok(None));
+    }
+}
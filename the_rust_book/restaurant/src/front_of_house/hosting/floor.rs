@@ -0,0 +1,456 @@
+// The floor plan: every table, how many it seats, where it is, and who is sitting there.
+//
+// Tables standing next to each other can be pushed together for a large party
+// (FloorPlan::make_adjacent says which ones). A party gets the free table, or the free group
+// of adjacent tables, that leaves the fewest seats empty; with a tie, the fewest tables,
+// then the lowest table numbers. When the party leaves, its tables are free again.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use super::{Party, PartyId};
+
+// More than this many tables pushed together is a banquet, not a seating.
+pub const MAX_JOINED_TABLES: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Zone {
+    Dining,
+    Patio,
+    Bar,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TableId(u32);
+
+impl TableId {
+    // 3 for T3.
+    pub fn number(&self) -> u32 {
+        self.0
+    }
+}
+
+impl fmt::Display for TableId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "T{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Table {
+    pub id: TableId,
+    pub seats: u32,
+    pub zone: Zone,
+    // The party sitting there, if any.
+    pub party: Option<PartyId>,
+}
+
+// Where a party was seated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Seating {
+    pub party: PartyId,
+    pub tables: Vec<TableId>,
+    pub seats: u32,
+    // Seats left empty at those tables.
+    pub wasted: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeatingError {
+    // A table without seats.
+    NoSeats,
+    UnknownTable(TableId),
+    // Only tables of the same zone can be pushed together.
+    DifferentZones(TableId, TableId),
+    // No table, and no tables pushed together, seat that many (in that zone), even on an empty night.
+    TooLarge { size: u32, largest: u32 },
+    // The party fits in principle, but the tables it needs are taken.
+    NoTableFree { size: u32 },
+    AlreadySeated(PartyId),
+    NotSeated(PartyId),
+}
+
+impl fmt::Display for SeatingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SeatingError::NoSeats => write!(f, "a table needs at least one seat"),
+            SeatingError::UnknownTable(id) => write!(f, "there is no table {id}"),
+            SeatingError::DifferentZones(a, b) => {
+                write!(f, "{a} and {b} are in different zones")
+            }
+            SeatingError::TooLarge { size, largest } => write!(
+                f,
+                "a party of {size} does not fit: at most {largest} can sit together"
+            ),
+            SeatingError::NoTableFree { size } => {
+                write!(f, "no free table for a party of {size} right now")
+            }
+            SeatingError::AlreadySeated(party) => write!(f, "party {party} is already seated"),
+            SeatingError::NotSeated(party) => write!(f, "party {party} is not seated"),
+        }
+    }
+}
+
+impl std::error::Error for SeatingError {}
+
+#[derive(Debug, Clone, Default)]
+pub struct FloorPlan {
+    // TableId(n) is tables[n - 1].
+    tables: Vec<Table>,
+    // Pairs of tables that can be pushed together.
+    adjacent: HashSet<(TableId, TableId)>,
+    seated: HashMap<PartyId, Seating>,
+}
+
+impl FloorPlan {
+    pub fn new() -> FloorPlan {
+        FloorPlan::default()
+    }
+
+    // The tables of the house: 2-seaters and pairs of 4-seaters in the dining room,
+    // two 6-seaters on the patio and four 2-seaters at the bar.
+    pub fn house() -> FloorPlan {
+        let mut floor = FloorPlan::new();
+        for _ in 0..4 {
+            floor.add_table(2, Zone::Dining).unwrap();
+        }
+        for _ in 0..2 {
+            let a = floor.add_table(4, Zone::Dining).unwrap();
+            let b = floor.add_table(4, Zone::Dining).unwrap();
+            floor.make_adjacent(a, b).unwrap();
+        }
+        for _ in 0..2 {
+            floor.add_table(6, Zone::Patio).unwrap();
+        }
+        for _ in 0..4 {
+            floor.add_table(2, Zone::Bar).unwrap();
+        }
+        floor
+    }
+
+    pub fn add_table(&mut self, seats: u32, zone: Zone) -> Result<TableId, SeatingError> {
+        if seats == 0 {
+            return Err(SeatingError::NoSeats);
+        }
+        let id = TableId(self.tables.len() as u32 + 1);
+        self.tables.push(Table {
+            id,
+            seats,
+            zone,
+            party: None,
+        });
+        Ok(id)
+    }
+
+    // The two tables stand next to each other and can be pushed together.
+    pub fn make_adjacent(&mut self, a: TableId, b: TableId) -> Result<(), SeatingError> {
+        let zone_a = self.table(a).ok_or(SeatingError::UnknownTable(a))?.zone;
+        let zone_b = self.table(b).ok_or(SeatingError::UnknownTable(b))?.zone;
+        if zone_a != zone_b {
+            return Err(SeatingError::DifferentZones(a, b));
+        }
+        if a != b {
+            self.adjacent.insert((a.min(b), a.max(b)));
+        }
+        Ok(())
+    }
+
+    pub fn table(&self, id: TableId) -> Option<&Table> {
+        let index = (id.0 as usize).checked_sub(1)?;
+        self.tables.get(index)
+    }
+
+    // The table with that number, if the floor has one.
+    pub fn table_numbered(&self, number: u32) -> Option<TableId> {
+        self.table(TableId(number)).map(|t| t.id)
+    }
+
+    pub fn tables(&self) -> &[Table] {
+        &self.tables
+    }
+
+    pub fn free_tables(&self) -> impl Iterator<Item = &Table> {
+        self.tables.iter().filter(|t| t.party.is_none())
+    }
+
+    pub fn seating(&self, party: PartyId) -> Option<&Seating> {
+        self.seated.get(&party)
+    }
+
+    // The largest party the restaurant (or one zone of it) can seat on an empty night.
+    pub fn largest_party(&self, zone: Option<Zone>) -> u32 {
+        self.groups(|t| zone.is_none_or(|z| t.zone == z))
+            .iter()
+            .map(|group| self.seats_of(group))
+            .max()
+            .unwrap_or(0)
+    }
+
+    // Seats the party at the free table(s) that waste the fewest seats,
+    // in the given zone or anywhere.
+    pub fn seat(&mut self, party: &Party, zone: Option<Zone>) -> Result<Seating, SeatingError> {
+        if self.seated.contains_key(&party.id) {
+            return Err(SeatingError::AlreadySeated(party.id));
+        }
+        let largest = self.largest_party(zone);
+        if party.size > largest {
+            return Err(SeatingError::TooLarge {
+                size: party.size,
+                largest,
+            });
+        }
+
+        let best = self
+            .best_fit(party.size, zone)
+            .ok_or(SeatingError::NoTableFree { size: party.size })?;
+
+        let seats = self.seats_of(&best);
+        self.occupy(&best, party.id);
+        let seating = Seating {
+            party: party.id,
+            tables: best,
+            seats,
+            wasted: seats - party.size,
+        };
+        self.seated.insert(party.id, seating.clone());
+        Ok(seating)
+    }
+
+    // The party left: its tables are free again.
+    pub fn release(&mut self, party: PartyId) -> Result<Seating, SeatingError> {
+        let seating = self
+            .seated
+            .remove(&party)
+            .ok_or(SeatingError::NotSeated(party))?;
+        for id in &seating.tables {
+            self.tables[id.0 as usize - 1].party = None;
+        }
+        Ok(seating)
+    }
+
+    // The same tables, with nobody sitting at them.
+    pub fn layout(&self) -> FloorPlan {
+        let mut layout = self.clone();
+        for table in &mut layout.tables {
+            table.party = None;
+        }
+        layout.seated.clear();
+        layout
+    }
+
+    // Whether parties of these sizes could all sit at once on an empty floor. The largest
+    // party chooses first, each at its best fit: not an exhaustive search, but quick and
+    // always the same answer.
+    pub fn can_seat_together(&self, sizes: &[u32]) -> bool {
+        let mut plan = self.layout();
+        let mut sizes = sizes.to_vec();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes.iter().all(|size| match plan.best_fit(*size, None) {
+            Some(group) => {
+                // A waitlist never hands out #0: it only marks the tables as taken.
+                plan.occupy(&group, PartyId(0));
+                true
+            }
+            None => false,
+        })
+    }
+
+    // The free table, or group of adjacent free tables, that leaves the fewest seats empty.
+    fn best_fit(&self, size: u32, zone: Option<Zone>) -> Option<Vec<TableId>> {
+        let in_zone = |t: &Table| zone.is_none_or(|z| t.zone == z);
+        self.groups(|t| t.party.is_none() && in_zone(t))
+            .into_iter()
+            .filter(|group| self.seats_of(group) >= size)
+            .min_by_key(|group| (self.seats_of(group), group.len(), group.clone()))
+    }
+
+    fn occupy(&mut self, group: &[TableId], party: PartyId) {
+        for id in group {
+            self.tables[id.0 as usize - 1].party = Some(party);
+        }
+    }
+
+    fn seats_of(&self, group: &[TableId]) -> u32 {
+        group
+            .iter()
+            .filter_map(|id| self.table(*id))
+            .map(|t| t.seats)
+            .sum()
+    }
+
+    fn are_adjacent(&self, a: TableId, b: TableId) -> bool {
+        self.adjacent.contains(&(a.min(b), a.max(b)))
+    }
+
+    // Every table on its own and every group of adjacent tables (up to MAX_JOINED_TABLES)
+    // made only of tables that pass the filter. Each group is sorted by table number.
+    fn groups(&self, usable: impl Fn(&Table) -> bool) -> Vec<Vec<TableId>> {
+        let mut seen = HashSet::new();
+        let mut groups = Vec::new();
+        let mut to_grow: Vec<Vec<TableId>> = self
+            .tables
+            .iter()
+            .filter(|t| usable(t))
+            .map(|t| vec![t.id])
+            .collect();
+
+        while let Some(group) = to_grow.pop() {
+            if !seen.insert(group.clone()) {
+                continue;
+            }
+            if group.len() < MAX_JOINED_TABLES {
+                for table in self.tables.iter().filter(|t| usable(t)) {
+                    let touches = group.iter().any(|id| self.are_adjacent(*id, table.id));
+                    if touches && !group.contains(&table.id) {
+                        let mut bigger = group.clone();
+                        bigger.push(table.id);
+                        bigger.sort();
+                        to_grow.push(bigger);
+                    }
+                }
+            }
+            groups.push(group);
+        }
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::super::Waitlist;
+    use super::*;
+
+    fn party(waitlist: &mut Waitlist, size: u32) -> Party {
+        let id = waitlist.add("Guests", size, None, Instant::now()).unwrap();
+        waitlist.cancel(id).unwrap()
+    }
+
+    // T1: 2 seats, T2: 4, T3: 4 next to T2, T4: 6 on the patio.
+    fn floor() -> (FloorPlan, [TableId; 4]) {
+        let mut floor = FloorPlan::new();
+        let t1 = floor.add_table(2, Zone::Dining).unwrap();
+        let t2 = floor.add_table(4, Zone::Dining).unwrap();
+        let t3 = floor.add_table(4, Zone::Dining).unwrap();
+        let t4 = floor.add_table(6, Zone::Patio).unwrap();
+        floor.make_adjacent(t2, t3).unwrap();
+        (floor, [t1, t2, t3, t4])
+    }
+
+    #[test]
+    fn fewest_wasted_seats() {
+        let mut waitlist = Waitlist::new();
+        let (mut floor, [t1, t2, t3, t4]) = floor();
+
+        assert_eq!(
+            floor.seat(&party(&mut waitlist, 2), None).unwrap().tables,
+            [t1]
+        );
+        let three = floor.seat(&party(&mut waitlist, 3), None).unwrap();
+        assert_eq!((three.tables, three.wasted), (vec![t2], 1));
+        assert_eq!(
+            floor.seat(&party(&mut waitlist, 5), None).unwrap().tables,
+            [t4]
+        );
+        assert_eq!(
+            floor.seat(&party(&mut waitlist, 4), None).unwrap().tables,
+            [t3]
+        );
+    }
+
+    #[test]
+    fn joins_adjacent_tables() {
+        let mut waitlist = Waitlist::new();
+        let (mut floor, [_, t2, t3, _]) = floor();
+
+        let eight = floor.seat(&party(&mut waitlist, 8), None).unwrap();
+        assert_eq!(eight.tables, [t2, t3]);
+        assert_eq!(eight.seats, 8);
+        assert_eq!(floor.table(t3).unwrap().party, Some(eight.party));
+    }
+
+    #[test]
+    fn zones() {
+        let mut waitlist = Waitlist::new();
+        let (mut floor, [t1, _, _, t4]) = floor();
+
+        assert_eq!(
+            floor
+                .seat(&party(&mut waitlist, 2), Some(Zone::Patio))
+                .unwrap()
+                .tables,
+            [t4]
+        );
+        assert_eq!(
+            floor.make_adjacent(t1, t4),
+            Err(SeatingError::DifferentZones(t1, t4))
+        );
+        assert_eq!(
+            floor.seat(&party(&mut waitlist, 2), Some(Zone::Bar)),
+            Err(SeatingError::TooLarge {
+                size: 2,
+                largest: 0
+            })
+        );
+    }
+
+    #[test]
+    fn impossible_seatings() {
+        let mut waitlist = Waitlist::new();
+        let (mut floor, _) = floor();
+
+        assert_eq!(floor.largest_party(None), 8);
+        assert_eq!(
+            floor.seat(&party(&mut waitlist, 9), None),
+            Err(SeatingError::TooLarge {
+                size: 9,
+                largest: 8
+            })
+        );
+
+        let eight = party(&mut waitlist, 8);
+        floor.seat(&eight, None).unwrap();
+        assert_eq!(
+            floor.seat(&eight, None),
+            Err(SeatingError::AlreadySeated(eight.id))
+        );
+        assert_eq!(
+            floor.seat(&party(&mut waitlist, 7), None),
+            Err(SeatingError::NoTableFree { size: 7 })
+        );
+        assert_eq!(floor.add_table(0, Zone::Bar), Err(SeatingError::NoSeats));
+    }
+
+    #[test]
+    fn parties_that_fit_together() {
+        let mut waitlist = Waitlist::new();
+        let (mut floor, _) = floor();
+        // Who is sitting now doesn't matter: the question is about an empty floor.
+        floor.seat(&party(&mut waitlist, 8), None).unwrap();
+
+        assert!(floor.can_seat_together(&[2, 4, 4, 6]));
+        assert!(floor.can_seat_together(&[6, 2, 8]));
+        assert!(!floor.can_seat_together(&[8, 6, 2, 1]));
+        assert!(!floor.can_seat_together(&[9]));
+        assert!(floor.can_seat_together(&[]));
+        assert_eq!(floor.free_tables().count(), 2);
+        assert_eq!(floor.layout().free_tables().count(), 4);
+    }
+
+    #[test]
+    fn release_frees_the_tables() {
+        let mut waitlist = Waitlist::new();
+        let (mut floor, _) = floor();
+        let eight = party(&mut waitlist, 8);
+
+        floor.seat(&eight, None).unwrap();
+        assert_eq!(floor.free_tables().count(), 2);
+        assert_eq!(floor.release(eight.id).unwrap().seats, 8);
+        assert_eq!(floor.free_tables().count(), 4);
+        assert_eq!(
+            floor.release(eight.id),
+            Err(SeatingError::NotSeated(eight.id))
+        );
+    }
+}
@@ -0,0 +1,502 @@
+// Reservations: tables booked ahead for a party, on a day, from a time, for so many minutes.
+//
+// A booking is only taken if, at every moment it lasts, all the parties booked at that moment
+// could sit down together on the floor plan (FloorPlan::can_seat_together). Walk-ins are not
+// counted: they get what the reservations leave.
+//
+// A party that hasn't shown up some minutes (the grace) after its time is a no-show, and the
+// tables it held can be booked again. A party that does show up joins the waitlist with
+// Priority::Reservation, so it is seated before the walk-ins who are already waiting.
+
+use std::fmt;
+use std::time::Instant;
+
+use super::{FloorPlan, PartyId, Priority, Waitlist, WaitlistError};
+use crate::calendar::{Date, DateTime, Time};
+
+// How late a party may be before its tables are given away.
+pub const GRACE_MINUTES: u32 = 15;
+// How far apart the times offered by free_slots are.
+pub const SLOT_MINUTES: u32 = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ReservationId(u32);
+
+impl fmt::Display for ReservationId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "R{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReservationStatus {
+    Booked,
+    // Came, and was put on the waitlist.
+    Arrived,
+    NoShow,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reservation {
+    pub id: ReservationId,
+    pub name: String,
+    pub size: u32,
+    pub start: DateTime,
+    pub minutes: u32,
+    pub status: ReservationStatus,
+    // Their place on the waitlist, once they arrived.
+    pub party: Option<PartyId>,
+}
+
+impl Reservation {
+    pub fn end(&self) -> DateTime {
+        self.start.add_minutes(i64::from(self.minutes))
+    }
+
+    // Booked parties may still come, and arrived ones are sitting there until the end.
+    fn holds_tables(&self) -> bool {
+        matches!(
+            self.status,
+            ReservationStatus::Booked | ReservationStatus::Arrived
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReservationError {
+    EmptyName,
+    EmptyParty,
+    NoTime,
+    // Starts before opening or ends after closing.
+    Closed {
+        start: DateTime,
+        end: DateTime,
+    },
+    // More guests than the whole floor seats at one table (or tables pushed together).
+    TooLarge {
+        size: u32,
+        largest: u32,
+    },
+    // The tables are booked by others at that moment.
+    Full {
+        size: u32,
+        at: DateTime,
+    },
+    UnknownReservation(ReservationId),
+    // Only a booked reservation can arrive or be cancelled.
+    NotBooked {
+        id: ReservationId,
+        status: ReservationStatus,
+    },
+    // Came after the grace; the tables may be gone.
+    TooLate(ReservationId),
+    Waitlist(WaitlistError),
+}
+
+impl fmt::Display for ReservationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReservationError::EmptyName => write!(f, "a reservation needs a name"),
+            ReservationError::EmptyParty => write!(f, "a reservation needs at least one guest"),
+            ReservationError::NoTime => write!(f, "a reservation lasts at least a minute"),
+            ReservationError::Closed { start, end } => {
+                write!(f, "the restaurant is not open from {start} to {end}")
+            }
+            ReservationError::TooLarge { size, largest } => write!(
+                f,
+                "a party of {size} can't be seated: the largest table seats {largest}"
+            ),
+            ReservationError::Full { size, at } => {
+                write!(f, "no tables left for a party of {size} at {at}")
+            }
+            ReservationError::UnknownReservation(id) => write!(f, "there is no reservation {id}"),
+            ReservationError::NotBooked { id, status } => {
+                write!(f, "reservation {id} is no longer booked ({status:?})")
+            }
+            ReservationError::TooLate(id) => {
+                write!(f, "reservation {id} came after the grace and is a no-show")
+            }
+            ReservationError::Waitlist(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for ReservationError {}
+
+impl From<WaitlistError> for ReservationError {
+    fn from(e: WaitlistError) -> ReservationError {
+        ReservationError::Waitlist(e)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Reservations {
+    // Only the tables: who is sitting there tonight doesn't matter for next week.
+    floor: FloorPlan,
+    opens: Time,
+    // Every reservation ends by then, on the day it starts.
+    closes: Time,
+    grace: u32,
+    slot: u32,
+    // In the order they were booked.
+    reservations: Vec<Reservation>,
+    next_id: u32,
+}
+
+impl Reservations {
+    pub fn new(floor: &FloorPlan, opens: Time, closes: Time) -> Reservations {
+        Reservations {
+            floor: floor.layout(),
+            opens,
+            closes,
+            grace: GRACE_MINUTES,
+            slot: SLOT_MINUTES,
+            reservations: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    pub fn with_grace(mut self, minutes: u32) -> Reservations {
+        self.grace = minutes;
+        self
+    }
+
+    pub fn with_slots(mut self, minutes: u32) -> Reservations {
+        self.slot = minutes.max(1);
+        self
+    }
+
+    pub fn book(
+        &mut self,
+        name: &str,
+        size: u32,
+        start: DateTime,
+        minutes: u32,
+    ) -> Result<ReservationId, ReservationError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(ReservationError::EmptyName);
+        }
+        self.check(size, start, minutes)?;
+
+        self.next_id += 1;
+        let id = ReservationId(self.next_id);
+        self.reservations.push(Reservation {
+            id,
+            name: name.to_string(),
+            size,
+            start,
+            minutes,
+            status: ReservationStatus::Booked,
+            party: None,
+        });
+        Ok(id)
+    }
+
+    pub fn cancel(&mut self, id: ReservationId) -> Result<(), ReservationError> {
+        let reservation = self.booked(id)?;
+        reservation.status = ReservationStatus::Cancelled;
+        Ok(())
+    }
+
+    pub fn get(&self, id: ReservationId) -> Option<&Reservation> {
+        self.reservations.iter().find(|r| r.id == id)
+    }
+
+    pub fn reservations(&self) -> &[Reservation] {
+        &self.reservations
+    }
+
+    // The reservations of one day, by time, whatever became of them.
+    pub fn on(&self, date: Date) -> Vec<&Reservation> {
+        let mut day: Vec<&Reservation> = self
+            .reservations
+            .iter()
+            .filter(|r| r.start.date == date)
+            .collect();
+        day.sort_by_key(|r| (r.start, r.id));
+        day
+    }
+
+    // Every booked party more than the grace late is a no-show from now on; their tables can
+    // be booked again. Returns the ones that just became no-shows.
+    pub fn mark_no_shows(&mut self, now: DateTime) -> Vec<ReservationId> {
+        let grace = i64::from(self.grace);
+        let mut no_shows = Vec::new();
+        for reservation in &mut self.reservations {
+            if reservation.status == ReservationStatus::Booked
+                && now > reservation.start.add_minutes(grace)
+            {
+                reservation.status = ReservationStatus::NoShow;
+                no_shows.push(reservation.id);
+            }
+        }
+        no_shows
+    }
+
+    // The party is at the door: it joins the waitlist ahead of the walk-ins.
+    // Early is fine; later than the grace and it is a no-show.
+    pub fn arrive(
+        &mut self,
+        id: ReservationId,
+        now: DateTime,
+        waitlist: &mut Waitlist,
+        arrived: Instant,
+    ) -> Result<PartyId, ReservationError> {
+        let grace = i64::from(self.grace);
+        let reservation = self.booked(id)?;
+        if now > reservation.start.add_minutes(grace) {
+            reservation.status = ReservationStatus::NoShow;
+            return Err(ReservationError::TooLate(id));
+        }
+
+        let party = waitlist.add(
+            &reservation.name,
+            reservation.size,
+            Some(Priority::Reservation),
+            arrived,
+        )?;
+        reservation.status = ReservationStatus::Arrived;
+        reservation.party = Some(party);
+        Ok(party)
+    }
+
+    // The times of the day a party of this size could still book for so many minutes,
+    // from opening on, every few minutes (with_slots).
+    pub fn free_slots(&self, date: Date, size: u32, minutes: u32) -> Vec<Time> {
+        let last = self.closes.minutes().saturating_sub(minutes);
+        (self.opens.minutes()..=last)
+            .step_by(self.slot as usize)
+            .filter_map(Time::from_minutes)
+            .filter(|time| {
+                self.check(size, DateTime::new(date, *time), minutes)
+                    .is_ok()
+            })
+            .collect()
+    }
+
+    fn booked(&mut self, id: ReservationId) -> Result<&mut Reservation, ReservationError> {
+        let reservation = self
+            .reservations
+            .iter_mut()
+            .find(|r| r.id == id)
+            .ok_or(ReservationError::UnknownReservation(id))?;
+        if reservation.status != ReservationStatus::Booked {
+            return Err(ReservationError::NotBooked {
+                id,
+                status: reservation.status,
+            });
+        }
+        Ok(reservation)
+    }
+
+    // Whether a party of this size can book then, next to everybody already booked.
+    fn check(&self, size: u32, start: DateTime, minutes: u32) -> Result<(), ReservationError> {
+        if size == 0 {
+            return Err(ReservationError::EmptyParty);
+        }
+        if minutes == 0 {
+            return Err(ReservationError::NoTime);
+        }
+        let end = start.add_minutes(i64::from(minutes));
+        let closing = DateTime::new(start.date, self.closes);
+        if start.time < self.opens || end > closing {
+            return Err(ReservationError::Closed { start, end });
+        }
+        let largest = self.floor.largest_party(None);
+        if size > largest {
+            return Err(ReservationError::TooLarge { size, largest });
+        }
+
+        let overlapping: Vec<&Reservation> = self
+            .reservations
+            .iter()
+            .filter(|r| r.holds_tables() && r.start < end && start < r.end())
+            .collect();
+        // Who is at the tables only grows when somebody sits down, so it is enough to look at
+        // the start of the new booking and at every other start during it.
+        let mut moments = vec![start];
+        moments.extend(overlapping.iter().map(|r| r.start).filter(|s| *s > start));
+        for at in moments {
+            let mut sizes: Vec<u32> = overlapping
+                .iter()
+                .filter(|r| r.start <= at && at < r.end())
+                .map(|r| r.size)
+                .collect();
+            sizes.push(size);
+            if !self.floor.can_seat_together(&sizes) {
+                return Err(ReservationError::Full { size, at });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Zone;
+    use super::*;
+
+    fn at(text: &str) -> DateTime {
+        text.parse().unwrap()
+    }
+
+    fn time(text: &str) -> Time {
+        text.parse().unwrap()
+    }
+
+    // T1: 2 seats, T2 and T3: 4 each and next to each other, T4: 6 on the patio.
+    // Open 18:00 to 23:00.
+    fn reservations() -> Reservations {
+        let mut floor = FloorPlan::new();
+        floor.add_table(2, Zone::Dining).unwrap();
+        let t2 = floor.add_table(4, Zone::Dining).unwrap();
+        let t3 = floor.add_table(4, Zone::Dining).unwrap();
+        floor.add_table(6, Zone::Patio).unwrap();
+        floor.make_adjacent(t2, t3).unwrap();
+        Reservations::new(&floor, time("18:00"), time("23:00"))
+    }
+
+    #[test]
+    fn books_up_to_the_tables() {
+        let mut book = reservations();
+        book.book("Ada", 8, at("2026-10-17 19:00"), 120).unwrap();
+        book.book("Bob", 6, at("2026-10-17 19:30"), 90).unwrap();
+
+        assert_eq!(
+            book.book("Cy", 4, at("2026-10-17 18:30"), 120),
+            Err(ReservationError::Full {
+                size: 4,
+                at: at("2026-10-17 19:30")
+            })
+        );
+        book.book("Cy", 2, at("2026-10-17 18:30"), 120).unwrap();
+        // Ada's tables are free again at nine.
+        book.book("Di", 4, at("2026-10-17 21:00"), 60).unwrap();
+        // Another day, another floor.
+        book.book("Ed", 8, at("2026-10-18 19:00"), 120).unwrap();
+
+        let day: Vec<&str> = book
+            .on(at("2026-10-17 00:00").date)
+            .iter()
+            .map(|r| r.name.as_str())
+            .collect();
+        assert_eq!(day, ["Cy", "Ada", "Bob", "Di"]);
+    }
+
+    #[test]
+    fn free_slots_for_a_party() {
+        let mut book = reservations().with_slots(30);
+        let date = at("2026-10-17 00:00").date;
+        book.book("Ada", 8, at("2026-10-17 19:00"), 120).unwrap();
+        book.book("Bob", 6, at("2026-10-17 18:00"), 300).unwrap();
+
+        let eight: Vec<String> = book
+            .free_slots(date, 8, 60)
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+        assert_eq!(eight, ["18:00", "21:00", "21:30", "22:00"]);
+        // T1 is free all evening: every half hour until an hour before closing.
+        assert_eq!(book.free_slots(date, 2, 60).len(), 9);
+        assert!(book.free_slots(date, 9, 60).is_empty());
+        assert!(book.free_slots(date, 2, 600).is_empty());
+    }
+
+    #[test]
+    fn reserved_parties_go_before_walk_ins() {
+        let opening = Instant::now();
+        let mut book = reservations();
+        let mut waitlist = Waitlist::new();
+        let walk_in = waitlist.add("Walk-in", 2, None, opening).unwrap();
+        let ada = book.book("Ada", 4, at("2026-10-17 19:00"), 90).unwrap();
+
+        let party = book
+            .arrive(ada, at("2026-10-17 18:55"), &mut waitlist, opening)
+            .unwrap();
+        assert_eq!(waitlist.position(party), Some(1));
+        assert_eq!(waitlist.position(walk_in), Some(2));
+        assert_eq!(book.get(ada).unwrap().status, ReservationStatus::Arrived);
+        assert_eq!(book.get(ada).unwrap().party, Some(party));
+        assert_eq!(
+            book.arrive(ada, at("2026-10-17 19:00"), &mut waitlist, opening),
+            Err(ReservationError::NotBooked {
+                id: ada,
+                status: ReservationStatus::Arrived
+            })
+        );
+    }
+
+    #[test]
+    fn no_shows_free_their_tables() {
+        let opening = Instant::now();
+        let mut book = reservations().with_grace(10);
+        let mut waitlist = Waitlist::new();
+        let ada = book.book("Ada", 8, at("2026-10-17 19:00"), 120).unwrap();
+        let bob = book.book("Bob", 6, at("2026-10-17 19:00"), 120).unwrap();
+        let cy = book.book("Cy", 2, at("2026-10-17 19:30"), 60).unwrap();
+        assert!(book.book("Di", 4, at("2026-10-17 19:45"), 60).is_err());
+
+        assert_eq!(book.mark_no_shows(at("2026-10-17 19:10")), []);
+        assert_eq!(book.mark_no_shows(at("2026-10-17 19:11")), [ada, bob]);
+        assert_eq!(book.get(bob).unwrap().status, ReservationStatus::NoShow);
+        book.book("Di", 4, at("2026-10-17 19:45"), 60).unwrap();
+
+        // Too late to be seated, even before anybody looked at the clock.
+        assert_eq!(
+            book.arrive(cy, at("2026-10-17 19:41"), &mut waitlist, opening),
+            Err(ReservationError::TooLate(cy))
+        );
+        assert_eq!(book.get(cy).unwrap().status, ReservationStatus::NoShow);
+        assert!(waitlist.is_empty());
+    }
+
+    #[test]
+    fn bad_bookings() {
+        let mut book = reservations();
+        let start = at("2026-10-17 22:00");
+
+        assert_eq!(
+            book.book(" ", 2, start, 60),
+            Err(ReservationError::EmptyName)
+        );
+        assert_eq!(
+            book.book("Ada", 0, start, 60),
+            Err(ReservationError::EmptyParty)
+        );
+        assert_eq!(book.book("Ada", 2, start, 0), Err(ReservationError::NoTime));
+        assert_eq!(
+            book.book("Ada", 2, start, 90),
+            Err(ReservationError::Closed {
+                start,
+                end: at("2026-10-17 23:30")
+            })
+        );
+        assert!(matches!(
+            book.book("Ada", 2, at("2026-10-17 12:00"), 60),
+            Err(ReservationError::Closed { .. })
+        ));
+        assert_eq!(
+            book.book("Ada", 9, start, 60),
+            Err(ReservationError::TooLarge {
+                size: 9,
+                largest: 8
+            })
+        );
+
+        let ada = book.book("Ada", 2, start, 60).unwrap();
+        book.cancel(ada).unwrap();
+        assert_eq!(
+            book.cancel(ada),
+            Err(ReservationError::NotBooked {
+                id: ada,
+                status: ReservationStatus::Cancelled
+            })
+        );
+        assert_eq!(
+            book.cancel(ReservationId(7)),
+            Err(ReservationError::UnknownReservation(ReservationId(7)))
+        );
+    }
+}
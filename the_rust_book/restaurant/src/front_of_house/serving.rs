@@ -0,0 +1,447 @@
+// Serving: taking the orders, bringing the food and taking the money.
+//
+// An order goes one way only:
+//
+//   Placed -> InKitchen -> Ready -> Served -> Paid
+//
+// and can be Cancelled until it is served (once the food is on the table it has to be paid).
+// Any other move is an OrderError and leaves the order as it was.
+// Every move is written down with its time, so we can tell how long a table waited for its food.
+// A move can't be dated before the one it follows, or those waits would come out negative.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use super::hosting::TableId;
+
+// In JSON: "placed", "in_kitchen", "ready"...
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderStatus {
+    Placed,
+    InKitchen,
+    Ready,
+    Served,
+    Paid,
+    Cancelled,
+}
+
+impl OrderStatus {
+    // Where an order in this status may go next.
+    pub fn next(self) -> &'static [OrderStatus] {
+        use OrderStatus::*;
+        match self {
+            Placed => &[InKitchen, Cancelled],
+            InKitchen => &[Ready, Cancelled],
+            Ready => &[Served, Cancelled],
+            Served => &[Paid],
+            Paid | Cancelled => &[],
+        }
+    }
+
+    // Nothing more happens to a paid or cancelled order.
+    pub fn is_closed(self) -> bool {
+        self.next().is_empty()
+    }
+}
+
+impl fmt::Display for OrderStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            OrderStatus::Placed => "placed",
+            OrderStatus::InKitchen => "in the kitchen",
+            OrderStatus::Ready => "ready",
+            OrderStatus::Served => "served",
+            OrderStatus::Paid => "paid",
+            OrderStatus::Cancelled => "cancelled",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct OrderId(u32);
+
+impl OrderId {
+    // 3 for O3.
+    pub fn number(&self) -> u32 {
+        self.0
+    }
+}
+
+impl fmt::Display for OrderId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "O{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrderError {
+    NothingOrdered,
+    UnknownOrder(OrderId),
+    IllegalTransition {
+        order: OrderId,
+        from: OrderStatus,
+        to: OrderStatus,
+    },
+    // The move is dated before the order got into its current status.
+    BackInTime {
+        order: OrderId,
+        from: OrderStatus,
+        to: OrderStatus,
+    },
+}
+
+impl fmt::Display for OrderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OrderError::NothingOrdered => write!(f, "an order needs at least one dish"),
+            OrderError::UnknownOrder(id) => write!(f, "there is no order {id}"),
+            OrderError::IllegalTransition { order, from, to } => {
+                write!(f, "order {order} is {from} and can't be {to}")
+            }
+            OrderError::BackInTime { order, from, to } => {
+                write!(f, "order {order} can't be {to} before it was {from}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for OrderError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Order {
+    id: OrderId,
+    table: TableId,
+    items: Vec<String>,
+    // Every status the order has been in and since when, oldest first.
+    // The first one is always Placed and the last one is the current status.
+    history: Vec<(OrderStatus, Instant)>,
+}
+
+impl Order {
+    pub fn id(&self) -> OrderId {
+        self.id
+    }
+
+    pub fn table(&self) -> TableId {
+        self.table
+    }
+
+    pub fn items(&self) -> &[String] {
+        &self.items
+    }
+
+    pub fn status(&self) -> OrderStatus {
+        self.history.last().expect("an order starts as Placed").0
+    }
+
+    pub fn history(&self) -> &[(OrderStatus, Instant)] {
+        &self.history
+    }
+
+    // When the order got into that status, if it ever did.
+    pub fn time_of(&self, status: OrderStatus) -> Option<Instant> {
+        self.history
+            .iter()
+            .find(|(s, _)| *s == status)
+            .map(|(_, at)| *at)
+    }
+
+    // From the order to the food on the table.
+    pub fn service_time(&self) -> Option<Duration> {
+        let placed = self.time_of(OrderStatus::Placed)?;
+        let served = self.time_of(OrderStatus::Served)?;
+        Some(served.duration_since(placed))
+    }
+
+    // Only through Orders::advance: the orders are never handed out to change.
+    fn advance(&mut self, to: OrderStatus, at: Instant) -> Result<(), OrderError> {
+        let &(from, since) = self.history.last().expect("an order starts as Placed");
+        if !from.next().contains(&to) {
+            return Err(OrderError::IllegalTransition {
+                order: self.id,
+                from,
+                to,
+            });
+        }
+        if at < since {
+            return Err(OrderError::BackInTime {
+                order: self.id,
+                from,
+                to,
+            });
+        }
+        self.history.push((to, at));
+        Ok(())
+    }
+}
+
+// Every order of the evening.
+#[derive(Debug, Clone, Default)]
+pub struct Orders {
+    orders: Vec<Order>,
+    next_id: u32,
+}
+
+impl Orders {
+    pub fn new() -> Orders {
+        Orders::default()
+    }
+
+    pub fn place(
+        &mut self,
+        table: TableId,
+        items: &[&str],
+        at: Instant,
+    ) -> Result<OrderId, OrderError> {
+        if items.is_empty() {
+            return Err(OrderError::NothingOrdered);
+        }
+        self.next_id += 1;
+        let id = OrderId(self.next_id);
+        self.orders.push(Order {
+            id,
+            table,
+            items: items.iter().map(|item| item.to_string()).collect(),
+            history: vec![(OrderStatus::Placed, at)],
+        });
+        Ok(id)
+    }
+
+    pub fn get(&self, id: OrderId) -> Option<&Order> {
+        self.orders.iter().find(|o| o.id == id)
+    }
+
+    // The order with that number, if there is one.
+    pub fn numbered(&self, number: u32) -> Option<OrderId> {
+        self.get(OrderId(number)).map(|o| o.id)
+    }
+
+    pub fn advance(&mut self, id: OrderId, to: OrderStatus, at: Instant) -> Result<(), OrderError> {
+        self.orders
+            .iter_mut()
+            .find(|o| o.id == id)
+            .ok_or(OrderError::UnknownOrder(id))?
+            .advance(to, at)
+    }
+
+    pub fn orders(&self) -> &[Order] {
+        &self.orders
+    }
+
+    pub fn for_table(&self, table: TableId) -> impl Iterator<Item = &Order> {
+        self.orders.iter().filter(move |o| o.table == table)
+    }
+
+    // The orders still going: not paid and not cancelled.
+    pub fn open(&self) -> impl Iterator<Item = &Order> {
+        self.orders.iter().filter(|o| !o.status().is_closed())
+    }
+
+    // How long each served order of the table took, in the order they were placed.
+    pub fn service_times(&self, table: TableId) -> Vec<Duration> {
+        self.for_table(table)
+            .filter_map(|o| o.service_time())
+            .collect()
+    }
+
+    pub fn average_service_time(&self, table: TableId) -> Option<Duration> {
+        let times = self.service_times(table);
+        if times.is_empty() {
+            return None;
+        }
+        Some(times.iter().sum::<Duration>() / times.len() as u32)
+    }
+}
+
+// The three steps of the book, on the real clock.
+// serve_order only works once the kitchen has marked the order Ready.
+pub fn take_order(
+    orders: &mut Orders,
+    table: TableId,
+    items: &[&str],
+) -> Result<OrderId, OrderError> {
+    orders.place(table, items, Instant::now())
+}
+
+pub fn serve_order(orders: &mut Orders, id: OrderId) -> Result<(), OrderError> {
+    orders.advance(id, OrderStatus::Served, Instant::now())
+}
+
+pub fn take_payment(orders: &mut Orders, id: OrderId) -> Result<(), OrderError> {
+    orders.advance(id, OrderStatus::Paid, Instant::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::hosting::{FloorPlan, Zone};
+    use super::*;
+
+    fn minutes(m: u64) -> Duration {
+        Duration::from_secs(m * 60)
+    }
+
+    fn tables() -> (TableId, TableId) {
+        let mut floor = FloorPlan::new();
+        (
+            floor.add_table(2, Zone::Dining).unwrap(),
+            floor.add_table(4, Zone::Dining).unwrap(),
+        )
+    }
+
+    #[test]
+    fn the_whole_way() {
+        let opening = Instant::now();
+        let (table, _) = tables();
+        let mut orders = Orders::new();
+        let id = orders.place(table, &["soup", "bread"], opening).unwrap();
+
+        orders
+            .advance(id, OrderStatus::InKitchen, opening + minutes(1))
+            .unwrap();
+        orders
+            .advance(id, OrderStatus::Ready, opening + minutes(12))
+            .unwrap();
+        orders
+            .advance(id, OrderStatus::Served, opening + minutes(14))
+            .unwrap();
+        orders
+            .advance(id, OrderStatus::Paid, opening + minutes(60))
+            .unwrap();
+
+        let order = orders.get(id).unwrap();
+        assert_eq!(order.status(), OrderStatus::Paid);
+        assert_eq!(order.history().len(), 5);
+        assert_eq!(order.service_time(), Some(minutes(14)));
+        assert_eq!(orders.open().count(), 0);
+    }
+
+    #[test]
+    fn illegal_moves_change_nothing() {
+        let opening = Instant::now();
+        let (table, _) = tables();
+        let mut orders = Orders::new();
+        let id = orders.place(table, &["soup"], opening).unwrap();
+
+        assert_eq!(
+            orders.advance(id, OrderStatus::Served, opening),
+            Err(OrderError::IllegalTransition {
+                order: id,
+                from: OrderStatus::Placed,
+                to: OrderStatus::Served
+            })
+        );
+        assert_eq!(
+            orders.advance(id, OrderStatus::Placed, opening),
+            Err(OrderError::IllegalTransition {
+                order: id,
+                from: OrderStatus::Placed,
+                to: OrderStatus::Placed
+            })
+        );
+        assert_eq!(orders.get(id).unwrap().history().len(), 1);
+        assert_eq!(
+            orders.advance(OrderId(99), OrderStatus::InKitchen, opening),
+            Err(OrderError::UnknownOrder(OrderId(99)))
+        );
+        assert_eq!(
+            orders.place(table, &[], opening),
+            Err(OrderError::NothingOrdered)
+        );
+    }
+
+    #[test]
+    fn moves_cannot_go_back_in_time() {
+        let opening = Instant::now() + minutes(60);
+        let (table, _) = tables();
+        let mut orders = Orders::new();
+        let id = orders.place(table, &["soup"], opening).unwrap();
+        orders
+            .advance(id, OrderStatus::InKitchen, opening + minutes(5))
+            .unwrap();
+
+        assert_eq!(
+            orders.advance(id, OrderStatus::Ready, opening + minutes(4)),
+            Err(OrderError::BackInTime {
+                order: id,
+                from: OrderStatus::InKitchen,
+                to: OrderStatus::Ready
+            })
+        );
+        assert_eq!(
+            orders.advance(id, OrderStatus::Cancelled, opening),
+            Err(OrderError::BackInTime {
+                order: id,
+                from: OrderStatus::InKitchen,
+                to: OrderStatus::Cancelled
+            })
+        );
+        assert_eq!(orders.get(id).unwrap().status(), OrderStatus::InKitchen);
+
+        // At the same moment is fine.
+        orders
+            .advance(id, OrderStatus::Ready, opening + minutes(5))
+            .unwrap();
+    }
+
+    #[test]
+    fn cancel_only_before_serving() {
+        let opening = Instant::now();
+        let (table, _) = tables();
+        let mut orders = Orders::new();
+        let early = orders.place(table, &["soup"], opening).unwrap();
+        let late = orders.place(table, &["cake"], opening).unwrap();
+
+        orders
+            .advance(early, OrderStatus::Cancelled, opening + minutes(2))
+            .unwrap();
+        assert!(
+            orders
+                .advance(early, OrderStatus::InKitchen, opening + minutes(3))
+                .is_err()
+        );
+
+        for to in [
+            OrderStatus::InKitchen,
+            OrderStatus::Ready,
+            OrderStatus::Served,
+        ] {
+            orders.advance(late, to, opening + minutes(5)).unwrap();
+        }
+        assert!(
+            orders
+                .advance(late, OrderStatus::Cancelled, opening + minutes(6))
+                .is_err()
+        );
+        assert_eq!(orders.open().count(), 1);
+    }
+
+    #[test]
+    fn service_times_per_table() {
+        let opening = Instant::now();
+        let (window, corner) = tables();
+        let mut orders = Orders::new();
+
+        for (table, placed, served) in [(window, 0, 10), (window, 20, 40), (corner, 5, 50)] {
+            let id = orders
+                .place(table, &["pasta"], opening + minutes(placed))
+                .unwrap();
+            for (to, at) in [
+                (OrderStatus::InKitchen, placed),
+                (OrderStatus::Ready, served),
+                (OrderStatus::Served, served),
+            ] {
+                orders.advance(id, to, opening + minutes(at)).unwrap();
+            }
+        }
+        // Still in the kitchen: it doesn't count yet.
+        orders.place(corner, &["pasta"], opening).unwrap();
+
+        assert_eq!(orders.service_times(window), [minutes(10), minutes(20)]);
+        assert_eq!(orders.average_service_time(window), Some(minutes(15)));
+        assert_eq!(orders.service_times(corner), [minutes(45)]);
+    }
+}
@@ -0,0 +1,29 @@
+// Serves the restaurant on localhost (see src/server.rs for what it answers).
+//
+//   cargo run                      on 127.0.0.1:7979
+//   cargo run -- 127.0.0.1:8080    somewhere else on this machine
+//
+// Just enough to start the server: everything else is in the library.
+
+use std::env;
+use std::process;
+
+use restaurant::server::{DEFAULT_ADDR, Server};
+
+fn main() {
+    let addr = env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_ADDR.to_string());
+    let server = match Server::house(&addr) {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("Could not listen on {addr}: {e}");
+            process::exit(1);
+        }
+    };
+    match server.local_addr() {
+        Ok(local) => println!("The restaurant is open on http://{local}"),
+        Err(e) => eprintln!("Listening, but on which address? {e}"),
+    }
+    server.run();
+}
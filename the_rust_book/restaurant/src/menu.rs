@@ -0,0 +1,373 @@
+// The menu: every dish the restaurant sells, read from a data file instead of being written
+// into the code like back_of_house::Breakfast and Appetizer.
+//
+// The file is TOML or JSON with the same shape (see menu.toml next to Cargo.toml):
+//
+//   [[item]]
+//   id = "soup"
+//   name = "Soup of the day"
+//   category = "appetizer"
+//   price = 6.00
+//   available = true                         # may be left out
+//   options = [{ name = "bread", price = 1.00 }]
+//
+// or {"item": [{"id": "soup", ...}]} in JSON ("items" works too).
+// Prices are read to the cent into Money (money.rs), so there is no rounding when they are added up.
+// Dishes can be switched off and on while the restaurant is open (the soup ran out).
+
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::money::Money;
+
+// The menu of the house, built into the crate.
+const HOUSE_MENU: &str = include_str!("../menu.toml");
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MenuOption {
+    pub name: String,
+    // On top of the price of the dish.
+    pub extra: Money,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MenuItem {
+    pub id: String,
+    pub name: String,
+    pub category: String,
+    pub price: Money,
+    pub available: bool,
+    pub options: Vec<MenuOption>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MenuError {
+    Io(String),
+    // Not TOML or JSON, or not the shape of a menu.
+    Parse(String),
+    // The file name ends in neither .toml nor .json.
+    UnknownFormat(String),
+    EmptyId,
+    EmptyName(String),
+    EmptyCategory(String),
+    DuplicateId(String),
+    // The dish (or one of its options) has a price below zero, or one that isn't a number.
+    BadPrice { id: String, price: f64 },
+    UnknownItem(String),
+}
+
+impl fmt::Display for MenuError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MenuError::Io(e) => write!(f, "could not read the menu: {e}"),
+            MenuError::Parse(e) => write!(f, "the menu is not valid: {e}"),
+            MenuError::UnknownFormat(path) => {
+                write!(f, "{path}: a menu file ends in .toml or .json")
+            }
+            MenuError::EmptyId => write!(f, "a menu item has no id"),
+            MenuError::EmptyName(id) => write!(f, "menu item {id} has no name"),
+            MenuError::EmptyCategory(id) => write!(f, "menu item {id} has no category"),
+            MenuError::DuplicateId(id) => write!(f, "there are two menu items with id {id}"),
+            MenuError::BadPrice { id, price } => {
+                write!(f, "menu item {id} has a bad price: {price}")
+            }
+            MenuError::UnknownItem(id) => write!(f, "there is no menu item {id}"),
+        }
+    }
+}
+
+impl std::error::Error for MenuError {}
+
+// The file as written, before it is checked.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MenuFile {
+    #[serde(rename = "item", alias = "items", default)]
+    items: Vec<RawItem>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawItem {
+    id: String,
+    name: String,
+    category: String,
+    price: f64,
+    #[serde(default = "yes")]
+    available: bool,
+    #[serde(default)]
+    options: Vec<RawOption>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawOption {
+    name: String,
+    #[serde(default)]
+    price: f64,
+}
+
+fn yes() -> bool {
+    true
+}
+
+fn to_money(id: &str, price: f64) -> Result<Money, MenuError> {
+    // The price as written: -0.001 is negative even if it rounds to 0 cents.
+    let cents = (price * 100.0).round();
+    if !price.is_finite() || price < 0.0 || cents > f64::from(u32::MAX) {
+        return Err(MenuError::BadPrice {
+            id: id.to_string(),
+            price,
+        });
+    }
+    Ok(Money::from_cents(cents as i64))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Menu {
+    // In the order of the file.
+    items: Vec<MenuItem>,
+}
+
+impl Menu {
+    pub fn house() -> Menu {
+        Menu::from_toml(HOUSE_MENU).expect("menu.toml is a valid menu")
+    }
+
+    pub fn from_toml(text: &str) -> Result<Menu, MenuError> {
+        let file: MenuFile = toml::from_str(text).map_err(|e| MenuError::Parse(e.to_string()))?;
+        Menu::check(file)
+    }
+
+    pub fn from_json(text: &str) -> Result<Menu, MenuError> {
+        let file: MenuFile =
+            serde_json::from_str(text).map_err(|e| MenuError::Parse(e.to_string()))?;
+        Menu::check(file)
+    }
+
+    // TOML or JSON, by the extension of the file.
+    pub fn load(path: &Path) -> Result<Menu, MenuError> {
+        let text = fs::read_to_string(path).map_err(|e| MenuError::Io(e.to_string()))?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => Menu::from_toml(&text),
+            Some("json") => Menu::from_json(&text),
+            _ => Err(MenuError::UnknownFormat(path.display().to_string())),
+        }
+    }
+
+    fn check(file: MenuFile) -> Result<Menu, MenuError> {
+        let mut ids = HashSet::new();
+        let mut items = Vec::new();
+        for raw in file.items {
+            let id = raw.id.trim().to_string();
+            if id.is_empty() {
+                return Err(MenuError::EmptyId);
+            }
+            if !ids.insert(id.clone()) {
+                return Err(MenuError::DuplicateId(id));
+            }
+            if raw.name.trim().is_empty() {
+                return Err(MenuError::EmptyName(id));
+            }
+            if raw.category.trim().is_empty() {
+                return Err(MenuError::EmptyCategory(id));
+            }
+            let options = raw
+                .options
+                .into_iter()
+                .map(|option| {
+                    Ok(MenuOption {
+                        name: option.name.trim().to_string(),
+                        extra: to_money(&id, option.price)?,
+                    })
+                })
+                .collect::<Result<Vec<_>, MenuError>>()?;
+
+            items.push(MenuItem {
+                price: to_money(&id, raw.price)?,
+                name: raw.name.trim().to_string(),
+                category: raw.category.trim().to_lowercase(),
+                available: raw.available,
+                options,
+                id,
+            });
+        }
+        Ok(Menu { items })
+    }
+
+    pub fn items(&self) -> &[MenuItem] {
+        &self.items
+    }
+
+    pub fn get(&self, id: &str) -> Option<&MenuItem> {
+        self.items.iter().find(|item| item.id == id)
+    }
+
+    // The dishes of one category, in the order of the menu. Categories ignore case.
+    pub fn in_category<'a>(&'a self, category: &'a str) -> impl Iterator<Item = &'a MenuItem> {
+        self.items
+            .iter()
+            .filter(move |item| item.category.eq_ignore_ascii_case(category.trim()))
+    }
+
+    // Every category once, in the order they first show up.
+    pub fn categories(&self) -> Vec<&str> {
+        let mut categories: Vec<&str> = Vec::new();
+        for item in &self.items {
+            if !categories.contains(&item.category.as_str()) {
+                categories.push(&item.category);
+            }
+        }
+        categories
+    }
+
+    // What can be ordered right now.
+    pub fn available(&self) -> impl Iterator<Item = &MenuItem> {
+        self.items.iter().filter(|item| item.available)
+    }
+
+    pub fn set_available(&mut self, id: &str, available: bool) -> Result<(), MenuError> {
+        let item = self
+            .items
+            .iter_mut()
+            .find(|item| item.id == id)
+            .ok_or_else(|| MenuError::UnknownItem(id.to_string()))?;
+        item.available = available;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_house_menu_loads() {
+        let menu = Menu::house();
+        assert_eq!(menu.get("soup").unwrap().price, Money::from_cents(600));
+        assert_eq!(
+            menu.get("breakfast").unwrap().options[3].extra,
+            Money::from_cents(150)
+        );
+        assert_eq!(
+            menu.categories(),
+            ["breakfast", "appetizer", "main", "side", "dessert", "drink"]
+        );
+        let appetizers: Vec<&str> = menu
+            .in_category("Appetizer")
+            .map(|item| item.id.as_str())
+            .collect();
+        assert_eq!(appetizers, ["soup", "salad"]);
+    }
+
+    #[test]
+    fn json_has_the_same_shape() {
+        let menu = Menu::from_json(
+            r#"{"items": [
+                {"id": "tea", "name": "Tea", "category": "Drink", "price": 2.2, "available": false}
+            ]}"#,
+        )
+        .unwrap();
+        let tea = menu.get("tea").unwrap();
+        assert_eq!((tea.price, tea.available), (Money::from_cents(220), false));
+        assert_eq!(tea.category, "drink");
+        assert_eq!(menu.available().count(), 0);
+    }
+
+    #[test]
+    fn rejects_bad_menus() {
+        let two_soups = r#"
+            [[item]]
+            id = "soup"
+            name = "Soup"
+            category = "appetizer"
+            price = 6.0
+
+            [[item]]
+            id = "soup"
+            name = "Other soup"
+            category = "appetizer"
+            price = 5.0
+        "#;
+        assert_eq!(
+            Menu::from_toml(two_soups),
+            Err(MenuError::DuplicateId(String::from("soup")))
+        );
+
+        let negative = r#"[{"id": "deal", "name": "Deal", "category": "main", "price": -1}]"#;
+        assert_eq!(
+            Menu::from_json(&format!(r#"{{"item": {negative}}}"#)),
+            Err(MenuError::BadPrice {
+                id: String::from("deal"),
+                price: -1.0
+            })
+        );
+
+        let almost_free = r#"{"item": [{"id": "tap", "name": "Tap water", "category": "drink",
+            "price": -0.001}]}"#;
+        assert!(matches!(
+            Menu::from_json(almost_free),
+            Err(MenuError::BadPrice { .. })
+        ));
+
+        let bad_option = r#"{"item": [{"id": "soup", "name": "Soup", "category": "appetizer",
+            "price": 6, "options": [{"name": "refund", "price": -6}]}]}"#;
+        assert!(matches!(
+            Menu::from_json(bad_option),
+            Err(MenuError::BadPrice { .. })
+        ));
+
+        assert!(matches!(
+            Menu::from_toml("[[item]]\nid = \"soup\"\n"),
+            Err(MenuError::Parse(_))
+        ));
+        assert!(matches!(
+            Menu::from_json(r#"{"item": [{"id": " ", "name": "?", "category": "?", "price": 1}]}"#),
+            Err(MenuError::EmptyId)
+        ));
+    }
+
+    #[test]
+    fn switching_items_off_and_on() {
+        let mut menu = Menu::house();
+        let all = menu.available().count();
+
+        menu.set_available("soup", false).unwrap();
+        assert_eq!(menu.available().count(), all - 1);
+        assert!(!menu.get("soup").unwrap().available);
+        menu.set_available("soup", true).unwrap();
+        assert_eq!(menu.available().count(), all);
+
+        assert_eq!(
+            menu.set_available("caviar", false),
+            Err(MenuError::UnknownItem(String::from("caviar")))
+        );
+    }
+
+    #[test]
+    fn loads_by_extension() {
+        let dir = std::env::temp_dir().join(format!("restaurant-menu-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let toml_path = dir.join("menu.toml");
+        fs::write(&toml_path, HOUSE_MENU).unwrap();
+        assert_eq!(Menu::load(&toml_path).unwrap(), Menu::house());
+
+        let yaml_path = dir.join("menu.yaml");
+        fs::write(&yaml_path, "item: []").unwrap();
+        assert!(matches!(
+            Menu::load(&yaml_path),
+            Err(MenuError::UnknownFormat(_))
+        ));
+        assert!(matches!(
+            Menu::load(&dir.join("missing.toml")),
+            Err(MenuError::Io(_))
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
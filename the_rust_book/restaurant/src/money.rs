@@ -0,0 +1,295 @@
+// Money in whole cents, so adding up a bill never drifts the way floating point does
+// (0.1 + 0.2 is 0.30 here, not 0.30000000000000004).
+//
+// Percentages are a Rate in basis points (hundredths of a percent: 825 is 8.25%), and
+// applying one rounds to the nearest cent, halves away from zero, like a cash register.
+// Sharing an amount out (Money::allocate) never loses or invents a cent: what is left after
+// the even part goes, one cent each, to whoever was closest to getting it, the first in line
+// on a tie.
+
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use serde::{Serialize, Serializer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Money {
+    cents: i64,
+}
+
+impl Money {
+    pub const ZERO: Money = Money { cents: 0 };
+
+    pub fn from_cents(cents: i64) -> Money {
+        Money { cents }
+    }
+
+    pub fn cents(&self) -> i64 {
+        self.cents
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.cents < 0
+    }
+
+    // This much of the amount, rounded to the cent.
+    pub fn at(&self, rate: Rate) -> Money {
+        let exact = i128::from(self.cents) * i128::from(rate.basis_points);
+        let half = i128::from(Rate::WHOLE) / 2;
+        let rounded = if exact >= 0 {
+            (exact + half) / i128::from(Rate::WHOLE)
+        } else {
+            (exact - half) / i128::from(Rate::WHOLE)
+        };
+        Money::from_cents(rounded as i64)
+    }
+
+    // The amount shared out in proportion to the weights, to the cent.
+    // None when there are no weights or they are all zero.
+    pub fn allocate(&self, weights: &[u64]) -> Option<Vec<Money>> {
+        let total_weight: u128 = weights.iter().map(|w| u128::from(*w)).sum();
+        if total_weight == 0 {
+            return None;
+        }
+        let sign = self.cents.signum();
+        let amount = u128::from(self.cents.unsigned_abs());
+
+        let mut shares: Vec<i64> = Vec::with_capacity(weights.len());
+        let mut leftovers: Vec<(u128, usize)> = Vec::with_capacity(weights.len());
+        for (i, weight) in weights.iter().enumerate() {
+            let exact = amount * u128::from(*weight);
+            shares.push((exact / total_weight) as i64);
+            leftovers.push((exact % total_weight, i));
+        }
+
+        let handed_out: i64 = shares.iter().sum();
+        let mut remaining = amount as i64 - handed_out;
+        // The biggest leftover first; on a tie, the first in line.
+        leftovers.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        for (_, i) in leftovers {
+            if remaining == 0 {
+                break;
+            }
+            shares[i] += 1;
+            remaining -= 1;
+        }
+        Some(
+            shares
+                .into_iter()
+                .map(|cents| Money::from_cents(cents * sign))
+                .collect(),
+        )
+    }
+}
+
+// 12.50, -2.00
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sign = if self.cents < 0 { "-" } else { "" };
+        let cents = self.cents.unsigned_abs();
+        let text = format!("{sign}{}.{:02}", cents / 100, cents % 100);
+        // So that {:>10} lines up the amounts of a receipt.
+        f.pad(&text)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoneyError(String);
+
+impl fmt::Display for MoneyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} is not an amount like 12.50", self.0)
+    }
+}
+
+impl std::error::Error for MoneyError {}
+
+// "12", "12.5", "12.50", "-2.00"
+impl FromStr for Money {
+    type Err = MoneyError;
+
+    fn from_str(text: &str) -> Result<Money, MoneyError> {
+        let error = || MoneyError(text.to_string());
+        let trimmed = text.trim();
+        let (negative, digits) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        let all_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+        if whole.is_empty() || fraction.len() > 2 || !all_digits(whole) || !all_digits(fraction) {
+            return Err(error());
+        }
+        let whole: i64 = whole.parse().map_err(|_| error())?;
+        let fraction: i64 = format!("{fraction:0<2}").parse().map_err(|_| error())?;
+        let cents = whole
+            .checked_mul(100)
+            .and_then(|c| c.checked_add(fraction))
+            .ok_or_else(error)?;
+        Ok(Money::from_cents(if negative { -cents } else { cents }))
+    }
+}
+
+// As "12.50": a string, so JSON readers don't turn it back into a float.
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, other: Money) -> Money {
+        Money::from_cents(self.cents + other.cents)
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, other: Money) {
+        self.cents += other.cents;
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, other: Money) -> Money {
+        Money::from_cents(self.cents - other.cents)
+    }
+}
+
+impl SubAssign for Money {
+    fn sub_assign(&mut self, other: Money) {
+        self.cents -= other.cents;
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        Money::from_cents(-self.cents)
+    }
+}
+
+impl Mul<u32> for Money {
+    type Output = Money;
+
+    fn mul(self, times: u32) -> Money {
+        Money::from_cents(self.cents * i64::from(times))
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Money {
+        iter.fold(Money::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Money> for Money {
+    fn sum<I: Iterator<Item = &'a Money>>(iter: I) -> Money {
+        iter.copied().sum()
+    }
+}
+
+// A percentage, in basis points: Rate::percent(10) is 10%, Rate::basis_points(825) is 8.25%.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Rate {
+    basis_points: u32,
+}
+
+impl Rate {
+    // 100%
+    pub const WHOLE: u32 = 10_000;
+
+    pub fn percent(percent: u32) -> Rate {
+        Rate {
+            basis_points: percent * 100,
+        }
+    }
+
+    pub fn basis_points(basis_points: u32) -> Rate {
+        Rate { basis_points }
+    }
+
+    pub fn as_basis_points(&self) -> u32 {
+        self.basis_points
+    }
+}
+
+// 10%, 8.25%
+impl fmt::Display for Rate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let whole = self.basis_points / 100;
+        let fraction = self.basis_points % 100;
+        if fraction == 0 {
+            write!(f, "{whole}%")
+        } else {
+            let fraction = format!("{fraction:02}");
+            write!(f, "{whole}.{}%", fraction.trim_end_matches('0'))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn money(text: &str) -> Money {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn no_drift() {
+        let total: Money = ["0.10", "0.20"].iter().map(|m| money(m)).sum();
+        assert_eq!(total, money("0.30"));
+        assert_eq!(money("9.99") * 3, money("29.97"));
+        assert_eq!((money("5") - money("7.25")).to_string(), "-2.25");
+        assert_eq!(format!("[{:>7}]", money("12.5")), "[  12.50]");
+    }
+
+    #[test]
+    fn parses_amounts() {
+        assert_eq!(money("12").cents(), 1200);
+        assert_eq!(money("0.05").cents(), 5);
+        assert_eq!(money("-2.5").cents(), -250);
+        for bad in ["", "1.234", "1,50", "abc", ".50", "1.-5", "--1"] {
+            assert!(bad.parse::<Money>().is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn rates_round_half_away_from_zero() {
+        assert_eq!(money("10.00").at(Rate::basis_points(825)), money("0.83"));
+        assert_eq!(money("0.50").at(Rate::percent(1)), money("0.01"));
+        assert_eq!(money("-0.50").at(Rate::percent(1)), money("-0.01"));
+        assert_eq!(money("0.49").at(Rate::percent(1)), money("0.00"));
+        assert_eq!(Rate::basis_points(825).to_string(), "8.25%");
+        assert_eq!(Rate::basis_points(750).to_string(), "7.5%");
+        assert_eq!(Rate::percent(10).to_string(), "10%");
+    }
+
+    #[test]
+    fn allocation_keeps_every_cent() {
+        let even = money("10.00").allocate(&[1, 1, 1]).unwrap();
+        assert_eq!(even, [money("3.34"), money("3.33"), money("3.33")]);
+
+        // 1/6, 2/6, 3/6 of 1.00: 16.67, 33.33, 50
+        let shares = money("1.00").allocate(&[1, 2, 3]).unwrap();
+        assert_eq!(shares, [money("0.17"), money("0.33"), money("0.50")]);
+
+        let refund = money("-0.10").allocate(&[1, 1, 1]).unwrap();
+        assert_eq!(refund.iter().sum::<Money>(), money("-0.10"));
+        assert_eq!(refund[0], money("-0.04"));
+
+        assert_eq!(money("1.00").allocate(&[0, 0]), None);
+        assert_eq!(money("1.00").allocate(&[]), None);
+        assert_eq!(
+            money("1.00").allocate(&[0, 1]).unwrap(),
+            [Money::ZERO, money("1.00")]
+        );
+    }
+}
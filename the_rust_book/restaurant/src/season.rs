@@ -0,0 +1,177 @@
+// Seasons, and what fruit they bring to the breakfast.
+//
+// The seasons are the meteorological ones, three whole months each: in the north spring is
+// March to May, summer June to August, and so on. South of the equator it is the other way
+// round (December is summer), which is why the restaurant has to say where it is.
+//
+// The fruit comes from a rule table, FruitRules: each season has a list of fruits, and the
+// breakfast of a day takes one of them, a different one each day. The default table is a
+// sensible start; a restaurant can build its own.
+
+use std::fmt;
+
+use crate::calendar::Date;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Hemisphere {
+    #[default]
+    Northern,
+    Southern,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    pub fn on(date: Date, hemisphere: Hemisphere) -> Season {
+        let northern = match date.month() {
+            3..=5 => Season::Spring,
+            6..=8 => Season::Summer,
+            9..=11 => Season::Autumn,
+            _ => Season::Winter,
+        };
+        match hemisphere {
+            Hemisphere::Northern => northern,
+            Hemisphere::Southern => northern.opposite(),
+        }
+    }
+
+    // The season on the other side of the equator.
+    pub fn opposite(self) -> Season {
+        match self {
+            Season::Spring => Season::Autumn,
+            Season::Summer => Season::Winter,
+            Season::Autumn => Season::Spring,
+            Season::Winter => Season::Summer,
+        }
+    }
+}
+
+impl fmt::Display for Season {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Season::Spring => "spring",
+            Season::Summer => "summer",
+            Season::Autumn => "autumn",
+            Season::Winter => "winter",
+        };
+        write!(f, "{name}")
+    }
+}
+
+// Which fruits each season has.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FruitRules {
+    rules: Vec<(Season, Vec<String>)>,
+}
+
+impl FruitRules {
+    // A table without any fruit: fill it with set().
+    pub fn empty() -> FruitRules {
+        FruitRules { rules: Vec::new() }
+    }
+
+    // Replaces the fruits of the season.
+    pub fn set(mut self, season: Season, fruits: &[&str]) -> FruitRules {
+        let fruits = fruits.iter().map(|f| f.to_string()).collect();
+        match self.rules.iter_mut().find(|(s, _)| *s == season) {
+            Some(rule) => rule.1 = fruits,
+            None => self.rules.push((season, fruits)),
+        }
+        self
+    }
+
+    pub fn fruits(&self, season: Season) -> &[String] {
+        self.rules
+            .iter()
+            .find(|(s, _)| *s == season)
+            .map(|(_, fruits)| fruits.as_slice())
+            .unwrap_or(&[])
+    }
+
+    // The fruit of that day: the season's fruits take turns, one a day.
+    // None if the table has no fruit for the season.
+    pub fn fruit_for(&self, date: Date, hemisphere: Hemisphere) -> Option<&str> {
+        let fruits = self.fruits(Season::on(date, hemisphere));
+        if fruits.is_empty() {
+            return None;
+        }
+        let turn = date.days().rem_euclid(fruits.len() as i64) as usize;
+        Some(&fruits[turn])
+    }
+}
+
+impl Default for FruitRules {
+    fn default() -> FruitRules {
+        FruitRules::empty()
+            .set(Season::Spring, &["strawberries", "cherries"])
+            .set(Season::Summer, &["peaches", "apricots", "melon"])
+            .set(Season::Autumn, &["apples", "pears", "grapes"])
+            .set(Season::Winter, &["oranges", "kiwis"])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(text: &str) -> Date {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn seasons_by_month_and_hemisphere() {
+        let north = Hemisphere::Northern;
+        let south = Hemisphere::Southern;
+
+        assert_eq!(Season::on(date("2026-03-01"), north), Season::Spring);
+        assert_eq!(Season::on(date("2026-07-15"), north), Season::Summer);
+        assert_eq!(Season::on(date("2026-11-30"), north), Season::Autumn);
+        assert_eq!(Season::on(date("2026-12-01"), north), Season::Winter);
+        assert_eq!(Season::on(date("2026-02-28"), north), Season::Winter);
+
+        assert_eq!(Season::on(date("2026-07-15"), south), Season::Winter);
+        assert_eq!(Season::on(date("2026-12-25"), south), Season::Summer);
+    }
+
+    #[test]
+    fn fruit_of_the_day() {
+        let rules = FruitRules::default();
+        let summer_day = date("2026-07-15");
+
+        let fruit = rules.fruit_for(summer_day, Hemisphere::Northern).unwrap();
+        assert!(rules.fruits(Season::Summer).iter().any(|f| f == fruit));
+        // The same day, the same fruit; the next day, the next one.
+        assert_eq!(
+            rules.fruit_for(summer_day, Hemisphere::Northern),
+            Some(fruit)
+        );
+        assert_ne!(
+            rules.fruit_for(summer_day.add_days(1), Hemisphere::Northern),
+            Some(fruit)
+        );
+
+        let winter_fruit = rules.fruit_for(summer_day, Hemisphere::Southern).unwrap();
+        assert!(["oranges", "kiwis"].contains(&winter_fruit));
+    }
+
+    #[test]
+    fn custom_rules() {
+        let rules = FruitRules::empty().set(Season::Summer, &["mango"]);
+        assert_eq!(
+            rules.fruit_for(date("2026-08-01"), Hemisphere::Northern),
+            Some("mango")
+        );
+        assert_eq!(
+            rules.fruit_for(date("2026-01-01"), Hemisphere::Northern),
+            None
+        );
+        let rules = rules.set(Season::Summer, &["figs"]);
+        assert_eq!(rules.fruits(Season::Summer), ["figs"]);
+    }
+}
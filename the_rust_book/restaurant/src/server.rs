@@ -0,0 +1,569 @@
+// The restaurant over HTTP, for the tablets at the door and in the dining room.
+// Only std::net and a thread per connection, like the web server at the end of the Rust book,
+// and only on localhost: there are no passwords, so nobody else may talk to it.
+//
+//   GET  /menu                      {"items": [...]}                     the whole menu
+//   GET  /waitlist                  {"parties": [...]}                   in the order they'll sit
+//   POST /waitlist                  {"name": "Ada", "size": 2}           a walk-in joins the line
+//   POST /orders                    {"table": 3, "items": ["soup"]}      a new order
+//   GET  /orders/<n>                                                     the order and its status
+//   POST /orders/<n>/status         {"status": "in_kitchen"}             moves the order on
+//   GET  /tables/<n>/bill                                                what the table owes
+//
+// Every answer is JSON, and every error is {"error": "..."} with a 4xx status. The handlers
+// do the work with what the library already has (hosting::add_to_waitlist, take_order,
+// deliver_order, Bill::for_table...), under one lock for the whole restaurant.
+// One request per connection: the answer says Connection: close.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::billing::{Bill, BillError};
+use crate::hosting::{self, FloorPlan, Waitlist};
+use crate::menu::Menu;
+use crate::serving::{self, Order, OrderError, OrderStatus, Orders};
+
+pub const DEFAULT_ADDR: &str = "127.0.0.1:7979";
+
+// Larger requests than this are not orders, whatever they say.
+const MAX_BODY: usize = 64 * 1024;
+const MAX_HEADERS: usize = 100;
+// The request line and every header: a line that goes on longer is not from a tablet.
+const MAX_LINE: usize = 8 * 1024;
+// A client that stops talking halfway doesn't keep its thread forever.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Request {
+    method: String,
+    // Without the query string.
+    path: String,
+    body: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Response {
+    status: u16,
+    body: Value,
+}
+
+impl Response {
+    fn ok(body: Value) -> Response {
+        Response { status: 200, body }
+    }
+
+    fn created(body: Value) -> Response {
+        Response { status: 201, body }
+    }
+
+    fn error(status: u16, message: impl ToString) -> Response {
+        Response {
+            status,
+            body: json!({ "error": message.to_string() }),
+        }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            201 => "Created",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            409 => "Conflict",
+            413 => "Payload Too Large",
+            422 => "Unprocessable Entity",
+            431 => "Request Header Fields Too Large",
+            _ => "Error",
+        }
+    }
+
+    fn write_to(&self, stream: &mut impl Write) -> io::Result<()> {
+        let body = self.body.to_string();
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+             Connection: close\r\n\r\n{body}",
+            self.status,
+            self.reason(),
+            body.len()
+        )?;
+        stream.flush()
+    }
+}
+
+// Everything the handlers share.
+struct Restaurant {
+    menu: Menu,
+    floor: FloorPlan,
+    waitlist: Waitlist,
+    orders: Orders,
+}
+
+impl Restaurant {
+    fn new(menu: Menu, floor: FloorPlan) -> Restaurant {
+        Restaurant {
+            menu,
+            floor,
+            waitlist: Waitlist::new(),
+            orders: Orders::new(),
+        }
+    }
+}
+
+pub struct Server {
+    listener: TcpListener,
+    restaurant: Arc<Mutex<Restaurant>>,
+}
+
+impl Server {
+    // Bind to port 0 to let the OS pick a free port, then ask local_addr which one it was.
+    // Addresses that are not loopback are refused.
+    pub fn bind(addr: impl ToSocketAddrs, menu: Menu, floor: FloorPlan) -> io::Result<Server> {
+        let local: Vec<SocketAddr> = addr
+            .to_socket_addrs()?
+            .filter(|addr| addr.ip().is_loopback())
+            .collect();
+        if local.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the restaurant only listens on localhost",
+            ));
+        }
+        Ok(Server {
+            listener: TcpListener::bind(&local[..])?,
+            restaurant: Arc::new(Mutex::new(Restaurant::new(menu, floor))),
+        })
+    }
+
+    // The house menu and tables.
+    pub fn house(addr: impl ToSocketAddrs) -> io::Result<Server> {
+        Server::bind(addr, Menu::house(), FloorPlan::house())
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    // Answers requests forever, one thread per connection.
+    pub fn run(self) {
+        for stream in self.listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    eprintln!("Connection failed: {e}");
+                    continue;
+                }
+            };
+
+            let restaurant = Arc::clone(&self.restaurant);
+            thread::spawn(move || {
+                if let Err(e) = handle_connection(stream, &restaurant) {
+                    eprintln!("Connection error: {e}");
+                }
+            });
+        }
+    }
+
+    // Runs the server on a background thread, which is what the tests need.
+    pub fn spawn(self) -> JoinHandle<()> {
+        thread::spawn(move || self.run())
+    }
+}
+
+fn handle_connection(stream: TcpStream, restaurant: &Mutex<Restaurant>) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    let response = match read_request(&mut BufReader::new(stream)) {
+        Ok(request) => {
+            // One handler that panicked shouldn't close the whole restaurant.
+            let mut restaurant = restaurant.lock().unwrap_or_else(|e| e.into_inner());
+            route(&mut restaurant, &request)
+        }
+        Err(response) => response,
+    };
+    response.write_to(&mut writer)
+}
+
+// One line, no longer than MAX_LINE: None if it doesn't end by then.
+fn read_line(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut line = String::new();
+    reader.by_ref().take(MAX_LINE as u64).read_line(&mut line)?;
+    if line.len() == MAX_LINE && !line.ends_with('\n') {
+        return Ok(None);
+    }
+    Ok(Some(line))
+}
+
+// The request line, the headers (only Content-Length matters) and the body.
+// A request that can't be read is answered with the error right away.
+fn read_request(reader: &mut impl BufRead) -> Result<Request, Response> {
+    let bad = |message: &str| Response::error(400, message);
+    let line = read_line(reader)
+        .map_err(|_| bad("could not read the request"))?
+        .ok_or_else(|| bad("the request line is too long"))?;
+    let mut words = line.split_whitespace();
+    let (Some(method), Some(target), Some(_version)) = (words.next(), words.next(), words.next())
+    else {
+        return Err(bad("the request line is not METHOD /path HTTP/1.1"));
+    };
+    let path = target.split('?').next().unwrap_or_default().to_string();
+    let method = method.to_string();
+
+    let mut length = 0;
+    for _ in 0..MAX_HEADERS {
+        let header = read_line(reader)
+            .map_err(|_| bad("could not read the headers"))?
+            .ok_or_else(|| Response::error(431, "a header is too long"))?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            let mut body = vec![0; length];
+            reader
+                .read_exact(&mut body)
+                .map_err(|_| bad("the body is shorter than its Content-Length"))?;
+            let body = String::from_utf8(body).map_err(|_| bad("the body is not UTF-8"))?;
+            return Ok(Request { method, path, body });
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.trim().eq_ignore_ascii_case("content-length")
+        {
+            length = value
+                .trim()
+                .parse()
+                .map_err(|_| bad("Content-Length is not a number"))?;
+            if length > MAX_BODY {
+                return Err(Response::error(413, "the body is too large"));
+            }
+        }
+    }
+    Err(Response::error(431, "too many headers"))
+}
+
+fn route(restaurant: &mut Restaurant, request: &Request) -> Response {
+    let segments: Vec<&str> = request.path.split('/').filter(|s| !s.is_empty()).collect();
+    let method = request.method.as_str();
+    match (method, segments.as_slice()) {
+        ("GET", ["menu"]) => Response::ok(json!({ "items": restaurant.menu.items() })),
+        ("GET", ["waitlist"]) => Response::ok(waitlist_json(&restaurant.waitlist)),
+        ("POST", ["waitlist"]) => add_to_waitlist(restaurant, &request.body),
+        ("POST", ["orders"]) => take_order(restaurant, &request.body),
+        ("GET", ["orders", number]) => match find_order(restaurant, number) {
+            Ok(order) => Response::ok(order_json(order)),
+            Err(response) => response,
+        },
+        ("POST", ["orders", number, "status"]) => advance_order(restaurant, number, &request.body),
+        ("GET", ["tables", number, "bill"]) => bill(restaurant, number),
+        (_, ["menu"] | ["waitlist"] | ["orders"] | ["orders", _] | ["orders", _, "status"])
+        | (_, ["tables", _, "bill"]) => {
+            Response::error(405, format!("{method} is not allowed on {}", request.path))
+        }
+        _ => Response::error(404, format!("there is nothing at {}", request.path)),
+    }
+}
+
+fn parse_body<'a, T: Deserialize<'a>>(body: &'a str) -> Result<T, Response> {
+    serde_json::from_str(body).map_err(|e| Response::error(400, format!("bad JSON: {e}")))
+}
+
+fn waitlist_json(waitlist: &Waitlist) -> Value {
+    let parties: Vec<Value> = waitlist
+        .parties()
+        .iter()
+        .enumerate()
+        .map(|(at, party)| {
+            json!({
+                "party": party.id.number(),
+                "name": party.name,
+                "size": party.size,
+                "position": at + 1,
+                "priority": party.priority.map(|p| format!("{p:?}").to_lowercase()),
+            })
+        })
+        .collect();
+    json!({ "parties": parties })
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NewParty {
+    name: String,
+    size: u32,
+}
+
+fn add_to_waitlist(restaurant: &mut Restaurant, body: &str) -> Response {
+    let party: NewParty = match parse_body(body) {
+        Ok(party) => party,
+        Err(response) => return response,
+    };
+    let waitlist = &mut restaurant.waitlist;
+    match hosting::add_to_waitlist(waitlist, &party.name, party.size) {
+        Ok(id) => Response::created(json!({
+            "party": id.number(),
+            "position": waitlist.position(id),
+        })),
+        Err(e) => Response::error(422, e),
+    }
+}
+
+fn order_json(order: &Order) -> Value {
+    json!({
+        "order": order.id().number(),
+        "table": order.table().number(),
+        "items": order.items(),
+        "status": order.status(),
+    })
+}
+
+fn order_error(e: OrderError) -> Response {
+    let status = match e {
+        OrderError::NothingOrdered => 422,
+        OrderError::UnknownOrder(_) => 404,
+        OrderError::IllegalTransition { .. } | OrderError::BackInTime { .. } => 409,
+    };
+    Response::error(status, e)
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NewOrder {
+    table: u32,
+    items: Vec<String>,
+}
+
+fn take_order(restaurant: &mut Restaurant, body: &str) -> Response {
+    let order: NewOrder = match parse_body(body) {
+        Ok(order) => order,
+        Err(response) => return response,
+    };
+    let Some(table) = restaurant.floor.table_numbered(order.table) else {
+        return Response::error(404, format!("there is no table {}", order.table));
+    };
+    // The kitchen can only make what is on the menu today.
+    for item in &order.items {
+        match restaurant.menu.get(item) {
+            Some(dish) if dish.available => {}
+            Some(_) => return Response::error(409, format!("{item} is not available today")),
+            None => return Response::error(422, format!("{item} is not on the menu")),
+        }
+    }
+
+    let items: Vec<&str> = order.items.iter().map(String::as_str).collect();
+    match serving::take_order(&mut restaurant.orders, table, &items) {
+        Ok(id) => Response::created(order_json(
+            restaurant
+                .orders
+                .get(id)
+                .expect("the order was just placed"),
+        )),
+        Err(e) => order_error(e),
+    }
+}
+
+fn find_order<'a>(restaurant: &'a Restaurant, number: &str) -> Result<&'a Order, Response> {
+    number
+        .parse()
+        .ok()
+        .and_then(|n| restaurant.orders.numbered(n))
+        .and_then(|id| restaurant.orders.get(id))
+        .ok_or_else(|| Response::error(404, format!("there is no order {number}")))
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NewStatus {
+    status: OrderStatus,
+}
+
+fn advance_order(restaurant: &mut Restaurant, number: &str, body: &str) -> Response {
+    let id = match find_order(restaurant, number) {
+        Ok(order) => order.id(),
+        Err(response) => return response,
+    };
+    let to = match parse_body::<NewStatus>(body) {
+        Ok(new) => new.status,
+        Err(response) => return response,
+    };
+
+    let orders = &mut restaurant.orders;
+    // The steps of the book where it has one, the plain move otherwise.
+    let moved = match to {
+        OrderStatus::Ready => crate::deliver_order(orders, id),
+        OrderStatus::Served => serving::serve_order(orders, id),
+        OrderStatus::Paid => serving::take_payment(orders, id),
+        OrderStatus::Placed | OrderStatus::InKitchen | OrderStatus::Cancelled => {
+            orders.advance(id, to, Instant::now())
+        }
+    };
+    match moved {
+        Ok(()) => Response::ok(order_json(orders.get(id).expect("the order exists"))),
+        Err(e) => order_error(e),
+    }
+}
+
+fn bill(restaurant: &Restaurant, number: &str) -> Response {
+    let Some(table) = number
+        .parse()
+        .ok()
+        .and_then(|n| restaurant.floor.table_numbered(n))
+    else {
+        return Response::error(404, format!("there is no table {number}"));
+    };
+    let bill = match Bill::for_table(&restaurant.orders, table, &restaurant.menu) {
+        Ok(bill) => bill,
+        Err(e @ BillError::NothingServed(_)) => return Response::error(409, e),
+        Err(e) => return Response::error(422, e),
+    };
+
+    let lines: Vec<Value> = bill
+        .lines()
+        .iter()
+        .map(|line| {
+            json!({
+                "item": line.item,
+                "name": line.name,
+                "price": line.price,
+                "order": line.order.map(|id| id.number()),
+            })
+        })
+        .collect();
+    let totals = bill.totals();
+    Response::ok(json!({
+        "table": table.number(),
+        "lines": lines,
+        "subtotal": totals.subtotal,
+        "discount": totals.discount,
+        "service": totals.service,
+        "tax": totals.tax,
+        "tip": totals.tip,
+        "total": totals.total,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: &str, path: &str, body: &str) -> Request {
+        Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    fn house() -> Restaurant {
+        Restaurant::new(Menu::house(), FloorPlan::house())
+    }
+
+    #[test]
+    fn reads_a_request() {
+        let raw = "POST /waitlist?from=door HTTP/1.1\r\nHost: localhost\r\n\
+                   content-length: 25\r\n\r\n{\"name\":\"Ada\",\"size\":2}\r\n";
+        let request = read_request(&mut raw.as_bytes()).unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/waitlist");
+        assert_eq!(request.body, "{\"name\":\"Ada\",\"size\":2}\r\n");
+
+        let garbage = read_request(&mut "hello\r\n\r\n".as_bytes()).unwrap_err();
+        assert_eq!(garbage.status, 400);
+        let short = "POST /orders HTTP/1.1\r\nContent-Length: 10\r\n\r\n{}";
+        assert_eq!(read_request(&mut short.as_bytes()).unwrap_err().status, 400);
+        let huge = format!(
+            "POST /orders HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            MAX_BODY + 1
+        );
+        assert_eq!(read_request(&mut huge.as_bytes()).unwrap_err().status, 413);
+
+        let long_path = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_LINE));
+        assert_eq!(
+            read_request(&mut long_path.as_bytes()).unwrap_err().status,
+            400
+        );
+        let long_header = format!("GET /menu HTTP/1.1\r\nX: {}\r\n\r\n", "a".repeat(MAX_LINE));
+        assert_eq!(
+            read_request(&mut long_header.as_bytes())
+                .unwrap_err()
+                .status,
+            431
+        );
+        let many = format!(
+            "GET /menu HTTP/1.1\r\n{}\r\n",
+            "X: y\r\n".repeat(MAX_HEADERS)
+        );
+        assert_eq!(read_request(&mut many.as_bytes()).unwrap_err().status, 431);
+    }
+
+    #[test]
+    fn routes() {
+        let mut restaurant = house();
+        let menu = route(&mut restaurant, &request("GET", "/menu", ""));
+        assert_eq!(menu.status, 200);
+        assert_eq!(menu.body["items"][1]["price"], "6.00");
+
+        assert_eq!(
+            route(&mut restaurant, &request("GET", "/kitchen", "")).status,
+            404
+        );
+        assert_eq!(
+            route(&mut restaurant, &request("DELETE", "/menu", "")).status,
+            405
+        );
+        assert_eq!(
+            route(
+                &mut restaurant,
+                &request("POST", "/waitlist", "{\"name\": ")
+            )
+            .status,
+            400
+        );
+        let empty = route(
+            &mut restaurant,
+            &request("POST", "/waitlist", r#"{"name": " ", "size": 2}"#),
+        );
+        assert_eq!(empty.status, 422);
+        assert_eq!(empty.body["error"], "a party needs a name");
+    }
+
+    #[test]
+    fn orders_only_what_the_menu_has() {
+        let mut restaurant = house();
+        restaurant.menu.set_available("soup", false).unwrap();
+        let order = |restaurant: &mut Restaurant, body: &str| {
+            route(restaurant, &request("POST", "/orders", body)).status
+        };
+
+        assert_eq!(
+            order(&mut restaurant, r#"{"table": 1, "items": ["soup"]}"#),
+            409
+        );
+        assert_eq!(
+            order(&mut restaurant, r#"{"table": 1, "items": ["caviar"]}"#),
+            422
+        );
+        assert_eq!(
+            order(&mut restaurant, r#"{"table": 99, "items": ["salad"]}"#),
+            404
+        );
+        assert_eq!(order(&mut restaurant, r#"{"table": 1, "items": []}"#), 422);
+        assert_eq!(
+            order(&mut restaurant, r#"{"table": 1, "items": ["salad"]}"#),
+            201
+        );
+        assert_eq!(
+            route(&mut restaurant, &request("GET", "/orders/1", "")).body["status"],
+            "placed"
+        );
+    }
+
+    #[test]
+    fn only_localhost() {
+        let far = Server::house("0.0.0.0:0").err().unwrap();
+        assert_eq!(far.kind(), io::ErrorKind::InvalidInput);
+    }
+}
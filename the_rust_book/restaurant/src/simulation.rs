@@ -0,0 +1,801 @@
+// A day of service, simulated, to try out staffing before trying it on real guests.
+//
+// Guests arrive at random (a Poisson process with a rate for every hour the doors are open,
+// drawn from a seed), and from there everything is an event on a made-up clock:
+//
+//   Arrive --> waitlist --> seated --Order--> kitchen --DishDone...--> served --Leave--> paid
+//                  |
+//                  +--GiveUp (waited longer than their patience)
+//
+// Hosting, serving, billing and the pantry are the real thing (Waitlist, FloorPlan, Orders,
+// Bill, Stock), run with Instants made from the simulated clock. The cooking is not: Kitchen
+// cooks on threads with real sleeps, which would make every run different. Here each station
+// has its cooks and a line of tickets, and a dish takes exactly the cook time the Routing
+// gives it. The ingredients of an order are taken with Stock::take_for all the same, so a
+// party whose order the pantry can't make leaves without eating, and a dish that runs out is
+// off the menu for the rest of the day.
+//
+// Events at the same time happen in the order they were scheduled, and the only randomness
+// comes from the seed, so the same Config always gives the same Report.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, VecDeque};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::back_of_house::stock::Stock;
+use crate::back_of_house::{Routing, Station};
+use crate::billing::Bill;
+use crate::hosting::{self, FloorPlan, PartyId, Seating, Waitlist};
+use crate::menu::Menu;
+use crate::money::Money;
+use crate::serving::{OrderId, OrderStatus, Orders};
+
+const HOUR: Duration = Duration::from_secs(60 * 60);
+// Arrivals are timed to the second: a busier door than one party a second is not a restaurant.
+const MAX_ARRIVALS_PER_HOUR: f64 = 3600.0;
+
+fn minutes(m: u64) -> Duration {
+    Duration::from_secs(m * 60)
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub seed: u64,
+    // Parties expected in each hour the doors are open: the day is as long as the list.
+    // Service goes on after the last hour until the last party has left.
+    pub arrivals_per_hour: Vec<f64>,
+    // How often parties of 1, 2, 3... guests come, as weights.
+    pub party_sizes: Vec<u32>,
+    // How long a party waits for a table before it goes somewhere else.
+    pub patience: Duration,
+    // From sitting down to the order going to the kitchen.
+    pub ordering: Duration,
+    // From the food on the table to the party leaving: somewhere between the two.
+    pub meal: (Duration, Duration),
+    pub floor: FloorPlan,
+    pub cooks: Vec<(Station, usize)>,
+    pub routing: Routing,
+    // Every guest orders one dish, any of the available ones the kitchen has a routing for
+    // and the pantry a recipe for. A menu without any is a SimulationError.
+    pub menu: Menu,
+    // The pantry at the opening of the doors.
+    pub stock: Stock,
+}
+
+impl Config {
+    // The house on an ordinary day: lunch, a quiet afternoon, dinner, from 11:00 to 22:00.
+    pub fn house(seed: u64) -> Config {
+        Config {
+            seed,
+            arrivals_per_hour: vec![6.0, 12.0, 10.0, 3.0, 2.0, 2.0, 4.0, 10.0, 14.0, 10.0, 4.0],
+            party_sizes: vec![2, 8, 2, 4, 1, 1],
+            patience: minutes(30),
+            ordering: minutes(5),
+            meal: (minutes(30), minutes(60)),
+            floor: FloorPlan::house(),
+            cooks: vec![
+                (Station::Grill, 2),
+                (Station::Fryer, 1),
+                (Station::Stove, 2),
+                (Station::ColdPrep, 1),
+            ],
+            routing: Routing::house(),
+            menu: Menu::house(),
+            stock: Stock::house(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SimulationError {
+    // No dish of the menu can be ordered: the guests would sit at their tables forever.
+    NothingToOrder,
+    // Not a number of parties: negative, infinite, NaN or more than one a second.
+    BadRate { hour: usize, rate: f64 },
+}
+
+impl fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SimulationError::NothingToOrder => write!(
+                f,
+                "no available dish of the menu has both a routing and a recipe"
+            ),
+            SimulationError::BadRate { hour, rate } => write!(
+                f,
+                "{rate} parties in hour {hour} is not a rate of arrivals (0 to {MAX_ARRIVALS_PER_HOUR})"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SimulationError {}
+
+// What a day looked like. Times are from the opening of the doors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub seed: u64,
+    pub parties: usize,
+    pub guests: u32,
+    pub seated: usize,
+    // Gave up waiting.
+    pub walked_away: usize,
+    // Larger than any table of the house.
+    pub turned_away: usize,
+    // From arriving to sitting down, for the parties that were seated.
+    pub average_wait: Duration,
+    pub p95_wait: Duration,
+    // The share of table time in use from opening until the last party left, 0 to 1.
+    pub table_utilisation: f64,
+    // Tickets waiting for a cook (not the ones on the fire).
+    pub peak_backlog: usize,
+    pub average_backlog: f64,
+    pub orders_served: usize,
+    // Orders the kitchen never finished: a station without cooks.
+    pub orders_unserved: usize,
+    // Seated, but the pantry couldn't make their order: they left without eating.
+    pub out_of_stock: usize,
+    // The dishes the pantry ran out of, in the order they did.
+    pub sold_out: Vec<String>,
+    pub revenue: Money,
+    // When the last party left.
+    pub day_length: Duration,
+}
+
+// 11h32
+fn clock(d: Duration) -> String {
+    let minutes = d.as_secs() / 60;
+    format!("{}h{:02}", minutes / 60, minutes % 60)
+}
+
+// 6m06s
+fn wait(d: Duration) -> String {
+    let seconds = d.as_secs();
+    format!("{}m{:02}s", seconds / 60, seconds % 60)
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "seed {}: {} parties, {} guests",
+            self.seed, self.parties, self.guests
+        )?;
+        writeln!(
+            f,
+            "  seated {}, walked away {}, turned away {}",
+            self.seated, self.walked_away, self.turned_away
+        )?;
+        writeln!(
+            f,
+            "  wait: average {}, p95 {}",
+            wait(self.average_wait),
+            wait(self.p95_wait)
+        )?;
+        writeln!(
+            f,
+            "  tables in use {:.1}% of a {} day",
+            self.table_utilisation * 100.0,
+            clock(self.day_length)
+        )?;
+        writeln!(
+            f,
+            "  kitchen backlog: {} tickets at most, {:.2} on average",
+            self.peak_backlog, self.average_backlog
+        )?;
+        writeln!(
+            f,
+            "  {} orders served ({} never done), revenue {}",
+            self.orders_served, self.orders_unserved, self.revenue
+        )?;
+        write!(f, "  {} parties left hungry", self.out_of_stock)?;
+        if !self.sold_out.is_empty() {
+            write!(f, ", sold out: {}", self.sold_out.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Event {
+    Arrive { size: u32 },
+    GiveUp(PartyId),
+    Order(PartyId),
+    DishDone { station: Station, order: OrderId },
+    Leave(PartyId),
+}
+
+#[derive(Debug, Default)]
+struct Line {
+    free_cooks: usize,
+    tickets: VecDeque<(OrderId, Duration)>,
+}
+
+struct Table {
+    seating: Seating,
+    since: Duration,
+    order: Option<OrderId>,
+}
+
+struct Service<'a> {
+    config: &'a Config,
+    rng: StdRng,
+    opening: Instant,
+    now: Duration,
+    // Soonest first; the number keeps events at the same time in the order they were scheduled.
+    events: BinaryHeap<Reverse<(Duration, u64, Event)>>,
+    scheduled: u64,
+    waitlist: Waitlist,
+    floor: FloorPlan,
+    orders: Orders,
+    arrived: HashMap<PartyId, Duration>,
+    at_table: HashMap<PartyId, Table>,
+    // In station order, so that starting the cooks never depends on a hash.
+    lines: BTreeMap<Station, Line>,
+    dishes_left: HashMap<OrderId, usize>,
+    stock: Stock,
+    // What the guests can order: the pantry still has at least one portion of each.
+    dishes: Vec<String>,
+    sold_out: Vec<String>,
+
+    parties: usize,
+    guests: u32,
+    walked_away: usize,
+    turned_away: usize,
+    out_of_stock: usize,
+    waits: Vec<Duration>,
+    table_time: Duration,
+    peak_backlog: usize,
+    // Tickets waiting multiplied by how long they waited, summed.
+    backlog_seconds: f64,
+    revenue: Money,
+}
+
+impl Service<'_> {
+    fn schedule(&mut self, after: Duration, event: Event) {
+        self.scheduled += 1;
+        self.events
+            .push(Reverse((self.now + after, self.scheduled, event)));
+    }
+
+    fn instant(&self) -> Instant {
+        self.opening + self.now
+    }
+
+    fn backlog(&self) -> usize {
+        self.lines.values().map(|line| line.tickets.len()).sum()
+    }
+
+    // Takes the dishes the pantry has no portion of left off the list.
+    fn update_dishes(&mut self) {
+        let stock = &self.stock;
+        let (left, gone): (Vec<String>, Vec<String>) = std::mem::take(&mut self.dishes)
+            .into_iter()
+            .partition(|item| stock.portions_left(item).is_ok_and(|n| n > 0));
+        self.dishes = left;
+        self.sold_out.extend(gone);
+    }
+
+    // The arrivals of the whole day, hour after hour.
+    fn arrivals(&mut self) {
+        let sizes = &self.config.party_sizes;
+        let total: u32 = sizes.iter().sum();
+        let mut arrivals = Vec::new();
+        for (hour, rate) in self.config.arrivals_per_hour.iter().enumerate() {
+            if *rate <= 0.0 || total == 0 {
+                continue;
+            }
+            let end = HOUR * (hour as u32 + 1);
+            let mut at = HOUR * hour as u32;
+            loop {
+                // Exponential gaps between arrivals, in hours.
+                let gap = -(1.0 - self.rng.r#gen::<f64>()).ln() / rate * 3600.0;
+                // A slow hour can draw a gap too long for any Duration: it's past the hour anyway.
+                if gap >= (end - at).as_secs_f64() {
+                    break;
+                }
+                at += Duration::from_secs(gap as u64);
+                let mut pick = self.rng.gen_range(0..total);
+                let size = sizes
+                    .iter()
+                    .position(|weight| {
+                        if pick < *weight {
+                            return true;
+                        }
+                        pick -= weight;
+                        false
+                    })
+                    .expect("the pick is below the total") as u32
+                    + 1;
+                arrivals.push((at, size));
+            }
+        }
+        for (at, size) in arrivals {
+            self.schedule(at, Event::Arrive { size });
+        }
+    }
+
+    fn run(mut self) -> Report {
+        self.arrivals();
+        while let Some(Reverse((at, _, event))) = self.events.pop() {
+            // Parties that were seated in time don't give up: that must not make the day longer.
+            if let Event::GiveUp(party) = event
+                && self.waitlist.get(party).is_none()
+            {
+                continue;
+            }
+            self.backlog_seconds += self.backlog() as f64 * (at - self.now).as_secs_f64();
+            self.now = at;
+            match event {
+                Event::Arrive { size } => self.arrive(size),
+                Event::GiveUp(party) => {
+                    self.waitlist.cancel(party);
+                    self.walked_away += 1;
+                    // They may have been the ones holding up the line.
+                    self.seat_waiting();
+                }
+                Event::Order(party) => self.order(party),
+                Event::DishDone { station, order } => self.dish_done(station, order),
+                Event::Leave(party) => self.leave(party),
+            }
+            self.peak_backlog = self.peak_backlog.max(self.backlog());
+        }
+        self.report()
+    }
+
+    fn arrive(&mut self, size: u32) {
+        self.parties += 1;
+        self.guests += size;
+        if size > self.floor.largest_party(None) {
+            self.turned_away += 1;
+            return;
+        }
+        let name = format!("Party {}", self.parties);
+        let party = self
+            .waitlist
+            .add(&name, size, None, self.instant())
+            .expect("a named party of at least one");
+        self.arrived.insert(party, self.now);
+        self.schedule(self.config.patience, Event::GiveUp(party));
+        self.seat_waiting();
+    }
+
+    // Seats the line from the front for as long as the next party fits.
+    fn seat_waiting(&mut self) {
+        while let Ok(Some((party, seating))) =
+            hosting::seat_at_table(&mut self.waitlist, &mut self.floor, None)
+        {
+            self.waits.push(self.now - self.arrived[&party.id]);
+            self.at_table.insert(
+                party.id,
+                Table {
+                    seating,
+                    since: self.now,
+                    order: None,
+                },
+            );
+            self.schedule(self.config.ordering, Event::Order(party.id));
+        }
+    }
+
+    fn order(&mut self, party: PartyId) {
+        let table = &self.at_table[&party];
+        let size = table.seating.seats - table.seating.wasted;
+        let table_id = table.seating.tables[0];
+        if self.dishes.is_empty() {
+            self.send_away(party);
+            return;
+        }
+        let items: Vec<String> = (0..size)
+            .map(|_| self.dishes[self.rng.gen_range(0..self.dishes.len())].clone())
+            .collect();
+        // Every dish has a portion left, but the whole order may still be too much.
+        if self.stock.take_for(&items).is_err() {
+            self.send_away(party);
+            return;
+        }
+        self.update_dishes();
+        let items: Vec<&str> = items.iter().map(String::as_str).collect();
+
+        let at = self.instant();
+        let order = self
+            .orders
+            .place(table_id, &items, at)
+            .expect("every guest orders a dish");
+        self.orders
+            .advance(order, OrderStatus::InKitchen, at)
+            .expect("a new order goes to the kitchen");
+        self.at_table.get_mut(&party).expect("seated").order = Some(order);
+        self.dishes_left.insert(order, items.len());
+
+        for item in &items {
+            let dish = self.config.routing.dish(item).expect("only routed dishes");
+            self.lines
+                .entry(dish.station)
+                .or_default()
+                .tickets
+                .push_back((order, dish.cook_time));
+        }
+        let stations: Vec<Station> = self.lines.keys().copied().collect();
+        for station in stations {
+            self.start_cooking(station);
+        }
+    }
+
+    // Every free cook of the station takes the next ticket.
+    fn start_cooking(&mut self, station: Station) {
+        let mut started = Vec::new();
+        let line = self.lines.entry(station).or_default();
+        while line.free_cooks > 0 {
+            let Some((order, cook_time)) = line.tickets.pop_front() else {
+                break;
+            };
+            line.free_cooks -= 1;
+            started.push((order, cook_time));
+        }
+        for (order, cook_time) in started {
+            self.schedule(cook_time, Event::DishDone { station, order });
+        }
+    }
+
+    fn dish_done(&mut self, station: Station, order: OrderId) {
+        self.lines.entry(station).or_default().free_cooks += 1;
+        self.start_cooking(station);
+
+        let left = self.dishes_left.get_mut(&order).expect("a cooking order");
+        *left -= 1;
+        if *left > 0 {
+            return;
+        }
+        // The last dish: to the pass and straight to the table.
+        let at = self.instant();
+        self.orders
+            .advance(order, OrderStatus::Ready, at)
+            .expect("the order was in the kitchen");
+        self.orders
+            .advance(order, OrderStatus::Served, at)
+            .expect("the order was ready");
+
+        let party = self
+            .at_table
+            .iter()
+            .find(|(_, table)| table.order == Some(order))
+            .map(|(party, _)| *party)
+            .expect("the order of a seated party");
+        let (shortest, longest) = self.config.meal;
+        let meal = if longest > shortest {
+            shortest + Duration::from_secs(self.rng.gen_range(0..=(longest - shortest).as_secs()))
+        } else {
+            shortest
+        };
+        self.schedule(meal, Event::Leave(party));
+    }
+
+    fn leave(&mut self, party: PartyId) {
+        let table = self.at_table.remove(&party).expect("a seated party");
+        let table_id = table.seating.tables[0];
+        let bill = Bill::for_table(&self.orders, table_id, &self.config.menu)
+            .expect("the party was served");
+        self.revenue += bill.total();
+        let at = self.instant();
+        if let Some(order) = table.order {
+            self.orders
+                .advance(order, OrderStatus::Paid, at)
+                .expect("the order was served");
+        }
+        self.free_tables(party, table);
+    }
+
+    // The pantry can't make what they want: they leave without ordering or paying.
+    fn send_away(&mut self, party: PartyId) {
+        let table = self.at_table.remove(&party).expect("a seated party");
+        self.out_of_stock += 1;
+        self.free_tables(party, table);
+    }
+
+    fn free_tables(&mut self, party: PartyId, table: Table) {
+        self.table_time += (self.now - table.since) * table.seating.tables.len() as u32;
+        let at = self.instant();
+        hosting::party_leaves(&mut self.waitlist, &mut self.floor, party, at)
+            .expect("the party was at its tables");
+        self.seat_waiting();
+    }
+
+    fn report(self) -> Report {
+        let mut waits = self.waits.clone();
+        waits.sort();
+        let average_wait = if waits.is_empty() {
+            Duration::ZERO
+        } else {
+            waits.iter().sum::<Duration>() / waits.len() as u32
+        };
+        // The nearest rank: the wait that 95% of the parties didn't go over.
+        let p95_wait = match waits.len() {
+            0 => Duration::ZERO,
+            n => waits[(n * 95).div_ceil(100) - 1],
+        };
+
+        let day = self.now.as_secs_f64();
+        let tables = self.floor.tables().len() as f64;
+        let (table_utilisation, average_backlog) = if day > 0.0 {
+            (
+                self.table_time.as_secs_f64() / (tables * day),
+                self.backlog_seconds / day,
+            )
+        } else {
+            (0.0, 0.0)
+        };
+        let orders_served = self
+            .orders
+            .orders()
+            .iter()
+            .filter(|o| o.status() == OrderStatus::Paid)
+            .count();
+
+        Report {
+            seed: self.config.seed,
+            parties: self.parties,
+            guests: self.guests,
+            seated: self.waits.len(),
+            walked_away: self.walked_away,
+            turned_away: self.turned_away,
+            average_wait,
+            p95_wait,
+            table_utilisation,
+            peak_backlog: self.peak_backlog,
+            average_backlog,
+            orders_served,
+            orders_unserved: self.orders.orders().len() - orders_served,
+            out_of_stock: self.out_of_stock,
+            sold_out: self.sold_out,
+            revenue: self.revenue,
+            day_length: self.now,
+        }
+    }
+}
+
+pub fn simulate(config: &Config) -> Result<Report, SimulationError> {
+    for (hour, rate) in config.arrivals_per_hour.iter().enumerate() {
+        if !(0.0..=MAX_ARRIVALS_PER_HOUR).contains(rate) {
+            return Err(SimulationError::BadRate { hour, rate: *rate });
+        }
+    }
+    let lines = config
+        .cooks
+        .iter()
+        .map(|(station, cooks)| {
+            (
+                *station,
+                Line {
+                    free_cooks: *cooks,
+                    tickets: VecDeque::new(),
+                },
+            )
+        })
+        .collect();
+    let dishes: Vec<String> = config
+        .menu
+        .available()
+        .filter(|item| config.routing.dish(&item.id).is_some())
+        .filter(|item| config.stock.recipes().get(&item.id).is_some())
+        .map(|item| item.id.clone())
+        .collect();
+    if dishes.is_empty() {
+        return Err(SimulationError::NothingToOrder);
+    }
+
+    let mut service = Service {
+        config,
+        rng: StdRng::seed_from_u64(config.seed),
+        opening: Instant::now(),
+        now: Duration::ZERO,
+        events: BinaryHeap::new(),
+        scheduled: 0,
+        waitlist: Waitlist::new(),
+        floor: config.floor.layout(),
+        orders: Orders::new(),
+        arrived: HashMap::new(),
+        at_table: HashMap::new(),
+        lines,
+        dishes_left: HashMap::new(),
+        stock: config.stock.clone(),
+        dishes,
+        sold_out: Vec::new(),
+        parties: 0,
+        guests: 0,
+        walked_away: 0,
+        turned_away: 0,
+        out_of_stock: 0,
+        waits: Vec::new(),
+        table_time: Duration::ZERO,
+        peak_backlog: 0,
+        backlog_seconds: 0.0,
+        revenue: Money::ZERO,
+    };
+    // Some dishes may be sold out before the doors open.
+    service.update_dishes();
+    Ok(service.run())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hosting::Zone;
+
+    #[test]
+    fn the_same_seed_gives_the_same_day() {
+        let report = simulate(&Config::house(7)).unwrap();
+        assert_eq!(simulate(&Config::house(7)).unwrap(), report);
+        assert_ne!(simulate(&Config::house(8)).unwrap(), report);
+
+        assert!(report.parties > 50);
+        assert_eq!(
+            report.seated + report.walked_away + report.turned_away,
+            report.parties
+        );
+        assert!(report.p95_wait >= report.average_wait);
+        assert!(report.table_utilisation > 0.0 && report.table_utilisation <= 1.0);
+        assert_eq!(report.orders_served, report.seated);
+        assert_eq!(report.orders_unserved, 0);
+        assert_eq!(report.out_of_stock, 0);
+        assert!(report.day_length >= HOUR * 11);
+    }
+
+    #[test]
+    fn one_guest_at_a_time() {
+        // A one-dish menu and parties of one: everything but the arrival times is known.
+        let menu = soup_only();
+        let mut floor = FloorPlan::new();
+        floor.add_table(2, Zone::Dining).unwrap();
+        let config = Config {
+            arrivals_per_hour: vec![1.5, 1.5],
+            party_sizes: vec![1],
+            meal: (minutes(10), minutes(10)),
+            floor,
+            cooks: vec![(Station::Stove, 1)],
+            menu,
+            ..Config::house(3)
+        };
+
+        let report = simulate(&config).unwrap();
+        assert!(report.parties > 0);
+        assert_eq!(report.guests as usize, report.parties);
+        assert_eq!(report.turned_away, 0);
+        assert_eq!(
+            report.revenue,
+            Money::from_cents(600) * report.orders_served as u32
+        );
+        // Ordering, five minutes of soup and the meal: 20 minutes at the table each.
+        let used = minutes(20) * report.seated as u32;
+        let expected = used.as_secs_f64() / report.day_length.as_secs_f64();
+        assert!((report.table_utilisation - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn more_cooks_shorter_backlog() {
+        let short_staffed = Config {
+            cooks: vec![
+                (Station::Grill, 1),
+                (Station::Fryer, 1),
+                (Station::Stove, 1),
+                (Station::ColdPrep, 1),
+            ],
+            ..Config::house(11)
+        };
+        let well_staffed = Config {
+            cooks: vec![
+                (Station::Grill, 4),
+                (Station::Fryer, 4),
+                (Station::Stove, 4),
+                (Station::ColdPrep, 4),
+            ],
+            ..Config::house(11)
+        };
+        let short = simulate(&short_staffed).unwrap();
+        let well = simulate(&well_staffed).unwrap();
+        // The same guests come either way.
+        assert_eq!(short.parties, well.parties);
+        assert!(short.peak_backlog > well.peak_backlog);
+        assert!(short.average_backlog > well.average_backlog);
+    }
+
+    #[test]
+    fn nobody_at_the_grill() {
+        let config = Config {
+            cooks: vec![(Station::Stove, 2), (Station::ColdPrep, 1)],
+            ..Config::house(5)
+        };
+        let report = simulate(&config).unwrap();
+        // Burgers and fries never come: those tables stay taken, and the line gives up.
+        assert!(report.orders_unserved > 0);
+        assert!(report.walked_away > 0);
+        assert_eq!(
+            report.seated,
+            report.orders_served + report.orders_unserved + report.out_of_stock
+        );
+    }
+
+    fn soup_only() -> Menu {
+        Menu::from_toml(
+            "[[item]]\nid = \"soup\"\nname = \"Soup\"\ncategory = \"appetizer\"\nprice = 6.00\n",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn the_pantry_runs_out() {
+        use crate::back_of_house::stock::{Recipes, Unit};
+
+        // Vegetables for two soups, and a busy lunch of guests on their own.
+        let config = Config {
+            arrivals_per_hour: vec![8.0, 8.0],
+            party_sizes: vec![1],
+            menu: soup_only(),
+            stock: Stock::new(Recipes::house())
+                .ingredient("vegetables", Unit::Grams, 500, 250)
+                .ingredient("broth", Unit::Millilitres, 10_000, 1_000),
+            ..Config::house(9)
+        };
+        let report = simulate(&config).unwrap();
+        assert_eq!(report.orders_served, 2);
+        assert!(report.out_of_stock > 0);
+        assert_eq!(report.seated, report.orders_served + report.out_of_stock);
+        assert_eq!(report.sold_out, ["soup"]);
+        assert_eq!(report.revenue, Money::from_cents(1_200));
+    }
+
+    #[test]
+    fn nothing_to_order() {
+        use crate::back_of_house::stock::Recipes;
+
+        let no_routing = Config {
+            routing: Routing::new(),
+            ..Config::house(2)
+        };
+        assert_eq!(simulate(&no_routing), Err(SimulationError::NothingToOrder));
+        let no_recipe = Config {
+            menu: soup_only(),
+            stock: Stock::new(Recipes::new()),
+            ..Config::house(2)
+        };
+        assert_eq!(simulate(&no_recipe), Err(SimulationError::NothingToOrder));
+    }
+
+    #[test]
+    fn rates_that_are_not_rates() {
+        for rate in [f64::NAN, f64::INFINITY, -1.0, 1e9] {
+            let config = Config {
+                arrivals_per_hour: vec![2.0, rate],
+                ..Config::house(4)
+            };
+            assert!(matches!(
+                simulate(&config),
+                Err(SimulationError::BadRate { hour: 1, .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn an_hour_with_almost_nobody() {
+        // Gaps far longer than any Duration: nobody comes in those hours, and nothing breaks.
+        let config = Config {
+            arrivals_per_hour: vec![0.0, 1e-300, 1e-300],
+            ..Config::house(4)
+        };
+        let report = simulate(&config).unwrap();
+        assert_eq!(report.parties, 0);
+        assert_eq!(report.day_length, Duration::ZERO);
+    }
+
+    #[test]
+    fn the_report_reads() {
+        let text = simulate(&Config::house(1)).unwrap().to_string();
+        assert!(text.starts_with("seed 1: "));
+        assert!(text.contains("kitchen backlog"));
+        assert!(text.contains("revenue"));
+    }
+}
@@ -0,0 +1,177 @@
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::thread;
+
+use restaurant::server::Server;
+use serde_json::{Value, json};
+
+fn start_server() -> SocketAddr {
+    let server = Server::house("127.0.0.1:0").unwrap();
+    let addr = server.local_addr().unwrap();
+    server.spawn();
+    addr
+}
+
+// Sends the request as it is and reads until the server closes the connection.
+fn raw(addr: SocketAddr, request: &str) -> (u16, Value) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream.write_all(request.as_bytes()).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+    assert!(head.contains("Content-Type: application/json"));
+    (status, serde_json::from_str(body).unwrap())
+}
+
+fn call(addr: SocketAddr, method: &str, path: &str, body: Option<Value>) -> (u16, Value) {
+    let body = body.map(|b| b.to_string()).unwrap_or_default();
+    raw(
+        addr,
+        &format!(
+            "{method} {path} HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\n\r\n{body}",
+            body.len()
+        ),
+    )
+}
+
+#[test]
+fn serves_the_menu() {
+    let addr = start_server();
+    let (status, menu) = call(addr, "GET", "/menu", None);
+    assert_eq!(status, 200);
+    let soup = &menu["items"][1];
+    assert_eq!(soup["id"], "soup");
+    assert_eq!(soup["price"], "6.00");
+    assert_eq!(soup["available"], true);
+}
+
+#[test]
+fn walk_ins_join_the_waitlist() {
+    let addr = start_server();
+    let (status, ada) = call(
+        addr,
+        "POST",
+        "/waitlist",
+        Some(json!({"name": "Ada", "size": 2})),
+    );
+    assert_eq!(status, 201);
+    assert_eq!(ada["position"], 1);
+    let (_, bob) = call(
+        addr,
+        "POST",
+        "/waitlist",
+        Some(json!({"name": "Bob", "size": 4})),
+    );
+    assert_eq!(bob["position"], 2);
+
+    let (status, waitlist) = call(addr, "GET", "/waitlist", None);
+    assert_eq!(status, 200);
+    assert_eq!(waitlist["parties"][0]["name"], "Ada");
+    assert_eq!(waitlist["parties"][1]["party"], bob["party"]);
+    assert_eq!(waitlist["parties"][1]["size"], 4);
+
+    let (status, error) = call(
+        addr,
+        "POST",
+        "/waitlist",
+        Some(json!({"name": "Cy", "size": 0})),
+    );
+    assert_eq!(status, 422);
+    assert_eq!(error["error"], "a party needs at least one guest");
+}
+
+#[test]
+fn an_order_from_the_table_to_the_bill() {
+    let addr = start_server();
+    let (status, order) = call(
+        addr,
+        "POST",
+        "/orders",
+        Some(json!({"table": 3, "items": ["soup", "coffee"]})),
+    );
+    assert_eq!(status, 201);
+    assert_eq!(order["status"], "placed");
+    let path = format!("/orders/{}/status", order["order"]);
+
+    // Nothing on the bill until the food is on the table.
+    assert_eq!(call(addr, "GET", "/tables/3/bill", None).0, 409);
+
+    for step in ["in_kitchen", "ready", "served"] {
+        let (status, order) = call(addr, "POST", &path, Some(json!({ "status": step })));
+        assert_eq!(status, 200);
+        assert_eq!(order["status"], step);
+    }
+    let (status, error) = call(addr, "POST", &path, Some(json!({"status": "cancelled"})));
+    assert_eq!(status, 409);
+    assert!(error["error"].as_str().unwrap().contains("served"));
+
+    let (status, bill) = call(addr, "GET", "/tables/3/bill", None);
+    assert_eq!(status, 200);
+    assert_eq!(bill["lines"].as_array().unwrap().len(), 2);
+    assert_eq!(bill["lines"][0]["order"], order["order"]);
+    assert_eq!(bill["total"], "8.50");
+
+    let (_, paid) = call(addr, "POST", &path, Some(json!({"status": "paid"})));
+    assert_eq!(paid["status"], "paid");
+    let (_, order) = call(addr, "GET", &format!("/orders/{}", order["order"]), None);
+    assert_eq!(order["status"], "paid");
+    assert_eq!(call(addr, "GET", "/tables/3/bill", None).0, 409);
+}
+
+#[test]
+fn bad_requests() {
+    let addr = start_server();
+    assert_eq!(call(addr, "GET", "/orders/42", None).0, 404);
+    assert_eq!(call(addr, "GET", "/tables/99/bill", None).0, 404);
+    assert_eq!(call(addr, "PUT", "/menu", None).0, 405);
+    assert_eq!(
+        call(
+            addr,
+            "POST",
+            "/orders",
+            Some(json!({"table": 1, "items": ["caviar"]}))
+        )
+        .0,
+        422
+    );
+    assert_eq!(
+        call(addr, "POST", "/orders", Some(json!({"table": "one"}))).0,
+        400
+    );
+    let (status, error) = raw(addr, "hello\r\n\r\n");
+    assert_eq!(status, 400);
+    assert!(error["error"].is_string());
+}
+
+#[test]
+fn many_hosts_at_once() {
+    let addr = start_server();
+    let hosts: Vec<_> = (0..16)
+        .map(|n| {
+            thread::spawn(move || {
+                call(
+                    addr,
+                    "POST",
+                    "/waitlist",
+                    Some(json!({"name": format!("Party {n}"), "size": 2})),
+                )
+            })
+        })
+        .collect();
+    let mut ids: Vec<u64> = hosts
+        .into_iter()
+        .map(|host| {
+            let (status, party) = host.join().unwrap();
+            assert_eq!(status, 201);
+            party["party"].as_u64().unwrap()
+        })
+        .collect();
+    ids.sort();
+    assert_eq!(ids, (1..=16).collect::<Vec<_>>());
+
+    let (_, waitlist) = call(addr, "GET", "/waitlist", None);
+    assert_eq!(waitlist["parties"].as_array().unwrap().len(), 16);
+}